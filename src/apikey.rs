@@ -0,0 +1,112 @@
+use crate::commands::Commands;
+use crate::{header_value, Type};
+use ring::digest::{digest, SHA256};
+use std::collections::HashMap;
+use std::fs;
+
+/// Header carrying the key unless the route sets another one
+const DEFAULT_HEADER: &str = "X-API-Key";
+
+/// Columns of the table rows holding the key
+const KEY_COLUMNS: [&str; 4] = ["key", "api_key", "api-key", "token"];
+
+/// Prefix of the key stored as its SHA-256 digest like "sha256:9f86d0..."
+const DIGEST_PREFIX: &str = "sha256:";
+
+/// Metadata pushed for the handler (the row of the key, the second item of the pair,
+/// or the key) by the SHA-256 digest of the key
+pub struct ApiKeys(HashMap<String, Type>);
+
+/// Get the SHA-256 digest of the key in hexadecimal.
+/// The keys are random enough that the fast hash is as safe as the password hashes
+fn hex_digest(key: &str) -> String {
+    digest(&SHA256, key.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|x| format!("{x:02x}"))
+        .collect()
+}
+
+impl ApiKeys {
+    /// Add the key stored as itself or as its digest
+    fn add(&mut self, key: &str, meta: Type) {
+        let digest = match key.strip_prefix(DIGEST_PREFIX) {
+            Some(digest) => digest.trim().to_ascii_lowercase(),
+            None => hex_digest(key),
+        };
+        self.0.insert(digest, meta);
+    }
+}
+
+/// Get the keys from the value pushed by the keys code: the list of the keys,
+/// the pairs of the key and its metadata, the table rows with the key column,
+/// or the path of the file listing a key per line
+pub fn load(value: Type) -> ApiKeys {
+    let mut keys = ApiKeys(HashMap::new());
+    if let Type::String(path) = &value {
+        match fs::read_to_string(path) {
+            Ok(text) => text
+                .lines()
+                .map(|x| x.trim())
+                .filter(|x| !x.is_empty() && !x.starts_with('#'))
+                .for_each(|x| keys.add(x, Type::String(x.into()))),
+            Err(e) => println!("Error! API key file \"{path}\": {e}"),
+        }
+        return keys;
+    }
+
+    for i in value.get_list() {
+        match &i {
+            Type::Object(_, row) => {
+                if let Some(key) = KEY_COLUMNS.iter().find_map(|x| row.get(*x)) {
+                    keys.add(&key.get_string(), i.clone());
+                }
+            }
+            Type::List(pair) => {
+                if let Some(key) = pair.first() {
+                    keys.add(
+                        &key.get_string(),
+                        pair.get(1).cloned().unwrap_or(Type::Null),
+                    );
+                }
+            }
+            _ => keys.add(&i.get_string(), i.clone()),
+        }
+    }
+    keys
+}
+
+/// Get the key sent by the client in the header of the route, or in the default header
+/// or `Authorization: Bearer` unless the route sets it
+pub fn presented(request_str: &str, header: Option<&str>) -> Option<String> {
+    let value = |name: &str| header_value(request_str, name).map(|x| x.to_string());
+    match header {
+        Some(header) => value(header),
+        None => value("Authorization")
+            .and_then(|x| {
+                let (scheme, token) = x.split_once(' ')?;
+                scheme
+                    .eq_ignore_ascii_case("Bearer")
+                    .then(|| token.trim().to_string())
+            })
+            .or_else(|| value(DEFAULT_HEADER)),
+    }
+    .filter(|x| !x.is_empty())
+}
+
+/// Get the metadata of the key matching the sent one by its digest, hashing it only once
+/// however many keys there are (looking up the digest doesn't leak the key by the time)
+pub fn find(keys: &ApiKeys, sent: &str) -> Option<Type> {
+    keys.0.get(&hex_digest(sent)).cloned()
+}
+
+/// Register the commands of the API keys
+pub fn register_commands(commands: &mut Commands) {
+    // Get the digest of the key to store it instead of the key like "sha256:9f86d0..."
+    commands.add("api-key-digest", |executor| {
+        let key = executor.pop_stack().get_string();
+        let digest = format!("{DIGEST_PREFIX}{}", hex_digest(&key));
+        executor.stack.push(Type::String(digest.into()));
+        Ok(())
+    });
+}
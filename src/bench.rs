@@ -0,0 +1,200 @@
+use crate::{config, Executor, Mode, Type};
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How long to wait for the server to start listening
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Settings of the benchmark
+pub struct Options {
+    pub iterations: usize,     // Number of the measured runs of each target
+    pub warmup: usize,         // Number of the runs before measuring
+    pub codes: Vec<String>,    // Code blocks evaluated after the program
+    pub requests: Vec<String>, // Requests like "GET /" to the server of the program
+    pub body: String,          // Body of the requests
+}
+
+/// Measured durations of the target
+struct Measurement {
+    target: String,       // Name of the target
+    times: Vec<Duration>, // Duration of each run
+    total: Duration,      // Time spent for all the runs
+    failed: usize,        // Number of the runs that failed
+}
+
+/// Run the target after the warmup, and measure each run
+/// (the run returns whether it succeeded)
+fn measure(target: &str, options: &Options, mut run: impl FnMut() -> bool) -> Measurement {
+    for _ in 0..options.warmup {
+        run();
+    }
+    let mut times = Vec::with_capacity(options.iterations);
+    let mut failed = 0;
+    let started = Instant::now();
+    for _ in 0..options.iterations {
+        let start = Instant::now();
+        if !run() {
+            failed += 1;
+        }
+        times.push(start.elapsed());
+    }
+    Measurement {
+        target: target.to_string(),
+        total: started.elapsed(),
+        times,
+        failed,
+    }
+}
+
+/// Whether the executor has no error value on the stack
+fn succeeded(executor: &Executor) -> bool {
+    !executor.stack.iter().any(|x| matches!(x, Type::Error(_)))
+}
+
+/// Evaluate the code on the executor prepared by the program
+fn bench_code(prepared: &Executor, target: &str, code: &str, options: &Options) -> Measurement {
+    measure(target, options, || {
+        let mut executor = prepared.clone();
+        executor.evaluate_program(code.to_string());
+        succeeded(&executor)
+    })
+}
+
+/// Send the request and read the response, returning whether the status is successful
+fn send(address: &str, request: &[u8]) -> io::Result<bool> {
+    let mut stream = TcpStream::connect(address)?;
+    stream.write_all(request)?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    Ok(response.starts_with(b"HTTP/1.1 2"))
+}
+
+/// Wait for the server of the program to start listening by sending the request
+fn wait(address: &str, request: &[u8], program: &JoinHandle<()>) -> Result<(), String> {
+    let started = Instant::now();
+    while send(address, request).is_err() {
+        if program.is_finished() || started.elapsed() > STARTUP_TIMEOUT {
+            return Err("server is not started in the program".to_string());
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    Ok(())
+}
+
+/// Start the server of the program on a free port, and send the requests to it
+fn bench_requests(code: String, options: &Options, mode: Mode) -> Result<Vec<Measurement>, String> {
+    let port = TcpListener::bind("127.0.0.1:0")
+        .and_then(|x| x.local_addr())
+        .map_err(|e| e.to_string())?
+        .port();
+    config::set_listen(Some("127.0.0.1".to_string()), Some(port));
+    let program = thread::spawn(move || Executor::new(mode).evaluate_program(code));
+
+    let address = format!("127.0.0.1:{port}");
+    let requests: Vec<(String, String)> = options
+        .requests
+        .iter()
+        .map(|target| {
+            let (method, path) = target.trim().split_once(' ').unwrap_or(("GET", target));
+            let request = format!(
+                "{method} {} HTTP/1.1\r\nHost: {address}\r\nContent-Length: {}\r\n\r\n{}",
+                path.trim(),
+                options.body.len(),
+                options.body
+            );
+            (format!("{method} {}", path.trim()), request)
+        })
+        .collect();
+    if let Some((_, request)) = requests.first() {
+        wait(&address, request.as_bytes(), &program)?;
+    }
+
+    Ok(requests
+        .iter()
+        .map(|(target, request)| {
+            measure(target, options, || {
+                send(&address, request.as_bytes()).unwrap_or(false)
+            })
+        })
+        .collect())
+}
+
+/// Get the duration at the percentile of the sorted durations
+fn percentile(times: &[Duration], percent: f64) -> Duration {
+    let index = ((times.len() - 1) as f64 * percent / 100.0).round() as usize;
+    times[index]
+}
+
+/// Format the duration in milliseconds
+fn millis(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Print the table of the measurements
+fn report(measurements: Vec<Measurement>) {
+    let mut rows = vec![[
+        "TARGET", "RUNS", "FAILED", "OPS/S", "MEAN", "P50", "P90", "P99", "MAX",
+    ]
+    .map(|x| x.to_string())];
+    for mut measurement in measurements {
+        measurement.times.sort();
+        let times = &measurement.times;
+        let sum: Duration = times.iter().sum();
+        rows.push([
+            measurement.target,
+            times.len().to_string(),
+            measurement.failed.to_string(),
+            format!(
+                "{:.1}",
+                times.len() as f64 / measurement.total.as_secs_f64()
+            ),
+            millis(sum / times.len() as u32),
+            millis(percentile(times, 50.0)),
+            millis(percentile(times, 90.0)),
+            millis(percentile(times, 99.0)),
+            millis(times.last().copied().unwrap_or_default()),
+        ]);
+    }
+
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|i| rows.iter().map(|x| x[i].chars().count()).max().unwrap_or(0))
+        .collect();
+    for row in &rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            // Align the target to the left and the numbers to the right
+            .map(|(i, (cell, width))| match i {
+                0 => format!("{cell:width$}"),
+                _ => format!("{cell:>width$}"),
+            })
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
+
+/// Measure the program itself, the code blocks evaluated after it,
+/// or the requests to the server started by it
+pub fn run(name: &str, code: String, options: &Options, mode: Mode) -> Result<(), String> {
+    if options.iterations == 0 {
+        return Err("number of the iterations must be positive".to_string());
+    }
+    let measurements = if !options.requests.is_empty() {
+        bench_requests(code, options, mode)?
+    } else if !options.codes.is_empty() {
+        let mut prepared = Executor::new(mode);
+        prepared.evaluate_program(code);
+        options
+            .codes
+            .iter()
+            .map(|x| bench_code(&prepared, x, x, options))
+            .collect()
+    } else {
+        vec![bench_code(&Executor::new(mode), name, &code, options)]
+    };
+    report(measurements);
+    Ok(())
+}
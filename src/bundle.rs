@@ -0,0 +1,149 @@
+use crate::{config, get_file_contents, TEMPORARIES};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::{json, Value};
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Extension of the bundle file
+pub const EXTENSION: &str = "stkb";
+
+/// Manifest in the bundle naming the main script
+const MANIFEST: &str = "bundle.json";
+
+/// Whether the file is a bundle (gzip-compressed archive)
+pub fn is_bundle(path: &Path) -> bool {
+    let mut magic = [0; 2];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok()
+        && magic == [0x1f, 0x8b]
+}
+
+/// Get the path in the bundle (relative paths are kept, others are put on the root)
+fn entry_name(path: &Path) -> PathBuf {
+    let relative = path
+        .components()
+        .all(|x| matches!(x, Component::Normal(_) | Component::CurDir));
+    if relative {
+        path.components()
+            .filter(|x| matches!(x, Component::Normal(_)))
+            .collect()
+    } else {
+        PathBuf::from(path.file_name().unwrap_or(path.as_os_str()))
+    }
+}
+
+/// Get the assets named by the configuration file (template directory and bundle includes)
+fn configured_assets() -> Result<Vec<String>, String> {
+    if !Path::new(config::DEFAULT_FILE).exists() {
+        return Ok(Vec::new());
+    }
+    let text = fs::read_to_string(config::DEFAULT_FILE).map_err(|e| e.to_string())?;
+    let file = toml::from_str::<Value>(&text).map_err(|e| e.to_string())?;
+
+    let mut assets = vec![config::DEFAULT_FILE.to_string()];
+    if let Some(dir) = file
+        .pointer("/server/template-dir")
+        .and_then(|x| x.as_str())
+    {
+        assets.push(dir.to_string());
+    }
+    if let Some(Value::Array(paths)) = file.pointer("/bundle/include") {
+        assets.extend(
+            paths
+                .iter()
+                .filter_map(|x| x.as_str().map(|x| x.to_string())),
+        );
+    }
+    Ok(assets)
+}
+
+/// Pack the script and the assets into the bundle, and return its path
+pub fn pack(script: &str, includes: &[&str], output: Option<&str>) -> Result<String, String> {
+    if !Path::new(script).is_file() {
+        return Err(format!("{script}: no such file"));
+    }
+    let output = match output {
+        Some(output) => output.to_string(),
+        None => Path::new(script)
+            .with_extension(EXTENSION)
+            .to_string_lossy()
+            .to_string(),
+    };
+    let file = File::create(&output).map_err(|e| format!("{output}: {e}"))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let main = entry_name(Path::new(script));
+    let mut paths = vec![script.to_string()];
+    for path in includes
+        .iter()
+        .map(|x| x.to_string())
+        .chain(configured_assets()?)
+    {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    let mut files = Vec::new();
+    for path in &paths {
+        let name = entry_name(Path::new(path));
+        let result = if Path::new(path).is_dir() {
+            builder.append_dir_all(&name, path)
+        } else {
+            builder.append_path_with_name(path, &name)
+        };
+        result.map_err(|e| format!("{path}: {e}"))?;
+        files.push(name.to_string_lossy().to_string());
+    }
+
+    // Write the manifest naming the main script
+    let manifest = json!({ "main": main.to_string_lossy(), "files": files }).to_string();
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST, manifest.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| e.to_string())?;
+    Ok(output)
+}
+
+/// Unpack the bundle into a temporary directory and move into it,
+/// and return the name and the code of the main script
+pub fn unpack(path: &Path) -> Result<(String, String), String> {
+    let file = File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let dir = tempfile::Builder::new()
+        .prefix("stack-bundle-")
+        .tempdir()
+        .map(|dir| dir.keep())
+        .map_err(|e| e.to_string())?;
+    TEMPORARIES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(dir.clone());
+
+    tar::Archive::new(GzDecoder::new(file))
+        .unpack(&dir)
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    let manifest = get_file_contents(&dir.join(MANIFEST))
+        .map_err(|e| format!("{}: {MANIFEST}: {e}", path.display()))?;
+    let manifest: Value = serde_json::from_str(&manifest).map_err(|e| e.to_string())?;
+    let main = manifest
+        .get("main")
+        .and_then(|x| x.as_str())
+        .ok_or(format!("{}: main script is not specified", path.display()))?;
+
+    env::set_current_dir(&dir).map_err(|e| e.to_string())?;
+    let code = get_file_contents(Path::new(main)).map_err(|e| format!("{main}: {e}"))?;
+    Ok((main.to_string(), code))
+}
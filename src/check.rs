@@ -0,0 +1,67 @@
+use crate::lexer::{self, Delimiter, Span};
+
+/// Characters that can follow the backslash
+const ESCAPES: [char; 10] = ['n', 't', 'r', '\\', '(', ')', '[', ']', '#', ' '];
+
+/// Syntax problem found in the program
+pub struct Problem {
+    pub line: usize,     // Line number from 1
+    pub column: usize,   // Column number from 1
+    pub message: String, // Description of the problem
+}
+
+/// Validate the program without executing, by the delimiters as the tokenizer reads them
+pub fn check(code: &str) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut strings: Vec<Span> = Vec::new(); // Positions of opened strings
+    let mut lists: Vec<Span> = Vec::new(); // Positions of opened lists
+    let mut comment: Option<Span> = None; // Position of the opened comment
+
+    let problem = |span: Span, message: String| Problem {
+        line: span.line,
+        column: span.column,
+        message,
+    };
+
+    for (delimiter, span) in lexer::delimiters(code) {
+        match delimiter {
+            Delimiter::Comment => {
+                comment = match comment {
+                    Some(_) => None,
+                    None => Some(span),
+                }
+            }
+            Delimiter::Escape(None) => {
+                problems.push(problem(span, "escape sequence is not finished".to_string()));
+            }
+            // The line break after the backslash is read as an escaped space
+            Delimiter::Escape(Some(c))
+                if comment.is_none() && !ESCAPES.contains(&c) && !c.is_whitespace() =>
+            {
+                problems.push(problem(span, format!("unknown escape sequence \"\\{c}\"")));
+            }
+            Delimiter::Escape(_) => {}
+            Delimiter::OpenString => strings.push(span),
+            Delimiter::CloseString if strings.pop().is_none() => {
+                problems.push(problem(span, "unexpected \")\"".to_string()));
+            }
+            Delimiter::OpenList => lists.push(span),
+            Delimiter::CloseList if lists.pop().is_none() => {
+                problems.push(problem(span, "unexpected \"]\"".to_string()));
+            }
+            Delimiter::CloseString | Delimiter::CloseList => {}
+        }
+    }
+
+    if let Some(start) = comment {
+        problems.push(problem(start, "comment is not terminated".to_string()));
+    }
+    for start in strings {
+        problems.push(problem(start, "string \"(\" is not closed".to_string()));
+    }
+    for start in lists {
+        problems.push(problem(start, "list \"[\" is not closed".to_string()));
+    }
+    problems.sort_by_key(|x| (x.line, x.column));
+    problems
+}
@@ -0,0 +1,169 @@
+use crate::{
+    apikey, files, form, i18n, lockout, password, plugin, request, schema, stats, tcp, telemetry,
+    Executor, StackError, Type,
+};
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// Native command implemented in Rust
+pub type Command = Arc<dyn Fn(&mut Executor) -> Result<(), StackError> + Send + Sync>;
+
+/// Native commands by the name
+#[derive(Default)]
+pub struct Commands(HashMap<String, Command>);
+
+impl Commands {
+    /// Add the command (replacing the command of the same name)
+    pub fn add(
+        &mut self,
+        name: &str,
+        command: impl Fn(&mut Executor) -> Result<(), StackError> + Send + Sync + 'static,
+    ) {
+        self.0.insert(name.to_string(), Arc::new(command));
+    }
+}
+
+/// Registry of the native commands, beginning with the built-in ones
+static COMMANDS: LazyLock<RwLock<Commands>> = LazyLock::new(|| {
+    let mut commands = Commands::default();
+    crate::register_builtins(&mut commands);
+    calculation(&mut commands);
+    apikey::register_commands(&mut commands);
+    files::register_commands(&mut commands);
+    form::register_commands(&mut commands);
+    i18n::register_commands(&mut commands);
+    lockout::register_commands(&mut commands);
+    password::register_commands(&mut commands);
+    request::register_commands(&mut commands);
+    schema::register_commands(&mut commands);
+    stats::register_commands(&mut commands);
+    tcp::register_commands(&mut commands);
+    telemetry::register_commands(&mut commands);
+    plugin::register_commands(&mut commands);
+    RwLock::new(commands)
+});
+
+/// Register the native command, unless the name is already taken
+/// by the built-in command or the registered one
+pub fn register_command(
+    name: &str,
+    command: impl Fn(&mut Executor) -> Result<(), StackError> + Send + Sync + 'static,
+) -> Result<(), StackError> {
+    let mut commands = COMMANDS.write().unwrap_or_else(|e| e.into_inner());
+    if commands.0.contains_key(name) {
+        return Err(StackError::new(
+            "command",
+            format!("\"{name}\" is already registered"),
+        ));
+    }
+    commands.add(name, command);
+    Ok(())
+}
+
+/// Get the native command by the name
+pub fn get(name: &str) -> Option<Command> {
+    COMMANDS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .0
+        .get(name)
+        .cloned()
+}
+
+/// Register the binary operation of the numbers
+fn number_operation(commands: &mut Commands, name: &str, operation: fn(f64, f64) -> Type) {
+    commands.add(name, move |executor| {
+        let b = executor.pop_stack().get_number();
+        let a = executor.pop_stack().get_number();
+        executor.stack.push(operation(a, b));
+        Ok(())
+    });
+}
+
+/// Register the function of a number
+fn number_function(commands: &mut Commands, name: &str, function: fn(f64) -> f64) {
+    commands.add(name, move |executor| {
+        let number = executor.pop_stack().get_number();
+        executor.stack.push(Type::Number(function(number)));
+        Ok(())
+    });
+}
+
+/// Commands of calculation
+fn calculation(commands: &mut Commands) {
+    // Arithmetic operations
+    number_operation(commands, "add", |a, b| Type::Number(a + b));
+    number_operation(commands, "sub", |a, b| Type::Number(a - b));
+    number_operation(commands, "mul", |a, b| Type::Number(a * b));
+    number_operation(commands, "div", |a, b| Type::Number(a / b));
+    number_operation(commands, "mod", |a, b| Type::Number(a % b));
+    number_operation(commands, "pow", |a, b| Type::Number(a.powf(b)));
+
+    // Judge is it less
+    number_operation(commands, "less", |a, b| Type::Bool(a < b));
+
+    // Rounding off and trigonometric functions
+    number_function(commands, "round", f64::round);
+    number_function(commands, "sin", f64::sin);
+    number_function(commands, "cos", f64::cos);
+    number_function(commands, "tan", f64::tan);
+
+    // Logical operations of AND
+    commands.add("and", |executor| {
+        let b = executor.pop_stack().get_bool();
+        let a = executor.pop_stack().get_bool();
+        executor.stack.push(Type::Bool(a && b));
+        Ok(())
+    });
+
+    // Logical operations of OR
+    commands.add("or", |executor| {
+        let b = executor.pop_stack().get_bool();
+        let a = executor.pop_stack().get_bool();
+        executor.stack.push(Type::Bool(a || b));
+        Ok(())
+    });
+
+    // Logical operations of NOT
+    commands.add("not", |executor| {
+        let b = executor.pop_stack().get_bool();
+        executor.stack.push(Type::Bool(!b));
+        Ok(())
+    });
+
+    // Judge is it equal
+    commands.add("equal", |executor| {
+        let b = executor.pop_stack().get_string();
+        let a = executor.pop_stack().get_string();
+        executor.stack.push(Type::Bool(a == b));
+        Ok(())
+    });
+
+    // Judge is it equal by comparing type and structure
+    commands.add("deep-equal", |executor| {
+        let b = executor.pop_stack();
+        let a = executor.pop_stack();
+        executor.stack.push(Type::Bool(a == b));
+        Ok(())
+    });
+
+    // Get random value from list
+    commands.add("rand", |executor| {
+        let list = executor.pop_stack().into_list();
+        let result = match list.choose(&mut rand::thread_rng()) {
+            Some(i) => i.to_owned(),
+            None => Type::List(list.into()),
+        };
+        executor.stack.push(result);
+        Ok(())
+    });
+
+    // Shuffle list by random
+    commands.add("shuffle", |executor| {
+        let mut list = executor.pop_stack().into_list();
+        list.shuffle(&mut rand::thread_rng());
+        executor.stack.push(Type::List(list.into()));
+        Ok(())
+    });
+}
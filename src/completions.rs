@@ -0,0 +1,67 @@
+use clap::App;
+use clap_complete::Shell;
+
+/// Name of the command to complete
+const NAME: &str = "stack-server";
+
+/// Bash function completing the program files of the subcommands taking a program
+const BASH_SCRIPTS: &str = r#"
+_stack-server_scripts() {
+    _stack-server "$@"
+    local i cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}" reply=()
+    case "${prev}" in
+        -e|--eval|--env-file|--config|--bind|--port|--watch-path|-o|--output)
+            return 0
+            ;;
+    esac
+    if [[ ${cur} == -* ]]; then
+        return 0
+    fi
+    if [[ ${COMP_CWORD} -eq 1 ]]; then
+        # Subcommands without the placeholders of the arguments
+        for i in "${COMPREPLY[@]}"; do
+            [[ ${i} == "<"* ]] || reply+=("${i}")
+        done
+    elif [[ " run serve check routes bench bundle " != *" ${COMP_WORDS[1]} "* ]]; then
+        return 0
+    fi
+    compopt -o filenames 2>/dev/null
+    COMPREPLY=("${reply[@]}" $(compgen -f -X '!*.stk' -- "${cur}") $(compgen -d -- "${cur}"))
+}
+
+complete -F _stack-server_scripts -o bashdefault -o default stack-server
+"#;
+
+/// Fish rules completing the program files
+const FISH_SCRIPTS: &str = r#"
+complete -c stack-server -n "__fish_use_subcommand" -k -a "(__fish_complete_suffix .stk)"
+complete -c stack-server -n "__fish_seen_subcommand_from run serve check routes bundle" -k -f -a "(__fish_complete_suffix .stk)"
+"#;
+
+/// Print the completion script of the shell, completing .stk files as the program
+pub fn print(shell: Shell, app: &mut App) {
+    let mut buffer = Vec::new();
+    clap_complete::generate(shell, app, NAME, &mut buffer);
+    let mut script = String::from_utf8_lossy(&buffer).to_string();
+
+    match shell {
+        Shell::Bash => script += BASH_SCRIPTS,
+        Shell::Fish => script += FISH_SCRIPTS,
+        Shell::Zsh => {
+            script = script
+                .lines()
+                .map(|line| {
+                    if line.contains(":script -- ") {
+                        line.replace(":_files'", ":_files -g \"*.stk\"'")
+                    } else {
+                        line.to_string()
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+                + "\n"
+        }
+        _ => {}
+    }
+    print!("{script}");
+}
@@ -0,0 +1,199 @@
+use crate::{jobs, logger, Type};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::{LazyLock, RwLock};
+
+/// Listen address of the server when the script doesn't specify it
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8000";
+
+/// Configuration file loaded from the working directory if it exists
+pub const DEFAULT_FILE: &str = "stack-server.toml";
+
+/// Settings given at the launch that override the script
+#[derive(Default)]
+struct Settings {
+    bind: Option<String>,   // Host to listen on
+    port: Option<u16>,      // Port to listen on
+    file: Value,            // Parsed configuration file (null if it is not loaded)
+    arguments: Vec<String>, // Arguments of the program
+    strict: bool,           // Whether stack underflow is an error in the REPL too
+}
+
+static SETTINGS: LazyLock<RwLock<Settings>> = LazyLock::new(|| RwLock::new(Settings::default()));
+
+/// Set the host and port overriding the listen address (None keeps the current one)
+pub fn set_listen(bind: Option<String>, port: Option<u16>) {
+    let mut settings = SETTINGS.write().unwrap_or_else(|e| e.into_inner());
+    if bind.is_some() {
+        settings.bind = bind;
+    }
+    if port.is_some() {
+        settings.port = port;
+    }
+}
+
+/// Set the arguments of the program
+pub fn set_arguments(arguments: Vec<String>) {
+    SETTINGS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .arguments = arguments;
+}
+
+/// Get the arguments of the program
+pub fn arguments() -> Vec<String> {
+    let settings = SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+    settings.arguments.clone()
+}
+
+/// Make stack underflow an error in the REPL too
+pub fn set_strict(strict: bool) {
+    SETTINGS.write().unwrap_or_else(|e| e.into_inner()).strict = strict;
+}
+
+/// Whether stack underflow is an error in the REPL too
+pub fn strict() -> bool {
+    SETTINGS.read().unwrap_or_else(|e| e.into_inner()).strict
+}
+
+/// Load the configuration file (the default one is optional), and apply its settings
+pub fn load(path: Option<&str>) -> Result<(), String> {
+    let path = match path {
+        Some(path) => path,
+        None if Path::new(DEFAULT_FILE).exists() => DEFAULT_FILE,
+        None => return Ok(()),
+    };
+    let text = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let file = toml::from_str::<Value>(&text).map_err(|e| format!("{path}: {e}"))?;
+
+    // Set environment variables
+    if let Some(Value::Object(variables)) = file.get("env") {
+        for (name, value) in variables {
+            match value {
+                Value::String(value) => env::set_var(name, value),
+                other => env::set_var(name, other.to_string()),
+            }
+        }
+    }
+
+    // Configure the log file and the job workers
+    if let Some(log) = file.get("log").filter(|x| x.get("path").is_some()) {
+        logger::configure(&Type::from_json(log))?;
+    }
+    if let Some(jobs) = file.get("jobs") {
+        jobs::configure(&Type::from_json(jobs));
+    }
+
+    // Override the listen address of the script
+    if let Some(address) = file.pointer("/server/address").and_then(|x| x.as_str()) {
+        let (host, port) = address.rsplit_once(':').unwrap_or((address, "8000"));
+        let port = port
+            .parse()
+            .map_err(|_| format!("{path}: invalid port \"{port}\""))?;
+        set_listen(Some(host.to_string()), Some(port));
+    }
+
+    SETTINGS.write().unwrap_or_else(|e| e.into_inner()).file = file;
+    Ok(())
+}
+
+/// Get the log level set by the configuration file
+pub fn log_level() -> Option<String> {
+    let settings = SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+    let level = settings.file.pointer("/log/level")?.as_str()?;
+    Some(level.to_string())
+}
+
+/// Get the paths to watch in the watch mode from the configuration file
+pub fn watch_paths() -> Vec<String> {
+    let settings = SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+    match settings.file.pointer("/watch/paths") {
+        Some(Value::Array(paths)) => paths
+            .iter()
+            .filter_map(|x| x.as_str().map(|x| x.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Fill the server options missing in the script by the configuration file
+pub fn server_option(option: Type) -> Type {
+    let settings = SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+    let Some(Value::Object(defaults)) = settings.file.get("server") else {
+        return option;
+    };
+    let defaults = defaults
+        .iter()
+        .map(|(key, value)| (key.to_owned(), Type::from_json(value)));
+    match option {
+        Type::Object(name, mut value) => {
+            for (key, default) in defaults {
+                value.entry(key).or_insert(default);
+            }
+            Type::Object(name, value)
+        }
+        address => {
+            let mut value: HashMap<String, Type> = defaults.collect();
+            value.insert(
+                "address".to_string(),
+                Type::String(address.get_string().into()),
+            );
+            Type::Object("app".to_string(), value)
+        }
+    }
+}
+
+/// Get the whole configuration file as an object (empty if it is not loaded)
+pub fn file() -> Type {
+    let settings = SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+    match &settings.file {
+        Value::Null => Type::Object("config".to_string(), HashMap::new()),
+        file => match Type::from_json(file) {
+            Type::Object(_, value) => Type::Object("config".to_string(), value),
+            other => other,
+        },
+    }
+}
+
+/// Apply the overriding host and port to the listen address of the script
+pub fn address(address: &str) -> String {
+    let settings = SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+    let (host, port) = address.rsplit_once(':').unwrap_or((address, "8000"));
+    format!(
+        "{}:{}",
+        settings.bind.as_deref().unwrap_or(host),
+        settings
+            .port
+            .map(|x| x.to_string())
+            .unwrap_or(port.to_string())
+    )
+}
+
+/// Get the overriding settings as an object (null if it is not given)
+pub fn server_config() -> Type {
+    let settings = SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+    let bind = settings
+        .bind
+        .clone()
+        .map(|x| Type::String(x.into()))
+        .unwrap_or(Type::Null);
+    let port = settings
+        .port
+        .map(|x| Type::Number(x as f64))
+        .unwrap_or(Type::Null);
+    drop(settings);
+    Type::Object(
+        "server-config".to_string(),
+        HashMap::from([
+            ("bind".to_string(), bind),
+            ("port".to_string(), port),
+            (
+                "address".to_string(),
+                Type::String(address(DEFAULT_ADDRESS).into()),
+            ),
+        ]),
+    )
+}
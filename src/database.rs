@@ -0,0 +1,392 @@
+use crate::Type;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, CachedStatement, Connection, StatementStatus};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Count of prepared statements cached per connection
+const STATEMENT_CACHE: usize = 64;
+
+/// Count of statements reused from the cache
+static STATEMENT_HITS: AtomicU64 = AtomicU64::new(0);
+/// Count of statements parsed because they weren't cached
+static STATEMENT_MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// Connection with its cache of prepared statements
+struct Session {
+    connection: Connection, // Connection of the database
+}
+
+/// Opened connections and the last identifier
+type Connections = (usize, HashMap<usize, Arc<Mutex<Session>>>);
+
+static CONNECTIONS: LazyLock<Mutex<Connections>> =
+    LazyLock::new(|| Mutex::new((0, HashMap::new())));
+
+/// Pool of connections shared by the server's handlers
+struct Pool {
+    path: String,                       // Path of the database
+    size: usize,                        // Maximum count of connections
+    busy_timeout: f64,                  // Seconds to wait for the locked database
+    wal: bool,                          // Use write-ahead logging
+    idle: Mutex<(usize, Vec<Session>)>, // Count of created connections and idle ones
+    available: Condvar,                 // Notify when a connection is returned
+}
+
+static POOL: Mutex<Option<Arc<Pool>>> = Mutex::new(None);
+
+thread_local! {
+    /// Pooled connection pinned to the thread during transaction
+    static PINNED: RefCell<Option<Session>> = const { RefCell::new(None) };
+}
+
+/// Lock the connection registry
+fn connections() -> MutexGuard<'static, Connections> {
+    CONNECTIONS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Session {
+    fn new(connection: Connection) -> Session {
+        connection.set_prepared_statement_cache_capacity(STATEMENT_CACHE);
+        Session { connection }
+    }
+
+    /// Prepare the statement, reusing the cached one by the SQL text
+    fn prepare(&self, sql_query: &str) -> rusqlite::Result<CachedStatement<'_>> {
+        let statement = self.connection.prepare_cached(sql_query)?;
+        // Statement that has been run before is the one taken from the cache
+        match statement.get_status(StatementStatus::Run) {
+            0 => STATEMENT_MISSES.fetch_add(1, Ordering::Relaxed),
+            _ => STATEMENT_HITS.fetch_add(1, Ordering::Relaxed),
+        };
+        Ok(statement)
+    }
+}
+
+impl Pool {
+    /// Take a connection, waiting until one is returned if the pool is full
+    fn checkout(&self) -> Option<Session> {
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if let Some(connection) = idle.1.pop() {
+                return Some(connection);
+            }
+            if idle.0 < self.size {
+                let connection = Connection::open(&self.path).ok()?;
+                connection
+                    .busy_timeout(Duration::from_secs_f64(self.busy_timeout))
+                    .ok()?;
+                if self.wal {
+                    connection
+                        .query_row("PRAGMA journal_mode=WAL", [], |_| Ok(()))
+                        .ok()?;
+                }
+                idle.0 += 1;
+                return Some(Session::new(connection));
+            }
+            idle = self.available.wait(idle).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Return the connection into the pool
+    fn checkin(&self, connection: Session) {
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        idle.1.push(connection);
+        self.available.notify_one();
+    }
+}
+
+/// Get the configured pool
+fn pool() -> Option<Arc<Pool>> {
+    POOL.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Configure the pool used by the server's handlers
+pub fn configure_pool(path: &str, size: usize, busy_timeout: f64, wal: bool) {
+    *POOL.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(Pool {
+        path: path.to_string(),
+        size: size.max(1),
+        busy_timeout,
+        wal,
+        idle: Mutex::new((0, Vec::new())),
+        available: Condvar::new(),
+    }));
+}
+
+/// Get handle object of the pool
+pub fn pool_handle() -> Type {
+    match pool() {
+        Some(pool) => Type::Object(
+            "connection".to_string(),
+            HashMap::from([
+                ("pool".to_string(), Type::Bool(true)),
+                ("path".to_string(), Type::String(pool.path.clone().into())),
+            ]),
+        ),
+        None => Type::error("sql-connect"),
+    }
+}
+
+/// Whether the handle refers to the pool
+fn is_pool(handle: &Type) -> bool {
+    let (_, object) = handle.get_object();
+    object.get("pool").map(|x| x.get_bool()).unwrap_or(false)
+}
+
+/// Use connection of the handle object (drawing from the pool if needed)
+fn with_connection<R>(handle: &Type, process: impl FnOnce(&Session) -> R) -> Option<R> {
+    if is_pool(handle) {
+        // Use the connection pinned by the transaction
+        if let Some(connection) = PINNED.with(|pinned| pinned.borrow_mut().take()) {
+            let result = process(&connection);
+            PINNED.with(|pinned| *pinned.borrow_mut() = Some(connection));
+            return Some(result);
+        }
+        let pool = pool()?;
+        let connection = pool.checkout()?;
+        let result = process(&connection);
+        pool.checkin(connection);
+        return Some(result);
+    }
+
+    let (_, object) = handle.get_object();
+    let id = object.get("id")?.get_number() as usize;
+    let connection = connections().1.get(&id).cloned()?;
+    let connection = connection.lock().unwrap_or_else(|e| e.into_inner());
+    Some(process(&connection))
+}
+
+/// Convert value into SQL parameter
+fn to_sql(value: &Type) -> SqlValue {
+    match value {
+        Type::Number(n) if n.fract() == 0.0 && n.abs() < i64::MAX as f64 => {
+            SqlValue::Integer(*n as i64)
+        }
+        Type::Number(n) => SqlValue::Real(*n),
+        Type::Bool(b) => SqlValue::Integer(*b as i64),
+        Type::Binary(b) => SqlValue::Blob(b.to_vec()),
+        Type::Null => SqlValue::Null,
+        other => SqlValue::Text(other.get_string()),
+    }
+}
+
+/// Open connection and return its handle object
+pub fn open(path: &str) -> Type {
+    let connection = match Connection::open(path) {
+        Ok(connection) => connection,
+        Err(_) => return Type::error("sql-connect"),
+    };
+
+    let mut connections = connections();
+    connections.0 += 1;
+    let id = connections.0;
+    connections
+        .1
+        .insert(id, Arc::new(Mutex::new(Session::new(connection))));
+
+    Type::Object(
+        "connection".to_string(),
+        HashMap::from([
+            ("id".to_string(), Type::Number(id as f64)),
+            ("path".to_string(), Type::String(path.to_string().into())),
+        ]),
+    )
+}
+
+/// Close connection, and return whether it was opened
+pub fn close(handle: &Type) -> bool {
+    let (_, object) = handle.get_object();
+    let id = object.get("id").map(|x| x.get_number() as usize);
+    id.and_then(|id| connections().1.remove(&id)).is_some()
+}
+
+/// Query rows by the connection
+pub fn query(handle: &Type, sql_query: &str, params: &[Type]) -> Type {
+    with_connection(handle, |connection| {
+        query_table(connection, sql_query, params)
+    })
+    .unwrap_or(Type::error("sql-connect"))
+}
+
+/// Query rows by the connection as JSON array of objects
+pub fn query_json(handle: &Type, sql_query: &str, params: &[Type]) -> Type {
+    match query(handle, sql_query, params) {
+        Type::Error(err) => Type::Error(err),
+        result => Type::Json(result.to_json().into()),
+    }
+}
+
+/// Execute statement by the connection, and return result of the writing
+pub fn execute(handle: &Type, sql_query: &str, params: &[Type]) -> Type {
+    with_connection(handle, |session| {
+        let Ok(mut stmt) = session.prepare(sql_query) else {
+            return Type::error("pre-query");
+        };
+        match stmt.execute(params_from_iter(params.iter().map(to_sql))) {
+            Ok(count) => write_result(&session.connection, count),
+            Err(_) => Type::error("exe-query"),
+        }
+    })
+    .unwrap_or(Type::error("sql-connect"))
+}
+
+/// Control transaction by the statement, and return error if it failed
+pub fn transaction(handle: &Type, statement: &str) -> Option<Type> {
+    // Pin a pooled connection to the thread while the transaction is open
+    if is_pool(handle) && statement == "BEGIN" {
+        let pinned = PINNED.with(|pinned| pinned.borrow().is_some());
+        if !pinned {
+            let connection = pool().and_then(|pool| pool.checkout());
+            let Some(connection) = connection else {
+                return Some(Type::error("sql-connect"));
+            };
+            PINNED.with(|pinned| *pinned.borrow_mut() = Some(connection));
+        }
+    }
+
+    let result = with_connection(handle, |session| {
+        session.connection.execute_batch(statement)
+    });
+
+    // Return the pinned connection when the transaction is finished
+    if is_pool(handle) && (statement != "BEGIN" || !matches!(result, Some(Ok(())))) {
+        if let (Some(connection), Some(pool)) =
+            (PINNED.with(|pinned| pinned.borrow_mut().take()), pool())
+        {
+            pool.checkin(connection);
+        }
+    }
+
+    match result {
+        Some(Ok(())) => None,
+        Some(Err(_)) => Some(Type::error("transaction")),
+        None => Some(Type::error("sql-connect")),
+    }
+}
+
+/// Roll back the transaction left open on the thread, and return its connection into the pool
+pub fn release() {
+    let Some(connection) = PINNED.with(|pinned| pinned.borrow_mut().take()) else {
+        return;
+    };
+    connection.connection.execute_batch("ROLLBACK").ok();
+    if let Some(pool) = pool() {
+        pool.checkin(connection);
+    }
+}
+
+/// Make object of the writing result
+fn write_result(conn: &Connection, count: usize) -> Type {
+    Type::Object(
+        "result".to_string(),
+        HashMap::from([
+            ("rows-affected".to_string(), Type::Number(count as f64)),
+            (
+                "last-insert-rowid".to_string(),
+                Type::Number(conn.last_insert_rowid() as f64),
+            ),
+        ]),
+    )
+}
+
+// Execute SQL query and return table data
+pub fn sql(db_path: &str, sql_query: &str) -> Type {
+    // Draw a connection from the pool instead of opening one if it's of the same database
+    if pool().is_some_and(|pool| pool.path == db_path) {
+        return query(&pool_handle(), sql_query, &[]);
+    }
+    let conn = match Connection::open(db_path) {
+        Ok(connection) => connection,
+        Err(_) => return Type::error("sql-connect"),
+    };
+    query_table(&Session::new(conn), sql_query, &[])
+}
+
+/// Get statistics of the prepared statement cache
+pub fn stats() -> Type {
+    Type::Object(
+        "stats".to_string(),
+        HashMap::from([
+            (
+                "statement-hits".to_string(),
+                Type::Number(STATEMENT_HITS.load(Ordering::Relaxed) as f64),
+            ),
+            (
+                "statement-misses".to_string(),
+                Type::Number(STATEMENT_MISSES.load(Ordering::Relaxed) as f64),
+            ),
+            (
+                "statement-cache".to_string(),
+                Type::Number(STATEMENT_CACHE as f64),
+            ),
+        ]),
+    )
+}
+
+// Execute SQL query on the connection and return table data
+fn query_table(session: &Session, sql_query: &str, params: &[Type]) -> Type {
+    // preprocessing to execution query
+    let mut stmt = match session.prepare(sql_query) {
+        Ok(statement) => statement,
+        Err(_) => return Type::error("pre-query"),
+    };
+
+    // Statement without columns returns result of the writing
+    if stmt.column_count() == 0 {
+        return match stmt.execute(params_from_iter(params.iter().map(to_sql))) {
+            Ok(count) => write_result(&session.connection, count),
+            Err(_) => Type::error("exe-query"),
+        };
+    }
+
+    // Get table's rows
+    let rows = match stmt.query_map(params_from_iter(params.iter().map(to_sql)), |row| {
+        let result: Result<Vec<(String, Type)>, rusqlite::Error> = Ok((0..row.column_count())
+            .map(|index| {
+                let column = row.column_name(index).unwrap().to_string();
+                let value = {
+                    let value = row.get_ref_unwrap(index);
+                    if let Ok(i) = value.as_str() {
+                        Type::String(i.to_string().into())
+                    } else if let Ok(i) = value.as_i64() {
+                        Type::Number(i as f64)
+                    } else if let Ok(i) = value.as_f64() {
+                        Type::Number(i)
+                    } else if let Ok(i) = value.as_blob() {
+                        Type::Binary(i.to_vec().into())
+                    } else if let rusqlite::types::ValueRef::Null = value {
+                        Type::Null
+                    } else {
+                        Type::error("parse-db")
+                    }
+                };
+                (column, value)
+            })
+            .collect());
+        result
+    }) {
+        Ok(rows) => rows,
+        Err(_) => return Type::error("exe-query"),
+    };
+
+    // Parse type for Stack
+    let mut result = Vec::new();
+    for row in rows {
+        match row {
+            Ok(values) => result.push({
+                let mut object = HashMap::new();
+                for (property, value) in values {
+                    object.insert(property, value);
+                }
+                Type::Object("table".to_string(), object)
+            }),
+            Err(_) => return Type::List(vec![].into()),
+        }
+    }
+
+    // Return table as list
+    Type::List(result.into())
+}
@@ -0,0 +1,162 @@
+use crate::Span;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Category of the error to handle it without knowing each command
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorKind {
+    Io,        // File system and process environment
+    Parse,     // Malformed text or data
+    Index,     // Position out of the range
+    NotFound,  // Missing key or item
+    Type,      // Value of unexpected type or shape
+    Network,   // Connection and protocol
+    Database,  // SQL and key-value store
+    Template,  // Template rendering
+    Security,  // Blocked by the security settings
+    Plugin,    // Native and WASM plugins
+    Assertion, // Failed assertion
+    Underflow, // Popping the empty stack
+    Other,     // Others including user-defined errors
+}
+
+impl ErrorKind {
+    /// Classify the error by the name of the command, the error or the kind
+    pub fn of(name: &str) -> ErrorKind {
+        match name {
+            "read-file" | "write-file" | "read-binary" | "create-file" | "temp-file"
+            | "temp-dir" | "path-not-found" | "dotenv" | "get-env" | "log-file" | "log"
+            | "pack" | "unpack" | "watch" | "on-signal" | "sys-info" => ErrorKind::Io,
+            "number-decoding" | "string-encoding" | "regex" | "json-path" | "time-format"
+            | "time-parse" | "time-zone" | "time-offset" | "schedule" | "log-level"
+            | "yaml-string" | "toml-string" | "csv-string" => ErrorKind::Parse,
+            "index-out-range" | "stack-out-range" => ErrorKind::Index,
+            "item-not-found" | "kv-not-found" | "global-not-found" | "job-not-found"
+            | "property" => ErrorKind::NotFound,
+            "not-object" | "not-list" | "cyclic-reference" | "instance-name"
+            | "instance-shortage" | "instance-default" | "cast" => ErrorKind::Type,
+            "tcp" | "websocket" | "mqtt" | "send-mail" | "http" => ErrorKind::Network,
+            "sql-connect" | "exe-query" | "pre-query" | "transaction" | "parse-db" | "kv-store" => {
+                ErrorKind::Database
+            }
+            "template" => ErrorKind::Template,
+            "eval-security-fault" => ErrorKind::Security,
+            "plugin" | "wasm" => ErrorKind::Plugin,
+            "assert" => ErrorKind::Assertion,
+            "stack-underflow" => ErrorKind::Underflow,
+            _ if name.ends_with("-parse") => ErrorKind::Parse,
+            // User-defined errors can be named by the kind
+            _ => ErrorKind::parse(name).unwrap_or(ErrorKind::Other),
+        }
+    }
+
+    /// Get the kind by the name used in the program
+    pub fn parse(name: &str) -> Option<ErrorKind> {
+        Some(match name {
+            "io" => ErrorKind::Io,
+            "parse" => ErrorKind::Parse,
+            "index" => ErrorKind::Index,
+            "not-found" => ErrorKind::NotFound,
+            "type" => ErrorKind::Type,
+            "network" => ErrorKind::Network,
+            "database" => ErrorKind::Database,
+            "template" => ErrorKind::Template,
+            "security" => ErrorKind::Security,
+            "plugin" => ErrorKind::Plugin,
+            "assertion" => ErrorKind::Assertion,
+            "underflow" => ErrorKind::Underflow,
+            "other" => ErrorKind::Other,
+            _ => return None,
+        })
+    }
+
+    /// Name of the kind used in the program
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::Io => "io",
+            ErrorKind::Parse => "parse",
+            ErrorKind::Index => "index",
+            ErrorKind::NotFound => "not-found",
+            ErrorKind::Type => "type",
+            ErrorKind::Network => "network",
+            ErrorKind::Database => "database",
+            ErrorKind::Template => "template",
+            ErrorKind::Security => "security",
+            ErrorKind::Plugin => "plugin",
+            ErrorKind::Assertion => "assertion",
+            ErrorKind::Underflow => "underflow",
+            ErrorKind::Other => "other",
+        }
+    }
+
+    /// HTTP status responded for the error
+    pub fn status(&self) -> &'static str {
+        match self {
+            ErrorKind::Parse => "400 Bad Request",
+            ErrorKind::Security => "403 Forbidden",
+            ErrorKind::NotFound => "404 Not Found",
+            _ => "500 Internal Server Error",
+        }
+    }
+}
+
+/// Error value with its kind, message, location and cause
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StackError {
+    pub kind: ErrorKind,                // Category of the error
+    pub name: String,                   // Name of the error like "read-file"
+    pub message: String,                // Description of the error
+    pub position: Option<Span>,         // Position of the token raising the error
+    pub cause: Option<Box<StackError>>, // Error causing this error
+}
+
+impl StackError {
+    /// Constructor classifying the error by the name of the built-in command
+    pub fn new(name: &str, message: impl fmt::Display) -> StackError {
+        StackError {
+            kind: ErrorKind::of(name),
+            name: name.to_string(),
+            message: message.to_string(),
+            position: None,
+            cause: None,
+        }
+    }
+
+    /// Constructor of the error whose kind is known where it's raised
+    pub fn with_kind(kind: ErrorKind, name: &str, message: impl fmt::Display) -> StackError {
+        StackError {
+            kind,
+            ..StackError::new(name, message)
+        }
+    }
+
+    /// Parse the error literal like "read-file: message"
+    pub fn parse(text: &str) -> StackError {
+        match text.split_once(':') {
+            Some((name, message)) => StackError::new(name.trim(), message.trim()),
+            None => StackError::new(text.trim(), ""),
+        }
+    }
+
+    /// Set the position unless it's already known
+    pub fn at(mut self, position: Span) -> StackError {
+        self.position.get_or_insert(position);
+        self
+    }
+
+    /// Set the error causing this error
+    pub fn caused_by(mut self, cause: StackError) -> StackError {
+        self.cause = Some(Box::new(cause));
+        self
+    }
+}
+
+impl fmt::Display for StackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.message.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{}: {}", self.name, self.message)
+        }
+    }
+}
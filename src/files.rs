@@ -0,0 +1,181 @@
+use crate::commands::Commands;
+use crate::{StackError, Type};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+
+/// Opened file read through the buffer
+pub type Reader = Arc<Mutex<BufReader<File>>>;
+
+/// Opened files and the last identifier
+type Files = (usize, HashMap<usize, Reader>);
+
+static FILES: LazyLock<Mutex<Files>> = LazyLock::new(|| Mutex::new((0, HashMap::new())));
+
+/// Lock the file registry
+fn files() -> MutexGuard<'static, Files> {
+    FILES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Get identifier of the handle object
+fn id(handle: &Type) -> Option<usize> {
+    match handle {
+        Type::Object(name, object) if name == "file" => {
+            object.get("id").map(|x| x.get_number() as usize)
+        }
+        _ => None,
+    }
+}
+
+/// Get the opened file of the handle object
+fn reader(handle: &Type) -> Result<Reader, String> {
+    let id = id(handle).ok_or("the value is not a file")?;
+    files()
+        .1
+        .get(&id)
+        .cloned()
+        .ok_or(format!("file {id} is closed"))
+}
+
+/// Lock the opened file to read it
+fn lock(reader: &Reader) -> MutexGuard<'_, BufReader<File>> {
+    reader.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Open the file to read, and return its handle object
+pub fn open(path: &str) -> Result<Type, String> {
+    let file = File::open(path).map_err(|e| format!("{path}: {e}"))?;
+    let size = file.metadata().map(|x| x.len()).unwrap_or(0);
+
+    let mut files = files();
+    files.0 += 1;
+    let id = files.0;
+    files
+        .1
+        .insert(id, Arc::new(Mutex::new(BufReader::new(file))));
+    Ok(Type::Object(
+        "file".to_string(),
+        HashMap::from([
+            ("id".to_string(), Type::Number(id as f64)),
+            ("path".to_string(), Type::String(path.into())),
+            ("size".to_string(), Type::Number(size as f64)),
+        ]),
+    ))
+}
+
+/// Read a line without the line break (None at the end of the file)
+pub fn read_line(handle: &Type) -> Result<Option<String>, String> {
+    let reader = reader(handle)?;
+    let mut file = lock(&reader);
+    let mut line = String::new();
+    match file.read_line(&mut line).map_err(|e| e.to_string())? {
+        0 => Ok(None),
+        _ => Ok(Some(line.trim_end_matches(['\r', '\n']).to_string())),
+    }
+}
+
+/// Read bytes up to the size (empty at the end of the file)
+pub fn read_chunk(handle: &Type, size: usize) -> Result<Vec<u8>, String> {
+    let reader = reader(handle)?;
+    let mut file = lock(&reader);
+    let mut buffer = Vec::new();
+    file.by_ref()
+        .take(size as u64)
+        .read_to_end(&mut buffer)
+        .map_err(|e| e.to_string())?;
+    Ok(buffer)
+}
+
+/// Move to the offset from the start (or from the end if it's negative),
+/// and return the new position
+pub fn seek(handle: &Type, offset: f64) -> Result<u64, String> {
+    let reader = reader(handle)?;
+    let position = if offset < 0.0 {
+        SeekFrom::End(offset as i64)
+    } else {
+        SeekFrom::Start(offset as u64)
+    };
+    let mut file = lock(&reader);
+    file.seek(position).map_err(|e| e.to_string())
+}
+
+/// Close the file, and return whether it was opened
+pub fn close(handle: &Type) -> bool {
+    id(handle).is_some_and(|id| files().1.remove(&id).is_some())
+}
+
+/// Whether the value is the handle of the opened file
+pub fn is_opened(handle: &Type) -> bool {
+    id(handle).is_some_and(|id| files().1.contains_key(&id))
+}
+
+/// Take the opened file out of the registry to stream its rest (None unless it's opened)
+pub fn take(handle: &Type) -> Option<Reader> {
+    files().1.remove(&id(handle)?)
+}
+
+/// Copy the rest of the file into the writer after the header made from its length.
+/// The file is copied without the buffer so that the copy can be done by the kernel
+pub fn copy(
+    reader: &Reader,
+    header: impl FnOnce(u64) -> String,
+    writer: &mut impl Write,
+) -> io::Result<u64> {
+    let mut file = lock(reader);
+    let position = file.stream_position()?;
+    let size = file.get_ref().metadata()?.len();
+    writer.write_all(header(size.saturating_sub(position)).as_bytes())?;
+
+    // Write the data already read into the buffer first
+    let buffered = file.buffer().len();
+    writer.write_all(file.buffer())?;
+    file.consume(buffered);
+    Ok(buffered as u64 + io::copy(file.get_mut(), writer)?)
+}
+
+/// Register the commands of file handles
+pub fn register_commands(commands: &mut Commands) {
+    // Open the file to read it little by little
+    commands.add("open", |executor| {
+        let path = executor.pop_stack().get_string();
+        let handle = open(&path).map_err(|e| StackError::new("read-file", e))?;
+        executor.stack.push(handle);
+        Ok(())
+    });
+
+    // Read a line from the file
+    commands.add("read-line", |executor| {
+        let handle = executor.pop_stack();
+        let line = read_line(&handle).map_err(|e| StackError::new("read-file", e))?;
+        executor
+            .stack
+            .push(line.map(|x| Type::String(x.into())).unwrap_or(Type::Null));
+        Ok(())
+    });
+
+    // Read bytes from the file
+    commands.add("read-chunk", |executor| {
+        let size = executor.pop_stack().get_number() as usize;
+        let handle = executor.pop_stack();
+        let data = read_chunk(&handle, size).map_err(|e| StackError::new("read-file", e))?;
+        executor.stack.push(Type::Binary(data.into()));
+        Ok(())
+    });
+
+    // Move the position to read in the file
+    commands.add("seek", |executor| {
+        let offset = executor.pop_stack().get_number();
+        let handle = executor.pop_stack();
+        let position = seek(&handle, offset).map_err(|e| StackError::new("read-file", e))?;
+        executor.stack.push(Type::Number(position as f64));
+        Ok(())
+    });
+
+    // Close the file
+    commands.add("close", |executor| {
+        let handle = executor.pop_stack();
+        executor.stack.push(Type::Bool(close(&handle)));
+        Ok(())
+    });
+}
@@ -0,0 +1,291 @@
+use crate::lexer::{self, Delimiter, Span};
+use std::fs;
+use std::io::{self, Read};
+
+/// Width of an indentation level
+const INDENT: &str = "    ";
+
+/// Characters separating tokens
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\r' | '　')
+}
+
+/// Ranges of the source kept as they are (strings and comments)
+struct Layout {
+    verbatim: Vec<(usize, usize)>, // Start and end index of the characters
+}
+
+/// Find the strings and comments by the delimiters as the tokenizer reads them
+fn layout(code: &str) -> Layout {
+    // Index of the character by its byte offset
+    let offsets: Vec<usize> = code.char_indices().map(|(offset, _)| offset).collect();
+    let index = |span: Span| offsets.binary_search(&span.start).unwrap_or_default();
+
+    let mut verbatim = Vec::new();
+    let mut strings: Vec<Span> = Vec::new(); // Positions of opened strings
+    let mut comment: Option<Span> = None;
+
+    for (delimiter, span) in lexer::delimiters(code) {
+        match delimiter {
+            Delimiter::Comment => match comment.take() {
+                Some(start) => verbatim.push((index(start), index(span))),
+                None => comment = Some(span),
+            },
+            Delimiter::OpenString => strings.push(span),
+            Delimiter::CloseString => {
+                if let Some(start) = strings.pop() {
+                    verbatim.push((index(start), index(span)));
+                }
+            }
+            _ => {}
+        }
+    }
+    // Strings and comment left open run to the end
+    for start in strings.into_iter().chain(comment) {
+        verbatim.push((index(start), offsets.len()));
+    }
+
+    // Drop the ranges inside other ones
+    verbatim.sort();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in verbatim {
+        match merged.last() {
+            Some(&(_, last)) if start <= last => {}
+            _ => merged.push((start, end)),
+        }
+    }
+    Layout { verbatim: merged }
+}
+
+/// Format a line of the source, and return it with its indentation level
+fn format_line(
+    chars: &[char],
+    range: (usize, usize),
+    layout: &Layout,
+    nests: &mut Vec<usize>,
+) -> (String, usize) {
+    let mut content = String::new();
+    let mut space = false;
+    let mut indent = nests.last().copied().unwrap_or(0);
+    let mut leading = true;
+    let mut index = range.0;
+
+    while index < range.1 {
+        let c = chars[index];
+
+        // Copy the string or comment as it is
+        if let Some(&(_, end)) = layout
+            .verbatim
+            .iter()
+            .find(|(start, end)| *start <= index && index <= *end)
+        {
+            if space && !content.is_empty() {
+                content.push(' ');
+            }
+            let end = end.min(range.1 - 1);
+            content.extend(&chars[index..=end]);
+            index = end + 1;
+            space = false;
+            leading = false;
+            continue;
+        }
+
+        if is_space(c) {
+            space = true;
+            index += 1;
+            continue;
+        }
+        if space && !content.is_empty() {
+            content.push(' ');
+        }
+        space = false;
+
+        match c {
+            '\\' => {
+                content.push(c);
+                if let Some(next) = chars.get(index + 1).filter(|_| index + 1 < range.1) {
+                    content.push(*next);
+                    index += 1;
+                }
+            }
+            // Contents of the nests opened in the line are indented by one level
+            '[' => nests.push(indent + 1),
+            ']' => {
+                let level = nests.pop().unwrap_or(1);
+                // Closing characters at the head of the line are outdented
+                if leading {
+                    indent = indent.min(level - 1);
+                }
+            }
+            _ => {}
+        }
+        if c != '\\' {
+            content.push(c);
+        }
+        leading &= c == ']';
+        index += 1;
+    }
+    (content.trim_end_matches('\r').to_string(), indent)
+}
+
+/// Find end of the matcher list in the line beginning with "[["
+fn matcher_end(content: &str) -> Option<usize> {
+    let mut brackets = 0;
+    let mut parentheses = 0;
+    let mut escape = false;
+    for (index, c) in content.char_indices().skip(1) {
+        match c {
+            _ if escape => escape = false,
+            '\\' => escape = true,
+            '(' => parentheses += 1,
+            ')' => parentheses -= 1,
+            '[' if parentheses == 0 => brackets += 1,
+            ']' if parentheses == 0 => {
+                brackets -= 1;
+                if brackets == 0 {
+                    return Some(index + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Align handlers of the consecutive route entries like `[[(GET /)] (...)]`
+fn align_routes(lines: &mut [(usize, String)]) {
+    let mut start = 0;
+    while start < lines.len() {
+        let indent = lines[start].0;
+        let mut end = start;
+        let mut width = 0;
+        while let Some((level, content)) = lines.get(end) {
+            let Some(matcher) = content
+                .starts_with("[[")
+                .then(|| matcher_end(content))
+                .flatten()
+                .filter(|x| *x < content.len() && *level == indent)
+            else {
+                break;
+            };
+            width = width.max(content[..matcher].chars().count());
+            end += 1;
+        }
+
+        if end - start > 1 {
+            for (_, content) in &mut lines[start..end] {
+                if let Some(matcher) = matcher_end(content) {
+                    let (head, rest) = content.split_at(matcher);
+                    let padding = width - head.chars().count() + 1;
+                    *content = format!("{head}{}{}", " ".repeat(padding), rest.trim_start());
+                }
+            }
+        }
+        start = end.max(start + 1);
+    }
+}
+
+/// Format the program
+pub fn format(code: &str) -> String {
+    let chars: Vec<char> = code.chars().collect();
+    let layout = layout(code);
+
+    // Split into the lines with index ranges of the characters
+    let mut ranges = Vec::new();
+    let mut begin = 0;
+    for (index, c) in chars.iter().enumerate() {
+        if *c == '\n' {
+            ranges.push((begin, index));
+            begin = index + 1;
+        }
+    }
+    ranges.push((begin, chars.len()));
+
+    let mut nests = Vec::new();
+    let mut lines: Vec<Option<(usize, String)>> = Vec::new();
+    for range in ranges {
+        // Keep the line beginning in the string or comment without indenting it
+        let inside = layout
+            .verbatim
+            .iter()
+            .any(|(start, end)| *start < range.0 && range.0 <= *end);
+        let (content, indent) = format_line(&chars, range, &layout, &mut nests);
+        if inside {
+            lines.push(Some((usize::MAX, content)));
+        } else {
+            lines.push((!content.is_empty()).then_some((indent, content)));
+        }
+    }
+
+    // Align route tables in each group of non-blank lines
+    let mut output = String::new();
+    let mut blank = false;
+    for group in lines.split(|x| x.is_none()) {
+        let mut group: Vec<(usize, String)> = group.iter().flatten().cloned().collect();
+        if group.is_empty() {
+            continue;
+        }
+        align_routes(&mut group);
+        if blank {
+            output.push('\n');
+        }
+        for (indent, content) in group {
+            if indent == usize::MAX {
+                output += &content;
+            } else {
+                output += &format!("{}{content}", INDENT.repeat(indent));
+            }
+            output.push('\n');
+        }
+        blank = true;
+    }
+    output
+}
+
+/// Format the files in place, print them, or show the difference if checking
+pub fn run(paths: &[&str], check: bool, stdout: bool) -> Result<bool, String> {
+    if paths.is_empty() {
+        let mut code = String::new();
+        io::stdin()
+            .read_to_string(&mut code)
+            .map_err(|e| e.to_string())?;
+        let formatted = format(&code);
+        if check {
+            print_diff("<stdin>", &code, &formatted);
+            return Ok(code == formatted);
+        }
+        print!("{formatted}");
+        return Ok(true);
+    }
+
+    let mut formatted_all = true;
+    for path in paths {
+        let code = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+        let formatted = format(&code);
+        if check {
+            print_diff(path, &code, &formatted);
+            formatted_all &= code == formatted;
+        } else if stdout {
+            print!("{formatted}");
+        } else if code != formatted {
+            fs::write(path, formatted).map_err(|e| format!("{path}: {e}"))?;
+            println!("Formatted {path}");
+        }
+    }
+    Ok(formatted_all)
+}
+
+/// Print the lines differing between the source and the formatted one
+fn print_diff(path: &str, code: &str, formatted: &str) {
+    if code == formatted {
+        return;
+    }
+    println!("--- {path}\n+++ {path} (formatted)");
+    for line in diff::lines(code, formatted) {
+        match line {
+            diff::Result::Left(line) => println!("-{line}"),
+            diff::Result::Right(line) => println!("+{line}"),
+            diff::Result::Both(..) => {}
+        }
+    }
+}
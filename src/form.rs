@@ -0,0 +1,202 @@
+use crate::commands::Commands;
+use crate::{ErrorKind, StackError, Type};
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Address like "name@example.com" accepted by the email type
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s.]+$").expect("email pattern is valid"));
+
+/// Result of the validation of the form
+pub enum Validation {
+    Valid(Map<String, Value>),   // Cleaned values by the field
+    Invalid(Map<String, Value>), // Error messages by the field
+}
+
+/// Parse the URL-encoded form like "name=alice&tags=a&tags=b" into the values by the name
+/// (the repeated names are made the list of the values), unless it's decoded already
+pub fn parse(body: &str, decoded: bool) -> HashMap<String, Type> {
+    let decode = |x: &str| match decoded {
+        true => x.to_string(),
+        false => {
+            let x = x.replace('+', " ");
+            percent_decode_str(&x).decode_utf8_lossy().to_string()
+        }
+    };
+    let mut form: HashMap<String, Vec<Type>> = HashMap::new();
+    for pair in body.trim().split('&').filter(|x| !x.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        form.entry(decode(name))
+            .or_default()
+            .push(Type::String(decode(value).into()));
+    }
+    form.into_iter()
+        .map(|(name, mut values)| match values.len() {
+            1 => (name, values.remove(0)),
+            _ => (name, Type::List(values.into())),
+        })
+        .collect()
+}
+
+/// Validate the values by the rules of each field like
+/// `{"age": {"required": true, "type": "integer", "min": 0}}`,
+/// getting the cleaned values or the errors by the field
+pub fn validate(
+    form: &Map<String, Value>,
+    rules: &Map<String, Value>,
+) -> Result<Validation, String> {
+    let mut cleaned = Map::new();
+    let mut errors = Map::new();
+    for (field, rule) in rules {
+        let values = match form.get(field) {
+            Some(Value::Array(values)) => values.clone(),
+            Some(Value::Null) | None => Vec::new(),
+            Some(value) => vec![value.clone()],
+        };
+        // Blank values are the same as the missing ones
+        let values: Vec<String> = values
+            .iter()
+            .map(|x| match x {
+                Value::String(s) => s.trim().to_string(),
+                x => x.to_string(),
+            })
+            .filter(|x| !x.is_empty())
+            .collect();
+
+        let kind = rule
+            .get("type")
+            .and_then(|x| x.as_str())
+            .unwrap_or("string");
+        if values.is_empty() {
+            if rule.get("required").and_then(|x| x.as_bool()) == Some(true) {
+                errors.insert(field.clone(), "is required".into());
+            } else if kind == "bool" {
+                // Unchecked checkbox isn't sent at all
+                cleaned.insert(field.clone(), false.into());
+            } else {
+                cleaned.insert(field.clone(), Value::Null);
+            }
+            continue;
+        }
+
+        let mut items = Vec::new();
+        for value in &values {
+            match check(value, kind, rule)? {
+                Ok(item) => items.push(item),
+                Err(error) => {
+                    errors.insert(field.clone(), error.into());
+                    break;
+                }
+            }
+        }
+        if !errors.contains_key(field) {
+            let value = match items.len() {
+                1 => items.remove(0),
+                _ => Value::Array(items),
+            };
+            cleaned.insert(field.clone(), value);
+        }
+    }
+    Ok(match errors.is_empty() {
+        true => Validation::Valid(cleaned),
+        false => Validation::Invalid(errors),
+    })
+}
+
+/// Check the value by the rule, and convert it to the type
+fn check(value: &str, kind: &str, rule: &Value) -> Result<Result<Value, String>, String> {
+    let length = value.chars().count() as f64;
+    if let Some(min) = number_rule(rule, "min-length").filter(|min| length < *min) {
+        return Ok(Err(format!("must be at least {min} characters")));
+    }
+    if let Some(max) = number_rule(rule, "max-length").filter(|max| length > *max) {
+        return Ok(Err(format!("must be at most {max} characters")));
+    }
+    if let Some(pattern) = rule.get("regex").and_then(|x| x.as_str()) {
+        let regex = Regex::new(&format!("^(?:{pattern})$"))
+            .map_err(|e| e.to_string().replace("Error", ""))?;
+        if !regex.is_match(value) {
+            return Ok(Err("is invalid".to_string()));
+        }
+    }
+
+    let value = match kind {
+        "string" => Value::String(value.to_string()),
+        "email" if EMAIL.is_match(value) => Value::String(value.to_string()),
+        "email" => return Ok(Err("must be an email address".to_string())),
+        "number" | "integer" => {
+            let Some(number) = value.parse::<f64>().ok().filter(|x| x.is_finite()) else {
+                return Ok(Err(match kind {
+                    "integer" => "must be an integer".to_string(),
+                    _ => "must be a number".to_string(),
+                }));
+            };
+            if kind == "integer" && number.fract() != 0.0 {
+                return Ok(Err("must be an integer".to_string()));
+            }
+            if let Some(min) = number_rule(rule, "min").filter(|min| number < *min) {
+                return Ok(Err(format!("must be at least {min}")));
+            }
+            if let Some(max) = number_rule(rule, "max").filter(|max| number > *max) {
+                return Ok(Err(format!("must be at most {max}")));
+            }
+            Value::from(number)
+        }
+        "bool" => match value.to_ascii_lowercase().as_str() {
+            "on" | "true" | "yes" | "1" => true.into(),
+            "off" | "false" | "no" | "0" => false.into(),
+            _ => return Ok(Err("must be a bool".to_string())),
+        },
+        kind => return Err(format!("unknown type \"{kind}\"")),
+    };
+    Ok(Ok(value))
+}
+
+/// Get the number of the rule
+fn number_rule(rule: &Value, key: &str) -> Option<f64> {
+    rule.get(key).and_then(|x| x.as_f64())
+}
+
+/// Register the commands of the forms
+pub fn register_commands(commands: &mut Commands) {
+    // Parse the URL-encoded form body
+    // (the raw body is decoded, while the body string given to the handler is decoded already)
+    commands.add("form-parse", |executor| {
+        let (body, decoded) = match executor.pop_stack() {
+            Type::Binary(bytes) => (String::from_utf8_lossy(&bytes).to_string(), false),
+            body => (body.get_string(), true),
+        };
+        executor
+            .stack
+            .push(Type::Object("form".to_string(), parse(&body, decoded)));
+        Ok(())
+    });
+
+    // Validate the form by the rules, getting the cleaned values or the errors by the field
+    commands.add("form-validate", |executor| {
+        let rules = executor.pop_stack().to_json();
+        let form = executor.pop_stack().to_json();
+        let (Value::Object(form), Value::Object(rules)) = (form, rules) else {
+            return Err(StackError::with_kind(
+                ErrorKind::Type,
+                "form-validate",
+                "the form and the rules must be objects",
+            ));
+        };
+        let result = validate(&form, &rules)
+            .map_err(|e| StackError::with_kind(ErrorKind::Parse, "form-validate", e))?;
+        let (name, values) = match result {
+            Validation::Valid(cleaned) => ("form", cleaned),
+            Validation::Invalid(errors) => ("form-errors", errors),
+        };
+        let values = values
+            .iter()
+            .map(|(field, value)| (field.clone(), Type::from_json(value)))
+            .collect();
+        executor.stack.push(Type::Object(name.to_string(), values));
+        Ok(())
+    });
+}
@@ -0,0 +1,47 @@
+use crate::{Executor, Type};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Running futures and the last identifier
+type Futures = (usize, HashMap<usize, JoinHandle<Type>>);
+
+static FUTURES: LazyLock<Mutex<Futures>> = LazyLock::new(|| Mutex::new((0, HashMap::new())));
+
+/// Start the code on a worker thread, and return handle object of the future
+pub fn spawn(code: String, mut executor: Executor) -> Type {
+    executor.stack.clear();
+    let handle = thread::spawn(move || {
+        executor.evaluate_program(code);
+        // The future results error if the code left it
+        match executor.stack.iter().find(|x| matches!(x, Type::Error(_))) {
+            Some(err) => err.clone(),
+            None => executor.stack.pop().unwrap_or(Type::Null),
+        }
+    });
+
+    let mut futures = FUTURES.lock().unwrap_or_else(|e| e.into_inner());
+    futures.0 += 1;
+    let id = futures.0;
+    futures.1.insert(id, handle);
+    Type::Object(
+        "future".to_string(),
+        HashMap::from([("id".to_string(), Type::Number(id as f64))]),
+    )
+}
+
+/// Block until the future is resolved, and return its result
+pub fn wait(future: &Type) -> Result<Type, String> {
+    let (_, object) = future.get_object();
+    let id = object
+        .get("id")
+        .map(|x| x.get_number() as usize)
+        .ok_or("the value is not a future")?;
+    let handle = FUTURES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .1
+        .remove(&id)
+        .ok_or(format!("future {id} is not found or already awaited"))?;
+    handle.join().map_err(|_| format!("future {id} panicked"))
+}
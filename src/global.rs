@@ -0,0 +1,59 @@
+use crate::Type;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, RwLock};
+
+/// Variables shared by all of executors in the process
+static GLOBALS: LazyLock<RwLock<HashMap<String, Type>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Lock to serialize writings of the variables
+static WRITING: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    /// Whether the thread is running the block of the update
+    static UPDATING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run the writing process holding the lock (the update's block already holds it)
+fn write<R>(process: impl FnOnce() -> R) -> R {
+    if UPDATING.with(|updating| updating.get()) {
+        return process();
+    }
+    let _lock = WRITING.lock().unwrap_or_else(|e| e.into_inner());
+    UPDATING.with(|updating| updating.set(true));
+    let result = process();
+    UPDATING.with(|updating| updating.set(false));
+    result
+}
+
+/// Store value of the variable
+fn store(name: &str, value: Type) {
+    GLOBALS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.to_string(), value);
+}
+
+/// Get value of the variable
+pub fn get(name: &str) -> Option<Type> {
+    GLOBALS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+        .cloned()
+}
+
+/// Set value of the variable
+pub fn set(name: &str, value: Type) {
+    write(|| store(name, value));
+}
+
+/// Replace value of the variable by the result of the process atomically
+pub fn update(name: &str, process: impl FnOnce(Type) -> Type) -> Type {
+    write(|| {
+        let value = process(get(name).unwrap_or(Type::Null));
+        store(name, value.clone());
+        value
+    })
+}
@@ -0,0 +1,715 @@
+use crate::{Executor, Type};
+use async_graphql_parser::types::{
+    BaseType, DocumentOperations, ExecutableDocument, Field, FieldDefinition, FragmentDefinition,
+    OperationType, Selection, SelectionSet, Type as FieldType, TypeDefinition, TypeKind,
+    TypeSystemDefinition,
+};
+use async_graphql_parser::{parse_query, parse_schema, Pos, Positioned};
+use async_graphql_value::{ConstValue, Name, Value as Input};
+use percent_encoding::percent_decode_str;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Scalars defined without the schema
+const BUILT_IN_SCALARS: [&str; 5] = ["Int", "Float", "String", "Boolean", "ID"];
+
+/// Types of the schema and the root types of the operations
+struct Schema {
+    types: HashMap<String, TypeDefinition>,
+    query: String,
+    mutation: Option<String>,
+}
+
+impl Schema {
+    /// Parse the schema definition language
+    fn parse(source: &str) -> Result<Schema, String> {
+        let document = parse_schema(source).map_err(|e| e.to_string())?;
+        let mut types: HashMap<String, TypeDefinition> = HashMap::new();
+        let mut roots = None;
+        for definition in document.definitions {
+            match definition {
+                TypeSystemDefinition::Schema(schema) => {
+                    let name = |x: Option<Positioned<Name>>| x.map(|x| x.node.to_string());
+                    roots = Some((name(schema.node.query), name(schema.node.mutation)));
+                }
+                TypeSystemDefinition::Type(definition) => {
+                    let definition = definition.node;
+                    let name = definition.name.node.to_string();
+                    // Fields of "extend type" are added to the type defined before
+                    match (types.get_mut(&name), definition.kind) {
+                        (Some(existing), TypeKind::Object(extension)) if definition.extend => {
+                            if let TypeKind::Object(object) = &mut existing.kind {
+                                object.fields.extend(extension.fields);
+                            }
+                        }
+                        (_, kind) => {
+                            types.insert(name, TypeDefinition { kind, ..definition });
+                        }
+                    }
+                }
+                TypeSystemDefinition::Directive(_) => {}
+            }
+        }
+
+        let (query, mutation) = match roots {
+            Some((query, mutation)) => (query.unwrap_or("Query".to_string()), mutation),
+            None => (
+                "Query".to_string(),
+                types
+                    .contains_key("Mutation")
+                    .then(|| "Mutation".to_string()),
+            ),
+        };
+        if !types.contains_key(&query) {
+            return Err(format!("the query type \"{query}\" is not defined"));
+        }
+        Ok(Schema {
+            types,
+            query,
+            mutation,
+        })
+    }
+
+    /// Get the field of the object or the interface type
+    fn field(&self, type_name: &str, name: &str) -> Option<&FieldDefinition> {
+        let fields = match &self.types.get(type_name)?.kind {
+            TypeKind::Object(object) => &object.fields,
+            TypeKind::Interface(interface) => &interface.fields,
+            _ => return None,
+        };
+        fields.iter().map(|x| &x.node).find(|x| x.name.node == name)
+    }
+
+    /// Whether the type has no fields to select (scalars and enums)
+    fn is_leaf(&self, type_name: &str) -> bool {
+        match self.types.get(type_name) {
+            Some(definition) => matches!(definition.kind, TypeKind::Scalar | TypeKind::Enum(_)),
+            None => BUILT_IN_SCALARS.contains(&type_name),
+        }
+    }
+
+    /// Whether the object type is the type itself, its member or its implementation
+    fn is_possible(&self, type_name: &str, object: &str) -> bool {
+        if type_name == object {
+            return true;
+        }
+        match (
+            self.types.get(type_name).map(|x| &x.kind),
+            self.types.get(object).map(|x| &x.kind),
+        ) {
+            (Some(TypeKind::Union(union)), _) => union.members.iter().any(|x| x.node == object),
+            (Some(TypeKind::Interface(_)), Some(TypeKind::Object(implementation))) => {
+                implementation
+                    .implements
+                    .iter()
+                    .any(|x| x.node == type_name)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Get the name of the type inside the lists and the non-null marks
+fn named(ty: &FieldType) -> &str {
+    match &ty.base {
+        BaseType::Named(name) => name.as_str(),
+        BaseType::List(inner) => named(inner),
+    }
+}
+
+/// Make the error with the location in the query
+fn error(message: impl Into<String>, pos: Pos, path: Option<&[Value]>) -> Value {
+    let mut error = json!({
+        "message": message.into(),
+        "locations": [{"line": pos.line, "column": pos.column}],
+    });
+    if let Some(path) = path {
+        error["path"] = Value::Array(path.to_vec());
+    }
+    error
+}
+
+/// GraphQL endpoint resolving the fields by the Stack code
+pub struct Endpoint {
+    schema: Result<Schema, String>,     // Schema, or why it can't be used
+    resolvers: HashMap<String, String>, // Code by "Type.field"
+}
+
+impl Endpoint {
+    /// Make the endpoint of the schema definition or the file of it,
+    /// and the resolvers like `[(Query.users) (code)]`
+    pub fn new(schema: &str, resolvers: Vec<(String, String)>) -> Endpoint {
+        let source = match Path::new(schema.trim()).is_file() {
+            true => fs::read_to_string(schema.trim()).map_err(|e| e.to_string()),
+            false => Ok(schema.to_string()),
+        };
+        let schema = source.and_then(|x| Schema::parse(&x));
+        match &schema {
+            Ok(schema) => {
+                for (name, _) in &resolvers {
+                    let (type_name, field) = name.split_once('.').unwrap_or((name, ""));
+                    if schema.field(type_name, field).is_none() {
+                        println!("Error! GraphQL resolver \"{name}\" is not a field of the schema");
+                    }
+                }
+            }
+            Err(e) => println!("Error! GraphQL schema: {e}"),
+        }
+        Endpoint {
+            schema,
+            resolvers: resolvers.into_iter().collect(),
+        }
+    }
+
+    /// Execute the request of the JSON body, or of the query string by GET,
+    /// and get the status and the JSON response
+    pub fn respond(
+        &self,
+        executor: &mut Executor,
+        method: &str,
+        query: &str,
+        body: &[u8],
+    ) -> (u16, Value) {
+        let failure = |status, message: &str| (status, json!({"errors": [{"message": message}]}));
+        let schema = match &self.schema {
+            Ok(schema) => schema,
+            Err(e) => return failure(500, &format!("the schema is invalid: {e}")),
+        };
+
+        // Parameters are "query", "variables" and "operationName"
+        let params: Map<String, Value> = if method == "GET" {
+            query
+                .split('&')
+                .filter_map(|pair| {
+                    let (key, value) = pair.split_once('=')?;
+                    let value = value.replace('+', " ");
+                    let value = percent_decode_str(&value).decode_utf8_lossy().to_string();
+                    let value = match key {
+                        "variables" => serde_json::from_str(&value).unwrap_or(Value::Null),
+                        _ => Value::String(value),
+                    };
+                    Some((key.to_string(), value))
+                })
+                .collect()
+        } else {
+            match serde_json::from_slice(body) {
+                Ok(Value::Object(params)) => params,
+                _ => return failure(400, "the body must be a JSON object with \"query\""),
+            }
+        };
+        let Some(source) = params.get("query").and_then(|x| x.as_str()) else {
+            return failure(400, "\"query\" is not given");
+        };
+        let variables = match params.get("variables") {
+            Some(Value::Object(variables)) => variables.clone(),
+            _ => Map::new(),
+        };
+        let operation_name = params.get("operationName").and_then(|x| x.as_str());
+
+        let document = match parse_query(source) {
+            Ok(document) => document,
+            Err(e) => return failure(400, &e.to_string()),
+        };
+        let mut execution = Execution {
+            schema,
+            resolvers: &self.resolvers,
+            document: &document,
+            variables,
+            executor,
+            errors: Vec::new(),
+        };
+        match execution.run(operation_name, method == "GET") {
+            Ok(data) => {
+                let mut response = json!({ "data": data });
+                if !execution.errors.is_empty() {
+                    response["errors"] = Value::Array(execution.errors);
+                }
+                (200, response)
+            }
+            Err(errors) => (400, json!({ "errors": errors })),
+        }
+    }
+}
+
+/// State of executing the operation
+struct Execution<'a> {
+    schema: &'a Schema,
+    resolvers: &'a HashMap<String, String>,
+    document: &'a ExecutableDocument,
+    variables: Map<String, Value>,
+    executor: &'a mut Executor,
+    errors: Vec<Value>,
+}
+
+/// Fields of the same response key in the order of the selection
+type Groups<'a> = Vec<(String, Vec<&'a Positioned<Field>>)>;
+
+impl<'a> Execution<'a> {
+    /// Validate and execute the operation, and get the data (the errors if it can't be executed)
+    fn run(&mut self, name: Option<&str>, read_only: bool) -> Result<Value, Vec<Value>> {
+        let request_error = |message: &str| vec![json!({ "message": message })];
+        let operation = match (&self.document.operations, name) {
+            (DocumentOperations::Single(operation), _) => operation,
+            (DocumentOperations::Multiple(operations), Some(name)) => operations
+                .get(name)
+                .ok_or(request_error(&format!("unknown operation \"{name}\"")))?,
+            (DocumentOperations::Multiple(_), None) => {
+                return Err(request_error("the operation name must be given"))
+            }
+        };
+        let root = match operation.node.ty {
+            OperationType::Query => self.schema.query.clone(),
+            OperationType::Mutation if read_only => {
+                return Err(request_error("mutations can't be sent by GET"))
+            }
+            OperationType::Mutation => self
+                .schema
+                .mutation
+                .clone()
+                .ok_or(request_error("the schema has no mutations"))?,
+            OperationType::Subscription => {
+                return Err(request_error("subscriptions are not supported"))
+            }
+        };
+
+        // Use the default values of the variables not given
+        for definition in &operation.node.variable_definitions {
+            let definition = &definition.node;
+            let name = definition.name.node.to_string();
+            if self.variables.get(&name).is_some_and(|x| !x.is_null()) {
+                continue;
+            }
+            match definition.default_value() {
+                Some(value) => {
+                    let value = value.clone().into_json().unwrap_or(Value::Null);
+                    self.variables.insert(name, value);
+                }
+                None => {
+                    let message = format!(
+                        "variable \"${name}\" of \"{}\" is required",
+                        definition.var_type.node
+                    );
+                    return Err(vec![error(message, definition.name.pos, None)]);
+                }
+            }
+        }
+
+        let mut errors = Vec::new();
+        let mut visited = HashSet::new();
+        self.validate(
+            &root,
+            &operation.node.selection_set.node,
+            &mut visited,
+            &mut errors,
+        );
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let groups = self.collect(&root, &[&operation.node.selection_set.node]);
+        Ok(self
+            .execute(&root, &Type::Null, groups, &mut Vec::new())
+            .unwrap_or(Value::Null))
+    }
+
+    /// Check the fields, the arguments and the fragments of the selections against the schema
+    fn validate(
+        &self,
+        type_name: &str,
+        set: &SelectionSet,
+        visited: &mut HashSet<String>,
+        errors: &mut Vec<Value>,
+    ) {
+        for item in &set.items {
+            match &item.node {
+                Selection::Field(field) => self.validate_field(type_name, field, visited, errors),
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.node.fragment_name.node.as_str();
+                    let Some(fragment) = self.document.fragments.get(name) else {
+                        errors.push(error(
+                            format!("unknown fragment \"{name}\""),
+                            spread.pos,
+                            None,
+                        ));
+                        continue;
+                    };
+                    // Validate each fragment once not to loop by the cycle
+                    if visited.insert(name.to_string()) {
+                        let condition = fragment.node.type_condition.node.on.node.as_str();
+                        self.validate_condition(
+                            condition,
+                            &fragment.node.selection_set.node,
+                            fragment.pos,
+                            visited,
+                            errors,
+                        );
+                    }
+                }
+                Selection::InlineFragment(fragment) => {
+                    let condition = fragment
+                        .node
+                        .type_condition
+                        .as_ref()
+                        .map_or(type_name, |x| x.node.on.node.as_str());
+                    self.validate_condition(
+                        condition,
+                        &fragment.node.selection_set.node,
+                        fragment.pos,
+                        visited,
+                        errors,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check the field exists on the type with its arguments, and its selection fits its type
+    fn validate_field(
+        &self,
+        type_name: &str,
+        field: &Positioned<Field>,
+        visited: &mut HashSet<String>,
+        errors: &mut Vec<Value>,
+    ) {
+        let name = field.node.name.node.as_str();
+        let mut fail = |message: String| errors.push(error(message, field.pos, None));
+        if name == "__typename" {
+            return;
+        }
+        if name.starts_with("__") {
+            return fail("introspection is not supported".to_string());
+        }
+        let Some(definition) = self.schema.field(type_name, name) else {
+            return fail(format!(
+                "cannot query field \"{name}\" on type \"{type_name}\""
+            ));
+        };
+
+        let defined = |argument: &str| {
+            definition
+                .arguments
+                .iter()
+                .find(|x| x.node.name.node == argument)
+        };
+        for (argument, _) in &field.node.arguments {
+            if defined(&argument.node).is_none() {
+                fail(format!(
+                    "unknown argument \"{}\" of \"{type_name}.{name}\"",
+                    argument.node
+                ));
+            }
+        }
+        for argument in &definition.arguments {
+            let argument = &argument.node;
+            let given = field.node.get_argument(&argument.name.node).is_some();
+            if !given && !argument.ty.node.nullable && argument.default_value.is_none() {
+                fail(format!(
+                    "argument \"{}\" of \"{type_name}.{name}\" is required",
+                    argument.name.node
+                ));
+            }
+        }
+
+        let field_type = named(&definition.ty.node);
+        let selection = &field.node.selection_set.node;
+        match (self.schema.is_leaf(field_type), selection.items.is_empty()) {
+            (true, false) => fail(format!(
+                "\"{name}\" of \"{field_type}\" can't have a selection"
+            )),
+            (false, true) => fail(format!("\"{name}\" of \"{field_type}\" needs a selection")),
+            _ => self.validate(field_type, selection, visited, errors),
+        }
+    }
+
+    /// Validate the selections of the fragment on the type
+    fn validate_condition(
+        &self,
+        condition: &str,
+        set: &SelectionSet,
+        pos: Pos,
+        visited: &mut HashSet<String>,
+        errors: &mut Vec<Value>,
+    ) {
+        match self.schema.types.contains_key(condition) {
+            true => self.validate(condition, set, visited, errors),
+            false => errors.push(error(format!("unknown type \"{condition}\""), pos, None)),
+        }
+    }
+
+    /// Get the value of the argument replacing the variables
+    fn input(&self, value: &Input) -> Value {
+        value
+            .clone()
+            .into_const_with(|name| {
+                let value = self
+                    .variables
+                    .get(name.as_str())
+                    .cloned()
+                    .unwrap_or_default();
+                ConstValue::from_json(value)
+            })
+            .and_then(|x| x.into_json())
+            .unwrap_or(Value::Null)
+    }
+
+    /// Whether the selection is excluded by @skip or @include
+    fn is_skipped(&self, selection: &Selection) -> bool {
+        selection.directives().iter().any(|directive| {
+            let condition = directive
+                .node
+                .get_argument("if")
+                .map(|x| self.input(&x.node))
+                .and_then(|x| x.as_bool());
+            match directive.node.name.node.as_str() {
+                "skip" => condition == Some(true),
+                "include" => condition == Some(false),
+                _ => false,
+            }
+        })
+    }
+
+    /// Group the fields of the selections on the object type by the response key,
+    /// expanding the fragments applying to the type
+    fn collect(&self, type_name: &str, sets: &[&'a SelectionSet]) -> Groups<'a> {
+        let mut groups: Groups<'a> = Vec::new();
+        let mut visited = HashSet::new();
+        for set in sets {
+            self.collect_into(type_name, set, &mut groups, &mut visited);
+        }
+        groups
+    }
+
+    /// Add the fields of the selections into the groups
+    fn collect_into(
+        &self,
+        type_name: &str,
+        set: &'a SelectionSet,
+        groups: &mut Groups<'a>,
+        visited: &mut HashSet<&'a str>,
+    ) {
+        for item in &set.items {
+            if self.is_skipped(&item.node) {
+                continue;
+            }
+            match &item.node {
+                Selection::Field(field) => {
+                    let key = field.node.response_key().node.to_string();
+                    match groups.iter_mut().find(|(x, _)| *x == key) {
+                        Some((_, fields)) => fields.push(field),
+                        None => groups.push((key, vec![field])),
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.node.fragment_name.node.as_str();
+                    let Some(fragment) = self.document.fragments.get(name) else {
+                        continue;
+                    };
+                    let FragmentDefinition {
+                        type_condition,
+                        selection_set,
+                        ..
+                    } = &fragment.node;
+                    if visited.insert(name)
+                        && self
+                            .schema
+                            .is_possible(&type_condition.node.on.node, type_name)
+                    {
+                        self.collect_into(type_name, &selection_set.node, groups, visited);
+                    }
+                }
+                Selection::InlineFragment(fragment) => {
+                    let applies = fragment
+                        .node
+                        .type_condition
+                        .as_ref()
+                        .is_none_or(|x| self.schema.is_possible(&x.node.on.node, type_name));
+                    if applies {
+                        self.collect_into(
+                            type_name,
+                            &fragment.node.selection_set.node,
+                            groups,
+                            visited,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Execute the fields on the object (Err if the null of the non-null field makes it null)
+    fn execute(
+        &mut self,
+        type_name: &str,
+        parent: &Type,
+        groups: Groups<'a>,
+        path: &mut Vec<Value>,
+    ) -> Result<Value, ()> {
+        let mut object = Map::new();
+        for (key, fields) in groups {
+            let field = &fields[0].node;
+            if field.name.node == "__typename" {
+                object.insert(key, Value::String(type_name.to_string()));
+                continue;
+            }
+            let schema = self.schema;
+            let Some(definition) = schema.field(type_name, &field.name.node) else {
+                continue;
+            };
+            path.push(Value::String(key.clone()));
+            let value = self.resolve(type_name, parent, definition, &fields, path);
+            path.pop();
+            object.insert(key, value?);
+        }
+        Ok(Value::Object(object))
+    }
+
+    /// Get the value of the field by its resolver or the property of the parent
+    fn resolve(
+        &mut self,
+        type_name: &str,
+        parent: &Type,
+        definition: &FieldDefinition,
+        fields: &[&'a Positioned<Field>],
+        path: &mut Vec<Value>,
+    ) -> Result<Value, ()> {
+        let field = fields[0];
+        let name = definition.name.node.as_str();
+        let Some(code) = self.resolvers.get(&format!("{type_name}.{name}")) else {
+            let value = match parent {
+                Type::Object(_, object) => object.get(name).cloned().unwrap_or(Type::Null),
+                Type::Json(json) => Type::from_json(&json[name]),
+                Type::Ref(reference) => match reference.get() {
+                    Type::Object(_, object) => object.get(name).cloned().unwrap_or(Type::Null),
+                    _ => Type::Null,
+                },
+                _ => Type::Null,
+            };
+            return self.complete(&definition.ty.node, value, fields, path);
+        };
+
+        // Arguments given or their default values
+        let mut arguments = Map::new();
+        for argument in &definition.arguments {
+            let argument = &argument.node;
+            let value = match field.node.get_argument(&argument.name.node) {
+                Some(value) => self.input(&value.node),
+                None => match &argument.default_value {
+                    Some(value) => value.node.clone().into_json().unwrap_or_default(),
+                    None => continue,
+                },
+            };
+            arguments.insert(argument.name.node.to_string(), value);
+        }
+
+        // Resolver takes the parent and the arguments, and leaves the value
+        let depth = self.executor.stack.len();
+        self.executor.stack.push(parent.clone());
+        self.executor
+            .stack
+            .push(Type::from_json(&Value::Object(arguments)));
+        self.executor.evaluate_program(code.to_owned());
+        let value = match self.executor.stack.len() > depth {
+            true => self.executor.pop_stack(),
+            false => Type::Null,
+        };
+        self.executor.stack.truncate(depth);
+        // Error value is reported by its name without the message, as the HTTP routes do
+        if let Type::Error(err) = value {
+            self.errors
+                .push(error(format!("error:{}", err.name), field.pos, Some(path)));
+            return self.null(&definition.ty.node);
+        }
+        self.complete(&definition.ty.node, value, fields, path)
+    }
+
+    /// Null of the type (Err if it's non-null)
+    fn null(&mut self, ty: &FieldType) -> Result<Value, ()> {
+        match ty.nullable {
+            true => Ok(Value::Null),
+            false => Err(()),
+        }
+    }
+
+    /// Convert the value into the type, executing the sub-selections on the objects
+    fn complete(
+        &mut self,
+        ty: &FieldType,
+        value: Type,
+        fields: &[&'a Positioned<Field>],
+        path: &mut Vec<Value>,
+    ) -> Result<Value, ()> {
+        let value = match value {
+            Type::Ref(reference) => reference.get(),
+            Type::Json(json) if !json.is_object() => Type::from_json(&json),
+            value => value,
+        };
+        let pos = fields[0].pos;
+        if matches!(value, Type::Null) {
+            if !ty.nullable {
+                let message = format!("null is returned for the non-null type \"{ty}\"");
+                self.errors.push(error(message, pos, Some(path)));
+            }
+            return self.null(ty);
+        }
+
+        match &ty.base {
+            BaseType::List(inner) => {
+                let Type::List(items) = value else {
+                    let message =
+                        format!("{} is returned for the list type \"{ty}\"", value.display());
+                    self.errors.push(error(message, pos, Some(path)));
+                    return self.null(ty);
+                };
+                let mut list = Vec::new();
+                for (index, item) in items.iter().enumerate() {
+                    path.push(json!(index));
+                    let item = self.complete(inner, item.clone(), fields, path);
+                    path.pop();
+                    match item {
+                        Ok(item) => list.push(item),
+                        Err(()) => return self.null(ty),
+                    }
+                }
+                Ok(Value::Array(list))
+            }
+            BaseType::Named(name) if self.schema.is_leaf(name) => Ok(match name.as_str() {
+                "Int" => json!(value.get_number() as i64),
+                "Float" => json!(value.get_number()),
+                "Boolean" => json!(value.get_bool()),
+                "String" | "ID" => json!(value.get_string()),
+                _ => value.to_json(),
+            }),
+            BaseType::Named(name) => {
+                // Object type of the interface or the union by "__typename" or the class name
+                let object = match (
+                    &value,
+                    self.schema.types.get(name.as_str()).map(|x| &x.kind),
+                ) {
+                    (_, Some(TypeKind::Object(_))) => Some(name.to_string()),
+                    (Type::Object(class, object), _) => object
+                        .get("__typename")
+                        .map(|x| x.get_string())
+                        .or(Some(class.clone())),
+                    (Type::Json(json), _) => json["__typename"].as_str().map(|x| x.to_string()),
+                    _ => None,
+                }
+                .filter(|x| self.schema.is_possible(name, x));
+                let Some(object) = object else {
+                    let message = format!("object type of \"{name}\" can't be decided");
+                    self.errors.push(error(message, pos, Some(path)));
+                    return self.null(ty);
+                };
+
+                let sets: Vec<&'a SelectionSet> =
+                    fields.iter().map(|x| &x.node.selection_set.node).collect();
+                let groups = self.collect(&object, &sets);
+                match self.execute(&object, &value, groups, path) {
+                    Ok(value) => Ok(value),
+                    Err(()) => self.null(ty),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{Executor, Type};
+use std::collections::HashMap;
+
+/// Path of the liveness probe by default
+const HEALTH_PATH: &str = "/healthz";
+
+/// Path of the readiness probe by default
+const READY_PATH: &str = "/readyz";
+
+/// Endpoint answering whether the server works
+struct Probe {
+    path: String,          // Path of the endpoint
+    check: Option<String>, // Code pushing whether it passes (passes without it)
+}
+
+/// Health and readiness endpoints served by the server itself
+pub struct Probes(Vec<Probe>);
+
+impl Probes {
+    /// Read the paths (`health-path`, `ready-path`) and the checks (`health-check`, `ready-check`)
+    /// in the server options. The endpoint is disabled by the empty path
+    pub fn new(option: &HashMap<String, Type>) -> Probes {
+        let probe = |name: &str, default: &str| {
+            let path = match option.get(&format!("{name}-path")) {
+                Some(path) => path.get_string(),
+                None => default.to_string(),
+            };
+            let check = option.get(&format!("{name}-check")).map(|x| x.get_string());
+            (!path.is_empty()).then_some(Probe { path, check })
+        };
+        Probes(
+            [probe("health", HEALTH_PATH), probe("ready", READY_PATH)]
+                .into_iter()
+                .flatten()
+                .collect(),
+        )
+    }
+
+    /// Get the response if the request is for the endpoint.
+    /// The check fails when it pushes false or error value
+    pub fn respond(&self, executor: &mut Executor, method: &str, path: &str) -> Option<String> {
+        if method != "GET" {
+            return None;
+        }
+        let probe = self.0.iter().find(|x| x.path == path)?;
+        let passed = match &probe.check {
+            Some(code) => {
+                executor.evaluate_program(code.to_owned());
+                match executor.pop_stack() {
+                    Type::Error(_) => false,
+                    result => result.get_bool(),
+                }
+            }
+            None => true,
+        };
+        let (status, body) = match passed {
+            true => ("200 OK", "ok"),
+            false => ("503 Service Unavailable", "unavailable"),
+        };
+        Some(format!(
+            "HTTP/1.1 {status}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\n\r\n{body}",
+            body.len()
+        ))
+    }
+}
@@ -0,0 +1,289 @@
+use crate::commands::Commands;
+use crate::{header_value, negotiate, ErrorKind, Executor, StackError, Type};
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{LazyLock, RwLock};
+
+/// Locale used when none of the client's is available, unless the server sets it
+const DEFAULT_LOCALE: &str = "en";
+
+/// Plural categories of the messages
+const CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// Message of the catalog
+enum Message {
+    Text(String),                    // Message like "Hello, {name}!"
+    Plural(HashMap<String, String>), // Messages by the plural category like "one" or "other"
+}
+
+/// Translated messages of the locales
+struct Catalog {
+    messages: HashMap<String, HashMap<String, Message>>, // Messages by the key by the locale
+    locales: Vec<String>,                                // Locales loaded in the order of name
+    default: String,                                     // Locale to fall back on
+}
+
+static CATALOG: LazyLock<RwLock<Catalog>> = LazyLock::new(|| {
+    RwLock::new(Catalog {
+        messages: HashMap::new(),
+        locales: Vec::new(),
+        default: DEFAULT_LOCALE.to_string(),
+    })
+});
+
+thread_local! {
+    /// Locale of the template being rendered on the current thread
+    static CURRENT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Load the catalog files named by the locale like "en.toml" or "pt-BR.json" in the directory
+pub fn load_dir(dir: &str) -> Result<(), String> {
+    let mut messages = HashMap::new();
+    for entry in fs::read_dir(dir)
+        .map_err(|e| format!("{dir}: {e}"))?
+        .flatten()
+    {
+        let path = entry.path();
+        let (Some(locale), Some(extension)) = (
+            path.file_stem().map(|x| x.to_string_lossy().to_string()),
+            path.extension()
+                .map(|x| x.to_string_lossy().to_ascii_lowercase()),
+        ) else {
+            continue;
+        };
+        let source = || fs::read_to_string(&path).map_err(|e| e.to_string());
+        let catalog: Value = match extension.as_str() {
+            "toml" => toml::from_str(&source()?).map_err(|e| e.to_string()),
+            "json" => serde_json::from_str(&source()?).map_err(|e| e.to_string()),
+            _ => continue,
+        }
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+
+        let mut catalog_messages = HashMap::new();
+        flatten("", &catalog, &mut catalog_messages);
+        messages.insert(locale, catalog_messages);
+    }
+
+    let mut locales: Vec<String> = messages.keys().cloned().collect();
+    locales.sort();
+    let mut catalog = CATALOG.write().unwrap_or_else(|e| e.into_inner());
+    catalog.messages = messages;
+    catalog.locales = locales;
+    Ok(())
+}
+
+/// Set the locale to fall back on
+pub fn set_default(locale: &str) {
+    CATALOG.write().unwrap_or_else(|e| e.into_inner()).default = locale.to_string();
+}
+
+/// Whether the table is the plural forms, having "other" and only the categories of texts
+fn is_plural(table: &Map<String, Value>) -> bool {
+    table.contains_key("other")
+        && table
+            .iter()
+            .all(|(key, value)| CATEGORIES.contains(&key.as_str()) && !value.is_object())
+}
+
+/// Flatten the nested tables into the keys joined by dot like "cart.title",
+/// except the tables of the plural forms
+fn flatten(prefix: &str, value: &Value, messages: &mut HashMap<String, Message>) {
+    match value {
+        Value::Object(table) if !prefix.is_empty() && is_plural(table) => {
+            let forms = table
+                .iter()
+                .map(|(category, x)| (category.clone(), text(x)))
+                .collect();
+            messages.insert(prefix.to_string(), Message::Plural(forms));
+        }
+        Value::Object(table) => {
+            for (key, value) in table {
+                let key = match prefix {
+                    "" => key.clone(),
+                    _ => format!("{prefix}.{key}"),
+                };
+                flatten(&key, value, messages);
+            }
+        }
+        value => {
+            messages.insert(prefix.to_string(), Message::Text(text(value)));
+        }
+    }
+}
+
+/// Get the value as the text without the quotes of the string
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Get the locale of the request by its Accept-Language header, or the default one
+pub fn locale(executor: &Executor) -> String {
+    let catalog = CATALOG.read().unwrap_or_else(|e| e.into_inner());
+    let accept = executor
+        .request
+        .as_ref()
+        .and_then(|x| header_value(&x.head, "Accept-Language"));
+    negotiate::choose_language(accept, &catalog.locales)
+        .unwrap_or(&catalog.default)
+        .to_string()
+}
+
+/// Get the plural category of the count by the rules of the language
+fn plural(locale: &str, count: f64) -> &'static str {
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+    let integer = count.fract() == 0.0;
+    let (i, mod10, mod100) = (count.trunc(), count % 10.0, count % 100.0);
+    let few = integer && (2.0..=4.0).contains(&mod10) && !(12.0..=14.0).contains(&mod100);
+    match language {
+        // Languages without the plural forms
+        "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "tr" => "other",
+        "fr" | "pt" if i == 0.0 || i == 1.0 => "one",
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" | "pl" if !integer => "other",
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" if mod10 == 1.0 && mod100 != 11.0 => "one",
+        "pl" if count == 1.0 => "one",
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" | "pl" if few => "few",
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" | "pl" => "many",
+        "cs" | "sk" if integer && (2.0..=4.0).contains(&count) => "few",
+        "cs" | "sk" if !integer => "many",
+        "ar" if count == 0.0 => "zero",
+        "ar" if count == 2.0 => "two",
+        "ar" if integer && (3.0..=10.0).contains(&mod100) => "few",
+        "ar" if integer && (11.0..=99.0).contains(&mod100) => "many",
+        _ if count == 1.0 => "one",
+        _ => "other",
+    }
+}
+
+/// Translate the message of the key into the locale, falling back on its language,
+/// the default locale and the key itself. The parameters fill the placeholders like "{name}",
+/// and "count" chooses the plural form ("zero" is taken for 0 if the message has it)
+pub fn translate(locale: &str, key: &str, params: &Map<String, Value>) -> String {
+    let catalog = CATALOG.read().unwrap_or_else(|e| e.into_inner());
+    let language = locale.split(['-', '_']).next().unwrap_or(locale);
+    let found = [locale, language, &catalog.default]
+        .into_iter()
+        .find_map(|x| Some((x, catalog.messages.get(x)?.get(key)?)));
+    let Some((found_locale, message)) = found else {
+        return key.to_string();
+    };
+
+    let message = match message {
+        Message::Text(text) => text.clone(),
+        Message::Plural(forms) => {
+            let count = params.get("count").and_then(|x| x.as_f64()).unwrap_or(0.0);
+            let category = match forms.contains_key("zero") && count == 0.0 {
+                true => "zero",
+                false => plural(found_locale, count),
+            };
+            forms
+                .get(category)
+                .or_else(|| forms.get("other"))
+                .cloned()
+                .unwrap_or_default()
+        }
+    };
+    fill(&message, params)
+}
+
+/// Fill the placeholders like "{name}" in the message by the parameters in a single pass,
+/// leaving the unknown ones and not filling the values again
+fn fill(message: &str, params: &Map<String, Value>) -> String {
+    let mut filled = String::new();
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest
+            .find('}')
+            .and_then(|end| Some((end, params.get(&rest[1..end])?)));
+        match value {
+            Some((end, value)) => {
+                filled.push_str(&text(value));
+                rest = &rest[end + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// Render with the locale, so that the templates translate into it
+pub fn scoped<T>(locale: String, render: impl FnOnce() -> T) -> T {
+    let _scope = Scope(CURRENT.with(|x| x.replace(Some(locale))));
+    render()
+}
+
+/// Locale before rendering, restored when it's dropped (even if the rendering panics)
+struct Scope(Option<String>);
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        CURRENT.with(|x| *x.borrow_mut() = self.0.take());
+    }
+}
+
+/// Template function like `{{ t(key="cart.items", count=3) }}`
+/// translating into the locale of the request unless `locale` is given
+pub fn function(args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let key = args
+        .get("key")
+        .and_then(|x| x.as_str())
+        .ok_or_else(|| tera::Error::msg("t: \"key\" is required"))?;
+    let locale = match args.get("locale").and_then(|x| x.as_str()) {
+        Some(locale) => locale.to_string(),
+        None => CURRENT.with(|x| x.borrow().clone()).unwrap_or_else(|| {
+            let catalog = CATALOG.read().unwrap_or_else(|e| e.into_inner());
+            catalog.default.clone()
+        }),
+    };
+    let params: Map<String, Value> = args
+        .iter()
+        .filter(|(name, _)| !matches!(name.as_str(), "key" | "locale"))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    Ok(Value::String(translate(&locale, key, &params)))
+}
+
+/// Register the commands of the translation
+pub fn register_commands(commands: &mut Commands) {
+    // Load the catalog files of the locales in the directory
+    commands.add("locale-dir", |executor| {
+        let dir = executor.pop_stack().get_string();
+        load_dir(&dir).map_err(|e| StackError::with_kind(ErrorKind::Io, "locale-dir", e))
+    });
+
+    // Get the locale of the request being handled
+    commands.add("locale", |executor| {
+        let locale = locale(executor);
+        executor.stack.push(Type::String(locale.into()));
+        Ok(())
+    });
+
+    // Translate the message of the key with the parameters like `[(count) 3]`
+    // (the locale of the request unless the parameters have "locale")
+    commands.add("t", |executor| {
+        let params = crate::route_options(&executor.pop_stack());
+        let key = executor.pop_stack().get_string();
+        let locale = match params.get("locale") {
+            Some(locale) => locale.get_string(),
+            None => locale(executor),
+        };
+        let params = params
+            .into_iter()
+            .filter(|(name, _)| name != "locale")
+            .map(|(name, value)| (name, value.to_json()))
+            .collect();
+        let message = translate(&locale, &key, &params);
+        executor.stack.push(Type::String(message.into()));
+        Ok(())
+    });
+}
@@ -0,0 +1,222 @@
+use crate::{Executor, Type};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Condvar, LazyLock, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Longest seconds before the retry, however many times the backoff is doubled
+const MAX_BACKOFF: f64 = 3600.0;
+
+/// Settings of the workers
+#[derive(Clone, Copy)]
+struct Policy {
+    workers: usize, // Count of worker threads
+    retries: usize, // Count of retries after the failure
+    backoff: f64,   // Seconds before the first retry (doubled at each retry)
+    ttl: f64,       // Seconds to keep the finished job for its status
+}
+
+/// Job registered in the queue
+struct Job {
+    code: String,       // Code to run
+    payload: Type,      // Value pushed before running
+    executor: Executor, // Executor that enqueued the job
+    status: String,     // queued, running, retrying, done or failed
+    attempts: usize,    // Count of runs
+    result: Type,       // Top of the stack after the last run
+    finished: f64,      // Time when it's done or failed (0 until then)
+}
+
+/// State of the queue
+struct State {
+    last: usize,                     // Last identifier
+    jobs: HashMap<usize, Job>,       // Registered jobs
+    pending: VecDeque<(usize, f64)>, // Jobs waiting to run and time not to run before
+    spawned: usize,                  // Count of spawned workers
+    policy: Policy,                  // Settings of the workers
+}
+
+/// Queue of background jobs
+struct Queue {
+    state: Mutex<State>, // State of the queue
+    signal: Condvar,     // Wake up when a job is pending
+}
+
+static QUEUE: LazyLock<Queue> = LazyLock::new(|| Queue {
+    state: Mutex::new(State {
+        last: 0,
+        jobs: HashMap::new(),
+        pending: VecDeque::new(),
+        spawned: 0,
+        policy: Policy {
+            workers: 4,
+            retries: 3,
+            backoff: 1.0,
+            ttl: 3600.0,
+        },
+    }),
+    signal: Condvar::new(),
+});
+
+/// Get now time as unix epoch
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+/// Lock the state of the queue
+fn lock() -> MutexGuard<'static, State> {
+    QUEUE.state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Configure the workers by options object
+pub fn configure(option: &Type) {
+    let (_, value) = option.get_object();
+    let mut state = lock();
+    let policy = &mut state.policy;
+    if let Some(workers) = value.get("workers") {
+        policy.workers = (workers.get_number() as usize).max(1);
+    }
+    if let Some(retries) = value.get("retries") {
+        policy.retries = retries.get_number() as usize;
+    }
+    if let Some(backoff) = value.get("backoff") {
+        policy.backoff = backoff.get_number().max(0.0);
+    }
+    if let Some(ttl) = value.get("ttl") {
+        policy.ttl = ttl.get_number().max(0.0);
+    }
+}
+
+/// Remove the finished jobs kept longer than the TTL
+fn expire(state: &mut State) {
+    let (time, ttl) = (now(), state.policy.ttl);
+    state
+        .jobs
+        .retain(|_, job| job.finished == 0.0 || time - job.finished < ttl);
+}
+
+/// Register the job, and return its id
+pub fn enqueue(code: String, payload: Type, mut executor: Executor) -> usize {
+    // The job starts with only the payload on the stack
+    executor.stack.clear();
+
+    let mut state = lock();
+    expire(&mut state);
+    state.last += 1;
+    let id = state.last;
+    state.jobs.insert(
+        id,
+        Job {
+            code,
+            payload,
+            executor,
+            status: "queued".to_string(),
+            attempts: 0,
+            result: Type::Null,
+            finished: 0.0,
+        },
+    );
+    state.pending.push_back((id, 0.0));
+
+    // Spawn workers up to the configured count
+    while state.spawned < state.policy.workers {
+        state.spawned += 1;
+        thread::spawn(work);
+    }
+    QUEUE.signal.notify_one();
+    id
+}
+
+/// Get status object of the job, unless it's expired
+pub fn status(id: usize) -> Option<Type> {
+    let mut state = lock();
+    expire(&mut state);
+    let job = state.jobs.get(&id)?;
+    Some(Type::Object(
+        "job".to_string(),
+        HashMap::from([
+            ("id".to_string(), Type::Number(id as f64)),
+            (
+                "status".to_string(),
+                Type::String(job.status.clone().into()),
+            ),
+            ("attempts".to_string(), Type::Number(job.attempts as f64)),
+            ("result".to_string(), job.result.clone()),
+        ]),
+    ))
+}
+
+/// Main loop of the worker thread
+fn work() {
+    let mut state = lock();
+    loop {
+        // Take the first job that is ready to run
+        let time = now();
+        let Some(index) = state.pending.iter().position(|(_, after)| *after <= time) else {
+            let wait = state
+                .pending
+                .iter()
+                .map(|(_, after)| after - time)
+                .min_by(|a, b| a.total_cmp(b));
+            state = match wait {
+                Some(wait) => {
+                    QUEUE
+                        .signal
+                        .wait_timeout(state, Duration::try_from_secs_f64(wait).unwrap_or_default())
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0
+                }
+                None => QUEUE.signal.wait(state).unwrap_or_else(|e| e.into_inner()),
+            };
+            continue;
+        };
+        let Some((id, _)) = state.pending.remove(index) else {
+            continue;
+        };
+        let Some(job) = state.jobs.get_mut(&id) else {
+            continue;
+        };
+        job.status = "running".to_string();
+        job.attempts += 1;
+        let (code, payload, mut executor) =
+            (job.code.clone(), job.payload.clone(), job.executor.clone());
+        drop(state);
+
+        // Run the job without locking the queue
+        executor.stack.push(payload);
+        executor.evaluate_program(code);
+        let failed = executor
+            .stack
+            .iter()
+            .find(|x| matches!(x, Type::Error(_)))
+            .cloned();
+
+        state = lock();
+        let policy = state.policy;
+        let Some(job) = state.jobs.get_mut(&id) else {
+            continue;
+        };
+        match failed {
+            None => {
+                job.status = "done".to_string();
+                job.result = executor.stack.pop().unwrap_or(Type::Null);
+                job.finished = now();
+            }
+            Some(err) if job.attempts <= policy.retries => {
+                job.status = "retrying".to_string();
+                job.result = err;
+                let delay = (policy.backoff * 2f64.powi(job.attempts as i32 - 1)).min(MAX_BACKOFF);
+                state.pending.push_back((id, now() + delay));
+                QUEUE.signal.notify_one();
+            }
+            Some(err) => {
+                job.status = "failed".to_string();
+                job.result = err;
+                job.finished = now();
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{Executor, Type};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Codes of the errors defined by JSON-RPC 2.0
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// Name of the error raised by the method for the invalid parameters
+const INVALID_PARAMS_ERROR: &str = "invalid-params";
+
+/// Methods of the JSON-RPC 2.0 route implemented by the Stack code
+pub struct Methods {
+    methods: HashMap<String, String>, // Code by the method name
+}
+
+/// Make the error response
+fn error(id: Value, code: i64, message: &str, data: Option<Value>) -> Value {
+    let mut error = json!({"code": code, "message": message});
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    json!({"jsonrpc": "2.0", "error": error, "id": id})
+}
+
+impl Methods {
+    /// Make the methods from the pairs of the name and the code
+    pub fn new(methods: Vec<(String, String)>) -> Methods {
+        Methods {
+            methods: methods.into_iter().collect(),
+        }
+    }
+
+    /// Call the methods of the request or the batch, and get the response
+    /// (None if all of them are notifications)
+    pub fn respond(&self, executor: &mut Executor, body: &[u8]) -> Option<Value> {
+        let request: Value = match serde_json::from_slice(body) {
+            Ok(request) => request,
+            Err(e) => {
+                return Some(error(
+                    Value::Null,
+                    PARSE_ERROR,
+                    "Parse error",
+                    Some(json!(e.to_string())),
+                ))
+            }
+        };
+        match request {
+            Value::Array(batch) if batch.is_empty() => {
+                Some(error(Value::Null, INVALID_REQUEST, "Invalid Request", None))
+            }
+            Value::Array(batch) => {
+                let responses: Vec<Value> = batch
+                    .into_iter()
+                    .filter_map(|request| self.call(executor, request))
+                    .collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            request => self.call(executor, request),
+        }
+    }
+
+    /// Call the method of the request (None if it's a notification without "id")
+    fn call(&self, executor: &mut Executor, request: Value) -> Option<Value> {
+        let invalid = |id| Some(error(id, INVALID_REQUEST, "Invalid Request", None));
+        let Value::Object(request) = request else {
+            return invalid(Value::Null);
+        };
+        let id = request.get("id").cloned();
+        let valid_id = id
+            .as_ref()
+            .is_none_or(|x| x.is_string() || x.is_number() || x.is_null());
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+        let (Some("2.0"), Some(method), true, true) = (
+            request.get("jsonrpc").and_then(|x| x.as_str()),
+            request.get("method").and_then(|x| x.as_str()),
+            valid_id,
+            matches!(params, Value::Array(_) | Value::Object(_) | Value::Null),
+        ) else {
+            return invalid(id.filter(|_| valid_id).unwrap_or(Value::Null));
+        };
+
+        let Some(code) = self.methods.get(method) else {
+            let id = id?;
+            return Some(error(
+                id,
+                METHOD_NOT_FOUND,
+                "Method not found",
+                Some(json!(method)),
+            ));
+        };
+
+        // Method takes the parameters and leaves the result
+        let depth = executor.stack.len();
+        executor.stack.push(Type::from_json(&params));
+        executor.evaluate_program(code.to_owned());
+        let result = match executor.stack.len() > depth {
+            true => executor.pop_stack(),
+            false => Type::Null,
+        };
+        executor.stack.truncate(depth);
+
+        let id = id?;
+        Some(match result {
+            Type::Error(err) if err.name == INVALID_PARAMS_ERROR => error(
+                id,
+                INVALID_PARAMS,
+                "Invalid params",
+                Some(json!(err.message)),
+            ),
+            // Other error values are reported by the name without the message like HTTP routes
+            Type::Error(err) => error(id, SERVER_ERROR, "Server error", Some(json!(err.name))),
+            result => json!({"jsonrpc": "2.0", "result": result.to_json(), "id": id}),
+        })
+    }
+}
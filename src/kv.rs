@@ -0,0 +1,69 @@
+use crate::Type;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Mutex;
+
+/// Connection to the key-value store
+static STORE: Mutex<Option<Connection>> = Mutex::new(None);
+
+/// Run the process with the store connection
+fn with_store<R>(process: impl FnOnce(&Connection) -> rusqlite::Result<R>) -> Result<R, String> {
+    let store = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    match store.as_ref() {
+        Some(connection) => process(connection).map_err(|e| e.to_string()),
+        None => Err("the key-value store is not configured".to_string()),
+    }
+}
+
+/// Open the store file as the key-value store
+pub fn open(path: &str) -> Result<(), String> {
+    let connection = Connection::open(path).map_err(|e| e.to_string())?;
+    connection
+        .execute_batch("CREATE TABLE IF NOT EXISTS kv (key TEXT PRIMARY KEY, value BLOB NOT NULL)")
+        .map_err(|e| e.to_string())?;
+    *STORE.lock().unwrap_or_else(|e| e.into_inner()) = Some(connection);
+    Ok(())
+}
+
+/// Set value of the key
+pub fn set(key: &str, value: &Type) -> Result<(), String> {
+    let value = rmp_serde::to_vec(value).map_err(|e| e.to_string())?;
+    with_store(|store| {
+        store.execute(
+            "INSERT INTO kv (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )
+    })
+    .map(|_| ())
+}
+
+/// Get value of the key
+pub fn get(key: &str) -> Result<Option<Type>, String> {
+    let value: Option<Vec<u8>> = with_store(|store| {
+        store
+            .query_row("SELECT value FROM kv WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()
+    })?;
+    match value {
+        Some(value) => rmp_serde::from_slice(&value)
+            .map(Some)
+            .map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
+}
+
+/// Delete the key, and return whether it existed
+pub fn delete(key: &str) -> Result<bool, String> {
+    with_store(|store| store.execute("DELETE FROM kv WHERE key = ?1", [key])).map(|x| x > 0)
+}
+
+/// Get all of keys
+pub fn keys() -> Result<Vec<String>, String> {
+    with_store(|store| {
+        let mut stmt = store.prepare("SELECT key FROM kv ORDER BY key")?;
+        let keys = stmt.query_map([], |row| row.get(0))?;
+        keys.collect()
+    })
+}
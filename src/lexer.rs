@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// Count of programs to keep parsed
+const PROGRAM_CACHE: usize = 1024;
+
+/// Bytes of the sources and the tokens to keep parsed in total
+const PROGRAM_CACHE_SIZE: usize = 16 << 20;
+
+/// Length of the longest source to keep parsed (longer ones are parsed every time)
+const PROGRAM_SIZE: usize = 64 << 10;
+
+/// Bytes kept in total and the parsed programs by the source
+type Programs = (usize, HashMap<String, Arc<[Token]>>);
+
+static PROGRAMS: LazyLock<RwLock<Programs>> = LazyLock::new(|| RwLock::new((0, HashMap::new())));
+
+/// Position of the token in the source
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,  // Byte offset of the first character
+    pub end: usize,    // Byte offset next to the last character
+    pub line: usize,   // Line number of the first character from 1
+    pub column: usize, // Column number of the first character from 1
+}
+
+impl Span {
+    /// Locate the span of the token in the body of the outer token (like the list)
+    pub fn within(self, outer: Span) -> Span {
+        let offset = outer.start + 1;
+        Span {
+            start: offset + self.start,
+            end: offset + self.end,
+            line: outer.line + self.line - 1,
+            column: match self.line {
+                1 => outer.column + self.column,
+                _ => self.column,
+            },
+        }
+    }
+}
+
+/// Token with its position in the source
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub text: String,
+    pub span: Span,
+}
+
+/// Character giving the nest structure of the source
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Delimiter {
+    OpenString,           // "(" opening the string
+    CloseString,          // ")" closing the string
+    OpenList,             // "[" opening the list
+    CloseList,            // "]" closing the list
+    Comment,              // "#" opening or closing the comment
+    Escape(Option<char>), // Backslash with the character it escapes (none at the end)
+}
+
+/// Characters read as a space
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\n' | '\t' | '\r' | '　')
+}
+
+/// Read the source into tokens (or a token without splitting by spaces),
+/// keeping the strings, lists and comments as they are, and collecting their delimiters if asked
+fn scan(
+    code: &str,
+    split: bool,
+    mut delimiters: Option<&mut Vec<(Delimiter, Span)>>,
+) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut buffer = String::new(); // Temporary storage
+    let mut span = Span::default(); // Position of the token in the buffer
+    let mut brackets = 0; // String's nest structure
+    let mut parentheses = 0; // List's nest structure
+    let mut hash = false; // Is it Comment
+    let mut escape = false; // Flag to indicate next character is escaped
+    let (mut line, mut column) = (1, 0);
+    let mut backslash = Span::default(); // Position of the escaping backslash
+
+    for (index, raw) in code.char_indices() {
+        if raw == '\n' {
+            (line, column) = (line + 1, 0);
+        } else {
+            column += 1;
+        }
+        if buffer.is_empty() && !escape {
+            span = Span {
+                start: index,
+                end: index,
+                line,
+                column,
+            };
+        }
+        span.end = index + raw.len_utf8();
+
+        let position = Span {
+            start: index,
+            end: index + raw.len_utf8(),
+            line,
+            column,
+        };
+        let mut delimit = |delimiter: Delimiter, span: Span| {
+            if let Some(delimiters) = delimiters.as_deref_mut() {
+                delimiters.push((delimiter, span));
+            }
+        };
+        if escape {
+            delimit(Delimiter::Escape(Some(raw)), backslash);
+        }
+
+        // Tabs, line breaks, and full-width spaces are read as half-width spaces
+        let c = if is_space(raw) { ' ' } else { raw };
+        match c {
+            '\\' if !escape => {
+                escape = true;
+                backslash = position;
+            }
+            '(' if !hash && !escape => {
+                brackets += 1;
+                buffer.push('(');
+                delimit(Delimiter::OpenString, position);
+            }
+            ')' if !hash && !escape => {
+                brackets -= 1;
+                buffer.push(')');
+                delimit(Delimiter::CloseString, position);
+            }
+            '#' if !escape => {
+                hash = !hash;
+                buffer.push('#');
+                delimit(Delimiter::Comment, position);
+            }
+            '[' if !hash && brackets == 0 && !escape => {
+                parentheses += 1;
+                buffer.push('[');
+                delimit(Delimiter::OpenList, position);
+            }
+            ']' if !hash && brackets == 0 && !escape => {
+                parentheses -= 1;
+                buffer.push(']');
+                delimit(Delimiter::CloseList, position);
+            }
+            ' ' if split && !hash && parentheses == 0 && brackets == 0 && !escape => {
+                if !buffer.is_empty() {
+                    span.end = index;
+                    tokens.push(Token {
+                        text: std::mem::take(&mut buffer),
+                        span,
+                    });
+                }
+            }
+            _ => {
+                if parentheses == 0 && brackets == 0 && !hash {
+                    if escape {
+                        match c {
+                            'n' => buffer.push_str("\\n"),
+                            't' => buffer.push_str("\\t"),
+                            'r' => buffer.push_str("\\r"),
+                            _ => buffer.push(c),
+                        }
+                    } else {
+                        buffer.push(c);
+                    }
+                } else {
+                    if escape {
+                        buffer.push('\\');
+                    }
+                    // Keep the nested structures as written to locate their tokens later
+                    buffer.push(if split { raw } else { c });
+                }
+                escape = false; // Reset escape flag for non-escape characters
+            }
+        }
+    }
+
+    if escape {
+        if let Some(delimiters) = delimiters {
+            delimiters.push((Delimiter::Escape(None), backslash));
+        }
+    }
+    if !buffer.is_empty() {
+        tokens.push(Token { text: buffer, span });
+    }
+    tokens
+}
+
+/// Split the source into tokens
+pub fn tokenize(code: &str) -> Vec<Token> {
+    scan(code, true, None)
+}
+
+/// Get the delimiters of the strings, lists, comments and escapes in the order of the source,
+/// read in the same way as the tokens
+pub fn delimiters(code: &str) -> Vec<(Delimiter, Span)> {
+    let mut delimiters = Vec::new();
+    scan(code, true, Some(&mut delimiters));
+    delimiters
+}
+
+/// Get the tokens of the code, reusing the parsed ones for the same code
+pub fn parse(code: &str) -> Arc<[Token]> {
+    if let Some(tokens) = PROGRAMS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .1
+        .get(code)
+    {
+        return tokens.clone();
+    }
+
+    let tokens: Arc<[Token]> = tokenize(code).into();
+    if code.len() > PROGRAM_SIZE {
+        return tokens;
+    }
+    let size = code.len()
+        + tokens
+            .iter()
+            .map(|x| x.text.len() + std::mem::size_of::<Token>())
+            .sum::<usize>();
+    let mut programs = PROGRAMS.write().unwrap_or_else(|e| e.into_inner());
+    // Start over instead of growing without limit by the generated code
+    if programs.1.len() >= PROGRAM_CACHE || programs.0 + size > PROGRAM_CACHE_SIZE {
+        *programs = (0, HashMap::new());
+    }
+    if programs
+        .1
+        .insert(code.to_string(), tokens.clone())
+        .is_none()
+    {
+        programs.0 += size;
+    }
+    tokens
+}
+
+/// Process the escapes in the body of the string literal
+pub fn unescape(body: &str) -> String {
+    scan(body, false, None)
+        .into_iter()
+        .map(|token| token.text)
+        .collect()
+}
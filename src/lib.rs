@@ -0,0 +1,4917 @@
+//! Stack programming language with the server, usable as the command-line interface
+//! or embedded in the Rust programs:
+//!
+//! ```
+//! use stack_server::{EvalError, Executor, Mode};
+//!
+//! let mut executor = Executor::new(Mode::Quiet);
+//! executor.set_var("price", &120).unwrap();
+//! let values = executor.eval("price 2 mul").unwrap();
+//! assert_eq!(values, vec![serde_json::json!(240)]);
+//!
+//! // `exit` stops the evaluation without exiting the host process
+//! assert_eq!(executor.eval("1 exit 2"), Err(EvalError::Exit(1)));
+//! ```
+
+mod apikey;
+mod bench;
+mod bundle;
+mod check;
+mod commands;
+mod completions;
+mod config;
+mod database;
+mod error;
+mod files;
+mod fmt;
+mod form;
+mod future;
+mod global;
+mod graphql;
+mod health;
+mod i18n;
+mod jobs;
+mod jsonrpc;
+mod kv;
+mod lexer;
+mod livereload;
+mod lockout;
+mod logger;
+mod mail;
+mod mqtt;
+mod negotiate;
+mod password;
+mod plugin;
+mod proxy;
+mod request;
+mod routes;
+mod scheduler;
+mod schema;
+mod shared;
+mod statics;
+mod stats;
+mod tcp;
+mod telemetry;
+mod templates;
+mod testing;
+mod threads;
+mod tls;
+mod upload;
+mod wasm;
+mod watch;
+mod webhook;
+mod ws;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::{App, Arg, ArgMatches, ValueHint};
+use health::Probes;
+use lexer::Token;
+use notify::Watcher;
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use rusqlite::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufReader, IsTerminal, Read};
+use std::io::{Error, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, sleep};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sys_info::{cpu_num, cpu_speed, hostname, mem_info, os_release, os_type};
+
+pub use commands::register_command;
+pub use error::{ErrorKind, StackError};
+pub use lexer::Span;
+pub use shared::Shared;
+
+/// Options to give the program
+fn program_args() -> Vec<Arg<'static>> {
+    vec![
+        Arg::new("script")
+            .index(1)
+            .value_name("FILE")
+            .value_hint(ValueHint::FilePath)
+            .help("Sets the server program file to execution (\"-\" reads standard input)")
+            .takes_value(true),
+        Arg::new("args")
+            .index(2)
+            .value_name("ARGS")
+            .help("Passes the arguments after \"--\" to the program")
+            .multiple_values(true)
+            .last(true),
+        Arg::new("eval")
+            .short('e')
+            .long("eval")
+            .value_name("CODE")
+            .help("Executes the inline program instead of the file")
+            .takes_value(true)
+            .conflicts_with("script"),
+    ]
+}
+
+/// Options of the log level
+fn level_args() -> Vec<Arg<'static>> {
+    vec![
+        Arg::new("debug")
+            .short('d')
+            .long("debug")
+            .help("Enables debug mode (same as -v)"),
+        Arg::new("verbose")
+            .short('v')
+            .long("verbose")
+            .help("Shows the stack while executing (-vv also dumps HTTP messages)")
+            .multiple_occurrences(true),
+        Arg::new("quiet")
+            .short('q')
+            .long("quiet")
+            .help("Shows only errors and the program's output")
+            .conflicts_with_all(&["debug", "verbose"]),
+        Arg::new("tracing")
+            .long("tracing")
+            .value_name("FILTER")
+            .help("Prints the spans of connections, requests and routes (and commands with -v) enabled by the filter like \"info\" into standard error")
+            .takes_value(true),
+        Arg::new("tracing-format")
+            .long("tracing-format")
+            .value_name("FORMAT")
+            .help("Sets the format of the tracing output [default: full]")
+            .possible_values(["full", "compact", "pretty", "json"])
+            .takes_value(true)
+            .requires("tracing"),
+        Arg::new("otlp")
+            .long("otlp")
+            .value_name("ENDPOINT")
+            .help("Exports the spans and the request metrics to the OpenTelemetry collector by OTLP/HTTP like \"http://localhost:4318\"")
+            .takes_value(true),
+        Arg::new("strict")
+            .long("strict")
+            .help("Makes stack underflow an error in the REPL too (default when running a program)"),
+    ]
+}
+
+/// Options of the environment variables, the configuration file and the plugins
+fn environment_args() -> Vec<Arg<'static>> {
+    vec![
+        Arg::new("env-file")
+            .long("env-file")
+            .value_name("PATH")
+            .help("Loads environment variables from the file before execution [default: .env]")
+            .takes_value(true),
+        Arg::new("config")
+            .long("config")
+            .value_name("PATH")
+            .help("Loads the configuration file [default: stack-server.toml]")
+            .takes_value(true),
+        Arg::new("plugin")
+            .long("plugin")
+            .value_name("PATH")
+            .help("Loads the shared library or WASM module adding commands before execution")
+            .takes_value(true)
+            .multiple_occurrences(true),
+    ]
+}
+
+/// Options of the server and the watch mode
+fn server_args() -> Vec<Arg<'static>> {
+    vec![
+        Arg::new("check")
+            .long("check")
+            .help("Validates syntax of the program without executing"),
+        Arg::new("bind")
+            .long("bind")
+            .value_name("HOST")
+            .help("Overrides the host that the server listens on")
+            .takes_value(true),
+        Arg::new("port")
+            .long("port")
+            .value_name("PORT")
+            .help("Overrides the port that the server listens on")
+            .takes_value(true),
+        Arg::new("watch")
+            .long("watch")
+            .help("Restarts the program whenever the script or the watched paths change, and reloads the pages it serves"),
+        Arg::new("watch-path")
+            .long("watch-path")
+            .value_name("PATH")
+            .help("Adds the file or directory to watch in the watch mode")
+            .takes_value(true)
+            .multiple_occurrences(true),
+    ]
+}
+
+/// Define the command-line interface
+fn cli() -> App<'static> {
+    App::new("Stack Server")
+        .version("0.1")
+        .author("Stack Programming Community")
+        .about("Server edition of Stack programming language distribution ")
+        // Running the script is the default command
+        .args(program_args())
+        .args(level_args())
+        .args(environment_args())
+        .args(server_args())
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            App::new("run")
+                .about("Executes the program (default command)")
+                .args(program_args())
+                .args(level_args())
+                .args(environment_args())
+                .args(server_args()),
+        )
+        .subcommand(
+            App::new("serve")
+                .about("Executes the server program")
+                .args(program_args())
+                .mut_arg("script", |x| x.required_unless_present("eval"))
+                .args(level_args())
+                .args(environment_args())
+                .args(server_args()),
+        )
+        .subcommand(
+            App::new("repl")
+                .about("Starts the interactive session")
+                .args(level_args())
+                .args(environment_args()),
+        )
+        .subcommand(
+            App::new("test")
+                .about("Runs the test programs (*.test.stk) [default: tests]")
+                .arg(
+                    Arg::new("paths")
+                        .value_name("PATH")
+                        .multiple_values(true)
+                        .help("Sets the test files or directories including them"),
+                )
+                .args(level_args())
+                .args(environment_args()),
+        )
+        .subcommand(
+            App::new("check")
+                .about("Validates syntax of the program without executing")
+                .args(program_args()),
+        )
+        .subcommand(
+            App::new("routes")
+                .about("Lists the routes of the server program without listening or side effects")
+                .args(program_args())
+                .args(level_args())
+                .args(environment_args()),
+        )
+        .subcommand(
+            App::new("bench")
+                .about("Measures throughput and latency of the program, code blocks or requests")
+                .args(program_args())
+                .arg(
+                    Arg::new("iterations")
+                        .short('n')
+                        .long("iterations")
+                        .value_name("N")
+                        .help("Sets the number of the measured runs of each target [default: 100]")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("warmup")
+                        .long("warmup")
+                        .value_name("N")
+                        .help("Sets the number of the runs before measuring [default: 10]")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("code")
+                        .short('c')
+                        .long("code")
+                        .value_name("CODE")
+                        .help("Measures the code evaluated after the program instead of the program")
+                        .takes_value(true)
+                        .multiple_occurrences(true),
+                )
+                .arg(
+                    Arg::new("request")
+                        .short('r')
+                        .long("request")
+                        .value_name("REQUEST")
+                        .help("Measures the request like \"GET /\" to the server started by the program")
+                        .takes_value(true)
+                        .multiple_occurrences(true)
+                        .conflicts_with("code"),
+                )
+                .arg(
+                    Arg::new("body")
+                        .long("body")
+                        .value_name("TEXT")
+                        .help("Sets the body of the requests")
+                        .takes_value(true)
+                        .requires("request"),
+                )
+                .args(level_args())
+                .args(environment_args()),
+        )
+        .subcommand(
+            App::new("bundle")
+                .about("Packs the program and its assets into a file that can be run directly")
+                .arg(
+                    Arg::new("script")
+                        .value_name("FILE")
+                        .value_hint(ValueHint::FilePath)
+                        .help("Sets the main program file")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("include")
+                        .short('i')
+                        .long("include")
+                        .value_name("PATH")
+                        .help("Adds the file or directory of the assets")
+                        .takes_value(true)
+                        .multiple_occurrences(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("PATH")
+                        .help("Sets the path of the bundle [default: <FILE>.stkb]")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            App::new("completions")
+                .about("Prints the completion script of the shell")
+                .arg(
+                    Arg::new("shell")
+                        .value_name("SHELL")
+                        .possible_values(["bash", "zsh", "fish", "elvish", "powershell"])
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            App::new("fmt")
+                .about("Formats the program files (standard input if no file is given)")
+                .arg(
+                    Arg::new("files")
+                        .value_name("FILE")
+                        .multiple_values(true)
+                        .help("Sets the program files to format in place"),
+                )
+                .arg(
+                    Arg::new("check")
+                        .long("check")
+                        .help("Shows the difference without writing, and fails if unformatted"),
+                )
+                .arg(
+                    Arg::new("stdout")
+                        .long("stdout")
+                        .help("Prints the formatted programs instead of writing"),
+                ),
+        )
+}
+
+/// Print the error and exit the process with failure
+fn fail(err: impl std::fmt::Display) -> ! {
+    println!("Error! {err}");
+    std::process::exit(1);
+}
+
+/// Whether `exit` stops the process at once, as the command-line interface does.
+/// The embedding hosts get the request from [`Executor::eval`] and decide themselves
+static EXIT_PROCESS: AtomicBool = AtomicBool::new(false);
+
+/// Entry point of the command-line interface
+pub fn main() {
+    EXIT_PROCESS.store(true, Ordering::Relaxed);
+    let arguments = cli().get_matches();
+    let (command, matches) = arguments.subcommand().unwrap_or(("run", &arguments));
+
+    match command {
+        // Format the program files
+        "fmt" => {
+            let paths: Vec<&str> = matches
+                .values_of("files")
+                .map(|x| x.collect())
+                .unwrap_or_default();
+            match fmt::run(
+                &paths,
+                matches.is_present("check"),
+                matches.is_present("stdout"),
+            ) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(err) => fail(err),
+            }
+        }
+
+        // Report syntax problems of the program
+        "check" => check_program(matches),
+
+        // Print the route tables of the servers
+        "routes" => {
+            let mode = prepare(matches);
+            let Some((_, code)) = read_program(matches) else {
+                fail("program to list the routes is not specified");
+            };
+            if let Err(err) = routes::run(code, mode.unwrap_or(Mode::Quiet)) {
+                fail(err);
+            }
+            cleanup_temporaries();
+        }
+
+        // Measure the performance of the program
+        "bench" => {
+            let mode = prepare(matches);
+            let Some((name, code)) = read_program(matches) else {
+                fail("program to benchmark is not specified");
+            };
+            let number = |key: &str, default: usize| match matches.value_of(key) {
+                Some(x) => x
+                    .parse()
+                    .unwrap_or_else(|_| fail(format!("invalid number \"{x}\""))),
+                None => default,
+            };
+            let values = |key: &str| -> Vec<String> {
+                matches
+                    .values_of(key)
+                    .map(|x| x.map(|x| x.to_string()).collect())
+                    .unwrap_or_default()
+            };
+            let options = bench::Options {
+                iterations: number("iterations", 100),
+                warmup: number("warmup", 10),
+                codes: values("code"),
+                requests: values("request"),
+                body: matches.value_of("body").unwrap_or_default().to_string(),
+            };
+            if let Err(err) = bench::run(&name, code, &options, mode.unwrap_or(Mode::Quiet)) {
+                fail(err);
+            }
+            cleanup_temporaries();
+        }
+
+        // Pack the program and the assets
+        "bundle" => {
+            let includes: Vec<&str> = matches
+                .values_of("include")
+                .map(|x| x.collect())
+                .unwrap_or_default();
+            match bundle::pack(
+                matches.value_of("script").unwrap_or_default(),
+                &includes,
+                matches.value_of("output"),
+            ) {
+                Ok(path) => println!("Bundle is written to {path}"),
+                Err(err) => fail(err),
+            }
+        }
+
+        // Print the completion script
+        "completions" => {
+            let shell = matches.value_of("shell").unwrap_or_default();
+            match shell.parse::<clap_complete::Shell>() {
+                Ok(shell) => completions::print(shell, &mut cli()),
+                Err(err) => fail(err),
+            }
+        }
+
+        "repl" => {
+            let mode = prepare(matches);
+            repl(mode.unwrap_or(Mode::Debug));
+        }
+
+        "test" => {
+            let mode = prepare(matches);
+            let paths: Vec<&str> = matches
+                .values_of("paths")
+                .map(|x| x.collect())
+                .unwrap_or_default();
+            match testing::run(&paths, mode.unwrap_or(Mode::Quiet)) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(err) => fail(err),
+            }
+        }
+
+        // Execute the program (run, serve, or without the subcommand)
+        _ => {
+            if matches.is_present("check") {
+                check_program(matches);
+                return;
+            }
+            let mode = prepare(matches);
+            run(matches, mode);
+        }
+    }
+}
+
+/// Load the environment variables and the configuration file, and get the log level
+fn prepare(matches: &ArgMatches) -> Option<Mode> {
+    // Load environment variables from .env file
+    match matches.value_of("env-file") {
+        Some(path) => {
+            if let Err(err) = dotenvy::from_path(path) {
+                fail(err);
+            }
+        }
+        None => {
+            dotenvy::dotenv().ok();
+        }
+    }
+
+    // Load the configuration file
+    if let Err(err) = config::load(matches.value_of("config")) {
+        fail(err);
+    }
+
+    // Load the plugins adding the commands
+    for path in matches.values_of("plugin").into_iter().flatten() {
+        if let Err(err) = plugin::load(path) {
+            fail(err);
+        }
+    }
+
+    config::set_strict(matches.is_present("strict"));
+
+    // Print the tracing spans into standard error, and export them to the collector
+    let (filter, endpoint) = (matches.value_of("tracing"), matches.value_of("otlp"));
+    if filter.is_some() || endpoint.is_some() {
+        let format = matches
+            .value_of("tracing-format")
+            .and_then(telemetry::Format::parse)
+            .unwrap_or(telemetry::Format::Full);
+        if let Err(err) = telemetry::init(filter, format, endpoint) {
+            fail(err);
+        }
+    }
+
+    // Decide the log level by the flags or the configuration file
+    match matches.occurrences_of("verbose") {
+        _ if matches.is_present("quiet") => Some(Mode::Quiet),
+        0 if matches.is_present("debug") => Some(Mode::Debug),
+        0 => config::log_level().map(|level| {
+            Mode::parse(&level).unwrap_or_else(|| fail(format!("unknown log level \"{level}\"")))
+        }),
+        1 => Some(Mode::Debug),
+        _ => Some(Mode::Trace),
+    }
+}
+
+/// Get the program from the inline code, the script file, or the standard input
+fn read_program(matches: &ArgMatches) -> Option<(String, String)> {
+    let script = matches.value_of("script");
+    if let Some(code) = matches.value_of("eval") {
+        Some(("<eval>".to_string(), code.to_string()))
+    } else if script == Some("-") || (script.is_none() && !io::stdin().is_terminal()) {
+        let mut code = String::new();
+        if let Err(err) = io::stdin().read_to_string(&mut code) {
+            fail(err);
+        }
+        Some(("<stdin>".to_string(), code))
+    } else {
+        let script = script?;
+        match get_file_contents(Path::new(&script.to_string())) {
+            Ok(code) => Some((script.to_string(), code)),
+            Err(err) => fail(err),
+        }
+    }
+}
+
+/// Report syntax problems instead of executing
+fn check_program(matches: &ArgMatches) {
+    let Some((name, code)) = read_program(matches) else {
+        fail("program to check is not specified");
+    };
+    let problems = check::check(&code);
+    for problem in &problems {
+        println!(
+            "{name}:{}:{}: {}",
+            problem.line, problem.column, problem.message
+        );
+    }
+    if !problems.is_empty() {
+        std::process::exit(1);
+    }
+    println!("{name}: syntax is OK");
+}
+
+/// Execute the program, or start the REPL if it's not given
+fn run(matches: &ArgMatches, mode: Option<Mode>) {
+    // Override the listen address of the server
+    let port = match matches.value_of("port").map(|x| x.parse::<u16>()) {
+        Some(Ok(port)) => Some(port),
+        Some(Err(err)) => fail(format!("invalid port: {err}")),
+        None => None,
+    };
+    config::set_listen(matches.value_of("bind").map(|x| x.to_string()), port);
+    config::set_arguments(
+        matches
+            .values_of("args")
+            .map(|x| x.map(|x| x.to_string()).collect())
+            .unwrap_or_default(),
+    );
+
+    // Restart the program in the child process whenever the watched paths change
+    if matches.is_present("watch") {
+        let mut paths: Vec<String> = matches
+            .values_of("watch-path")
+            .map(|x| x.map(|x| x.to_string()).collect())
+            .unwrap_or_default();
+        paths.extend(
+            matches
+                .value_of("script")
+                .filter(|x| *x != "-")
+                .map(|x| x.to_string()),
+        );
+        paths.extend(config::watch_paths());
+        if let Err(err) = watch::run(&paths) {
+            fail(err);
+        }
+        return;
+    }
+
+    // Run the bundle in the unpacked directory with its configuration and assets
+    let script = matches.value_of("script").filter(|x| *x != "-");
+    let program = if let Some(path) = script.filter(|x| bundle::is_bundle(Path::new(x))) {
+        let program = bundle::unpack(Path::new(path)).unwrap_or_else(|err| fail(err));
+        dotenvy::dotenv().ok();
+        if matches.value_of("config").is_none() {
+            if let Err(err) = config::load(None) {
+                fail(err);
+            }
+            // Keep the listen address given by the flags
+            config::set_listen(matches.value_of("bind").map(|x| x.to_string()), port);
+        }
+        Some(program)
+    } else {
+        read_program(matches)
+    };
+
+    if let Some((_, code)) = program {
+        let mut stack = Executor::new(mode.unwrap_or(Mode::Script));
+        stack.evaluate_program(code);
+        cleanup_temporaries();
+        telemetry::shutdown();
+    } else {
+        repl(mode.unwrap_or(Mode::Debug));
+    }
+}
+
+/// Start the interactive session
+fn repl(mode: Mode) {
+    // Show a title
+    println!("Stack Programming Language: Server Edition");
+    let mut executor = Executor::new(mode);
+    // Underflow in the REPL is an error only if it's asked, whatever the log level is
+    executor.strict = config::strict();
+    let mut history = String::new(); // Code entered in the session
+
+    // REPL Execution
+    loop {
+        let mut code = String::new();
+        loop {
+            let enter = input("> ");
+
+            // Processing REPL directives
+            if enter.starts_with(':') {
+                repl_directive(&mut executor, &mut history, &enter);
+                continue;
+            }
+
+            code += &format!("{enter}\n");
+            if enter.is_empty() {
+                break;
+            }
+        }
+
+        history += code.trim_end();
+        history.push('\n');
+        executor.evaluate_program(code)
+    }
+}
+
+/// Execute directive of the REPL
+fn repl_directive(executor: &mut Executor, history: &mut String, directive: &str) {
+    let (name, argument) = directive
+        .trim()
+        .split_once(' ')
+        .map(|(name, argument)| (name, argument.trim()))
+        .unwrap_or((directive.trim(), ""));
+    match (name, argument) {
+        (":reset", _) => {
+            executor.stack.clear();
+            executor.memory.clear();
+            history.clear();
+            println!("Stack and memory are reset");
+        }
+
+        // Write the code entered in the session into the file
+        (":save", "") | (":load", "") => println!("Error! file path is not specified"),
+        (":save", path) => match std::fs::write(path, history.as_bytes()) {
+            Ok(()) => println!("Session is saved to {path}"),
+            Err(err) => println!("Error! {err}"),
+        },
+
+        // Evaluate the file in the current session
+        (":load", path) => match get_file_contents(Path::new(path)) {
+            Ok(code) => {
+                *history += code.trim_end();
+                history.push('\n');
+                executor.evaluate_program(code);
+            }
+            Err(err) => println!("Error! {err}"),
+        },
+
+        // Show the variables and their values
+        (":vars", _) => {
+            let mut names: Vec<&String> = executor.memory.keys().collect();
+            names.sort();
+            for name in names {
+                println!("{name} = {}", executor.memory[name].display());
+            }
+        }
+
+        // Show the stack
+        (":stack", _) => println!("{}", executor.show_stack()),
+
+        (":help", _) => {
+            println!(":reset        Reset the stack, memory and session");
+            println!(":save <path>  Write the code entered in the session");
+            println!(":load <path>  Evaluate the file in the current session");
+            println!(":vars         Show the variables");
+            println!(":stack        Show the stack");
+        }
+        _ => println!("Error! unknown directive \"{directive}\""),
+    }
+}
+
+/// Temporary paths that should be deleted at process exit
+static TEMPORARIES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Signals handled by the program with on-signal, which don't terminate it
+static HANDLED_SIGNALS: Mutex<Vec<i32>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// Auto-delete temporaries made by the request being handled on the current thread
+    static REQUEST_TEMPORARIES: std::cell::RefCell<Vec<PathBuf>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Delete the temporary file or directory
+fn remove_temporary(path: &Path) {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path).ok();
+    } else {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+/// Delete the temporaries registered as auto-delete
+fn cleanup_temporaries() {
+    let mut paths = TEMPORARIES.lock().unwrap_or_else(|e| e.into_inner());
+    for path in paths.drain(..) {
+        remove_temporary(&path);
+    }
+}
+
+/// Delete the auto-delete temporaries made by the request after responding to it
+fn cleanup_request_temporaries() {
+    let paths = REQUEST_TEMPORARIES.with(|x| x.take());
+    if paths.is_empty() {
+        return;
+    }
+    TEMPORARIES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|x| !paths.contains(x));
+    for path in paths {
+        remove_temporary(&path);
+    }
+}
+
+/// Delete the temporaries before SIGINT or SIGTERM terminates the process
+/// (unless the program handles the signal with on-signal)
+#[cfg(unix)]
+fn cleanup_temporaries_on_signal() {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+    use std::sync::Once;
+
+    static REGISTERED: Once = Once::new();
+    REGISTERED.call_once(|| {
+        let Ok(mut signals) = signal_hook::iterator::Signals::new([SIGINT, SIGTERM]) else {
+            return;
+        };
+        thread::spawn(move || {
+            for signal in signals.forever() {
+                let handled = HANDLED_SIGNALS.lock().unwrap_or_else(|e| e.into_inner());
+                if !handled.contains(&signal) {
+                    cleanup_temporaries();
+                    signal_hook::low_level::emulate_default_handler(signal).ok();
+                }
+            }
+        });
+    });
+}
+
+/// Read string of the file
+fn get_file_contents(name: &Path) -> Result<String, Error> {
+    let mut f = File::open(name)?;
+    let mut contents = String::new();
+    f.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// Get standard input
+fn input(prompt: &str) -> String {
+    print!("{}", prompt);
+    io::stdout().flush().unwrap();
+    let mut result = String::new();
+    io::stdin().read_line(&mut result).ok();
+    result.trim().to_string()
+}
+
+/// Execution Mode (levels of the output from less to more)
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Mode {
+    Quiet,  // Errors and the program's output only
+    Script, // Script execution
+    Debug,  // Debug execution showing the stack
+    Trace,  // Debug execution with HTTP wire dumps
+}
+
+impl Mode {
+    /// Get mode by the name of the log level
+    fn parse(name: &str) -> Option<Mode> {
+        match name {
+            "quiet" => Some(Mode::Quiet),
+            "normal" => Some(Mode::Script),
+            "verbose" | "debug" => Some(Mode::Debug),
+            "trace" => Some(Mode::Trace),
+            _ => None,
+        }
+    }
+}
+
+/// Data type
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Type {
+    Number(f64),
+    String(Shared<String>),
+    Bool(bool),
+    List(Shared<Vec<Type>>),
+    Json(Shared<Value>),
+    Object(String, HashMap<String, Type>),
+    Error(StackError),
+    Binary(#[serde(with = "shared::bytes")] Shared<Vec<u8>>),
+    Ref(Reference),
+    Null,
+}
+
+impl From<Value> for Type {
+    fn from(json: Value) -> Type {
+        Type::from_json(&json)
+    }
+}
+
+impl From<Type> for Value {
+    fn from(value: Type) -> Value {
+        value.to_json()
+    }
+}
+
+/// Shared reference to mutable value
+#[derive(Clone, Debug)]
+pub struct Reference(Arc<Mutex<Type>>);
+
+thread_local! {
+    /// Addresses of the references being visited on the current thread, to stop at the cycles
+    static VISITING: std::cell::RefCell<Vec<usize>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Mark of the reference being visited, removed when it's dropped
+struct Visit(usize);
+
+impl Drop for Visit {
+    fn drop(&mut self) {
+        VISITING.with(|x| x.borrow_mut().retain(|address| *address != self.0));
+    }
+}
+
+impl Reference {
+    /// Constructor
+    fn new(value: Type) -> Reference {
+        Reference(Arc::new(Mutex::new(value)))
+    }
+
+    /// Lock the referenced value to access it
+    fn lock(&self) -> MutexGuard<'_, Type> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Get copy of the referenced value
+    fn get(&self) -> Type {
+        self.lock().clone()
+    }
+
+    /// Mark the reference to visit its value, or None if it's already being visited (cyclic)
+    fn visit(&self) -> Option<Visit> {
+        let address = Arc::as_ptr(&self.0) as usize;
+        VISITING.with(|x| {
+            let mut visiting = x.borrow_mut();
+            if visiting.contains(&address) {
+                return None;
+            }
+            visiting.push(address);
+            Some(Visit(address))
+        })
+    }
+}
+
+impl Serialize for Reference {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.visit() {
+            Some(_visit) => self.get().serialize(serializer),
+            None => serializer.serialize_unit(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Reference {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Type::deserialize(deserializer).map(Reference::new)
+    }
+}
+
+impl PartialEq for Reference {
+    fn eq(&self, other: &Self) -> bool {
+        // Comparing the reference again in itself finds nothing different
+        Arc::ptr_eq(&self.0, &other.0)
+            || match self.visit() {
+                Some(_visit) => self.get() == other.get(),
+                None => true,
+            }
+    }
+}
+
+/// Reason why the value can't be set by the path
+enum PathError {
+    NotFound, // Path doesn't lead to the value
+    Cyclic,   // Path goes through the reference being set again
+}
+
+/// Implement methods
+impl Type {
+    /// Show data to display
+    pub fn display(&self) -> String {
+        match self {
+            Type::Number(num) => num.to_string(),
+            Type::String(s) => format!("({})", s),
+            Type::Bool(b) => b.to_string(),
+            Type::List(list) => {
+                let result: Vec<String> = list.iter().map(|token| token.display()).collect();
+                format!("[{}]", result.join(" "))
+            }
+            Type::Json(j) => serde_json::to_string_pretty(&j).unwrap_or("{}".to_string()),
+            Type::Error(err) => format!("error:{err}"),
+            Type::Object(name, _) => format!("Object<{name}>"),
+            Type::Binary(i) => format!("Binary<{}>", i.len()),
+            Type::Ref(r) => match r.visit() {
+                Some(_visit) => format!("Ref<{}>", r.get().display()),
+                None => "Ref<...>".to_string(),
+            },
+            Type::Null => "null".to_string(),
+        }
+    }
+
+    /// Get string form data
+    pub fn get_string(&self) -> String {
+        match self {
+            Type::String(s) => s.to_string(),
+            Type::Number(i) => i.to_string(),
+            Type::Bool(b) => b.to_string(),
+            Type::List(l) => Type::List(l.to_owned()).display(),
+            Type::Json(j) => j.as_str().unwrap_or("").to_string(),
+            Type::Error(err) => format!("error:{err}"),
+            Type::Object(name, _) => format!("Object<{name}>"),
+            Type::Binary(i) => format!("Binary<{}>", i.len()),
+            Type::Ref(r) => r.get().get_string(),
+            Type::Null => "".to_string(),
+        }
+    }
+
+    /// Get number from data
+    pub fn get_number(&self) -> f64 {
+        match self {
+            Type::String(s) => s.parse().unwrap_or(0.0),
+            Type::Number(i) => *i,
+            Type::Bool(b) => {
+                if *b {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Type::Json(j) => j.as_f64().unwrap_or(0f64),
+            Type::List(l) => l.len() as f64,
+            Type::Error(e) => e.message.parse().unwrap_or(0f64),
+            Type::Object(_, object) => object.len() as f64,
+            Type::Binary(i) => i.len() as f64,
+            Type::Ref(r) => r.get().get_number(),
+            Type::Null => 0f64,
+        }
+    }
+
+    /// Get bool from data
+    pub fn get_bool(&self) -> bool {
+        match self {
+            Type::String(s) => !s.is_empty(),
+            Type::Number(i) => *i != 0.0,
+            Type::Bool(b) => *b,
+            Type::List(l) => !l.is_empty(),
+            Type::Json(j) => j.as_bool().unwrap_or(false),
+            Type::Error(e) => e.message.parse().unwrap_or(false),
+            Type::Object(_, object) => object.is_empty(),
+            Type::Binary(i) => !i.is_empty(),
+            Type::Ref(r) => r.get().get_bool(),
+            Type::Null => false,
+        }
+    }
+
+    /// Get list form data
+    pub fn get_list(&self) -> Vec<Type> {
+        match self {
+            Type::String(s) => s
+                .to_string()
+                .chars()
+                .map(|x| Type::String(x.to_string().into()))
+                .collect::<Vec<Type>>(),
+            Type::Number(i) => vec![Type::Number(*i)],
+            Type::Bool(b) => vec![Type::Bool(*b)],
+            Type::List(l) => l.to_vec(),
+            Type::Json(j) => {
+                if let Some(obj) = j.as_object() {
+                    obj.keys()
+                        .cloned()
+                        .map(|x| Type::String(x.into()))
+                        .collect::<Vec<Type>>()
+                } else if let Some(array) = j.as_array() {
+                    array
+                        .iter()
+                        .cloned()
+                        .map(|x| Type::Json(x.into()))
+                        .collect::<Vec<Type>>()
+                } else {
+                    Vec::new()
+                }
+            }
+            Type::Error(e) => vec![Type::Error(e.clone())],
+            Type::Object(_, object) => object.values().map(|x| x.to_owned()).collect::<Vec<Type>>(),
+            Type::Binary(i) => i.iter().map(|x| Type::Number(*x as f64)).collect(),
+            Type::Ref(r) => r.get().get_list(),
+            Type::Null => Vec::new(),
+        }
+    }
+
+    /// Take list form data, copying the items only if they are shared
+    pub fn into_list(self) -> Vec<Type> {
+        match self {
+            Type::List(l) => l.into_inner(),
+            other => other.get_list(),
+        }
+    }
+
+    fn get_json(&mut self) -> Value {
+        match self {
+            Type::Json(j) => (**j).clone(),
+            Type::String(j) => serde_json::from_str(j).unwrap_or(json!({})),
+            Type::Ref(r) => r.get().get_json(),
+            Type::Null => Value::Null,
+            _ => json!({}),
+        }
+    }
+
+    pub fn get_object(&self) -> (String, HashMap<String, Type>) {
+        match self {
+            Type::Object(name, value) => (name.to_owned(), value.to_owned()),
+            Type::Ref(r) => r.get().get_object(),
+            _ => ("".to_string(), HashMap::new()),
+        }
+    }
+
+    /// Convert data into json deeply
+    fn to_json(&self) -> Value {
+        match self {
+            Type::Number(n) => {
+                if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+                    json!(*n as i64)
+                } else {
+                    json!(n)
+                }
+            }
+            Type::String(s) => json!(s),
+            Type::Bool(b) => json!(b),
+            Type::List(list) => Value::Array(list.iter().map(|x| x.to_json()).collect()),
+            Type::Json(j) => (**j).clone(),
+            Type::Object(_, object) => Value::Object(
+                object
+                    .iter()
+                    .map(|(key, value)| (key.to_owned(), value.to_json()))
+                    .collect(),
+            ),
+            Type::Error(err) => json!(format!("error:{err}")),
+            Type::Binary(i) => json!(STANDARD.encode(i)),
+            Type::Ref(r) => match r.visit() {
+                Some(_visit) => r.get().to_json(),
+                None => Value::Null,
+            },
+            Type::Null => Value::Null,
+        }
+    }
+
+    /// Create the error value by the name
+    fn error(name: &str) -> Type {
+        Type::Error(StackError::new(name, ""))
+    }
+
+    /// Convert the Rust value into data through json
+    pub fn from_serde<T: Serialize>(value: &T) -> Result<Type, serde_json::Error> {
+        serde_json::to_value(value).map(|json| Type::from_json(&json))
+    }
+
+    /// Convert data into the Rust value through json
+    pub fn to_serde<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(self.to_json())
+    }
+
+    /// Convert json into native data deeply
+    fn from_json(json: &Value) -> Type {
+        match json {
+            Value::Null => Type::Null,
+            Value::Bool(b) => Type::Bool(*b),
+            Value::Number(n) => Type::Number(n.as_f64().unwrap_or(0f64)),
+            Value::String(s) => Type::String(s.to_owned().into()),
+            Value::Array(array) => Type::List(array.iter().map(Type::from_json).collect()),
+            Value::Object(object) => Type::Object(
+                "object".to_string(),
+                object
+                    .iter()
+                    .map(|(key, value)| (key.to_owned(), Type::from_json(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Get value by following the path of keys
+    fn get_path(&self, path: &[&str]) -> Option<Type> {
+        let Some((key, rest)) = path.split_first() else {
+            return Some(self.clone());
+        };
+        let child = match self {
+            Type::Object(_, object) => object.get(*key)?.clone(),
+            Type::List(list) => list.get(key.parse::<usize>().ok()?)?.clone(),
+            Type::Json(json) => match &**json {
+                Value::Array(array) => {
+                    Type::Json(array.get(key.parse::<usize>().ok()?)?.clone().into())
+                }
+                json => Type::Json(json.get(*key)?.clone().into()),
+            },
+            Type::Ref(r) => return r.get().get_path(path),
+            _ => return None,
+        };
+        child.get_path(rest)
+    }
+
+    /// Set value by following the path of keys
+    fn set_path(&mut self, path: &[&str], data: Type) -> Result<(), PathError> {
+        let Some((key, rest)) = path.split_first() else {
+            *self = data;
+            return Ok(());
+        };
+        let index = || key.parse::<usize>().map_err(|_| PathError::NotFound);
+        match self {
+            Type::Object(_, object) => {
+                if rest.is_empty() {
+                    object.insert(key.to_string(), data);
+                    Ok(())
+                } else {
+                    object
+                        .get_mut(*key)
+                        .ok_or(PathError::NotFound)?
+                        .set_path(rest, data)
+                }
+            }
+            Type::List(list) => list
+                .get_mut(index()?)
+                .ok_or(PathError::NotFound)?
+                .set_path(rest, data),
+            Type::Json(json) => {
+                let mut child = Type::Json(
+                    match &**json {
+                        Value::Array(array) => {
+                            array.get(index()?).ok_or(PathError::NotFound)?.clone()
+                        }
+                        _ => json.get(*key).cloned().unwrap_or(json!({})),
+                    }
+                    .into(),
+                );
+                child.set_path(rest, data)?;
+                let child = child.to_json();
+                match &mut **json {
+                    Value::Array(array) => array[index()?] = child,
+                    Value::Object(object) => {
+                        object.insert(key.to_string(), child);
+                    }
+                    _ => return Err(PathError::NotFound),
+                }
+                Ok(())
+            }
+            // Locking the reference again in itself would wait forever
+            Type::Ref(r) => {
+                let _visit = r.visit().ok_or(PathError::Cyclic)?;
+                r.lock().set_path(path, data)
+            }
+            _ => Err(PathError::NotFound),
+        }
+    }
+}
+
+/// Interpreter of the Stack programs, which can be embedded in the Rust programs
+/// by [`Executor::eval`], [`Executor::set_var`] and [`Executor::get_var`]
+#[derive(Clone, Debug)]
+pub struct Executor {
+    stack: Vec<Type>,                       // Data stack
+    memory: HashMap<String, Type>,          // Variable's memory
+    mode: Mode,                             // Execution mode
+    black_list: Type,                       // List of token that should not eval
+    strict: bool,                           // Whether stack underflow is an error
+    position: Span,                         // Position of the token in the program being evaluated
+    underflow: bool,                        // Whether the command popped the empty stack
+    request: Option<Arc<request::Request>>, // Request being handled by the server
+    exit: Option<i32>,                      // Exit status requested, which stops the evaluation
+}
+
+/// Why the evaluation by [`Executor::eval`] didn't give the values
+#[derive(Clone, Debug, PartialEq)]
+pub enum EvalError {
+    Error(StackError), // Error value pushed by the code
+    Exit(i32),         // Exit requested by `exit` with the status, left to the host
+}
+
+impl std::fmt::Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EvalError::Error(err) => write!(f, "{err}"),
+            EvalError::Exit(status) => write!(f, "exit is requested with status {status}"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+impl From<StackError> for EvalError {
+    fn from(err: StackError) -> EvalError {
+        EvalError::Error(err)
+    }
+}
+
+impl Executor {
+    /// Make the interpreter with the empty stack and memory, where stack underflow is an error,
+    /// printing the logs by the mode ([`Mode::Quiet`] for the embedding hosts)
+    pub fn new(mode: Mode) -> Executor {
+        Executor {
+            stack: Vec::new(),
+            memory: HashMap::new(),
+            mode,
+            black_list: Type::List(vec![].into()),
+            strict: true,
+            position: Span::default(),
+            underflow: false,
+            request: None,
+            exit: None,
+        }
+    }
+
+    /// Evaluate the code, and take the values it pushed as JSON.
+    /// Fails if an error value is pushed, or if `exit` is called (the process isn't exited,
+    /// and the values pushed before it are dropped)
+    pub fn eval(&mut self, code: &str) -> Result<Vec<Value>, EvalError> {
+        let depth = self.stack.len();
+        self.evaluate_program(code.to_string());
+        let values = self.stack.split_off(depth.min(self.stack.len()));
+
+        if let Some(status) = self.exit.take() {
+            return Err(EvalError::Exit(status));
+        }
+        if let Some(Type::Error(err)) = values.iter().find(|x| matches!(x, Type::Error(_))) {
+            return Err(EvalError::Error(err.clone()));
+        }
+        Ok(values.iter().map(|x| x.to_json()).collect())
+    }
+
+    /// Define the variable by the Rust value
+    pub fn set_var<T: Serialize>(&mut self, name: &str, value: &T) -> Result<(), StackError> {
+        let value = Type::from_serde(value)
+            .map_err(|e| StackError::with_kind(ErrorKind::Type, "json", e))?;
+        self.memory.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// Get the variable as the Rust value
+    pub fn get_var<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        self.memory.get(name)?.to_serde().ok()
+    }
+
+    /// Output log
+    fn log_print(&mut self, msg: String) {
+        if self.mode >= Mode::Debug {
+            print!("{msg}");
+        }
+    }
+
+    /// Output error (into standard error unless it's shown with the log)
+    fn error_print(&mut self, msg: String) {
+        if self.mode >= Mode::Debug {
+            print!("{msg}");
+        } else {
+            eprint!("{msg}");
+        }
+    }
+
+    /// Show variable inside memory
+    fn show_variables(&mut self) {
+        self.log_print("Variables {\n".to_string());
+        let max = self.memory.keys().map(|s| s.len()).max().unwrap_or(0);
+        let lines: String = self
+            .memory
+            .iter()
+            .map(|(name, value)| format!(" {:>width$}: {}\n", name, value.display(), width = max))
+            .collect();
+        self.log_print(lines);
+        self.log_print("}\n".to_string())
+    }
+
+    /// Show inside the stack
+    fn show_stack(&mut self) -> String {
+        format!(
+            "Stack〔 {} 〕",
+            self.stack
+                .iter()
+                .map(|x| x.display())
+                .collect::<Vec<_>>()
+                .join(" | ")
+        )
+    }
+
+    fn processing_token(&mut self, token: String) {
+        // Show inside stack to debug
+        let stack = self.show_stack();
+        self.log_print(format!("{stack} ←  {token}\n"));
+
+        // Character vector for token processing
+        let chars: Vec<char> = token.chars().collect();
+
+        // Judge what the token is
+        if let Ok(i) = token.parse::<f64>() {
+            // Push number value on the stack
+            self.stack.push(Type::Number(i));
+        } else if token == "true" || token == "false" {
+            // Push bool value on the stack
+            self.stack.push(Type::Bool(token.parse().unwrap_or(true)));
+        } else if token == "null" {
+            // Push null value on the stack
+            self.stack.push(Type::Null);
+        } else if chars[0] == '(' && chars[chars.len() - 1] == ')' {
+            // Processing string escape and push string value on the stack
+            let string = lexer::unescape(&token[1..token.len() - 1]);
+            self.stack.push(Type::String(string.into()));
+        } else if chars[0] == '[' && chars[chars.len() - 1] == ']' {
+            // Push list value on the stack
+            let old_len = self.stack.len(); // length of old stack
+            let slice = &token[1..token.len() - 1];
+            let outer = self.position;
+            self.evaluate_tokens(&lexer::parse(slice), Some(outer), true);
+            // Make increment of stack an element of list
+            let mut list = Vec::new();
+            for _ in old_len..self.stack.len() {
+                list.push(self.pop_stack());
+            }
+            list.reverse(); // reverse list
+            self.stack.push(Type::List(list.into()));
+        } else if token.starts_with("error:") {
+            // Push error value on the stack
+            self.stack.push(Type::Error(StackError::parse(
+                token.trim_start_matches("error:"),
+            )))
+        } else if let Some(i) = self.memory.get(&token) {
+            // Push variable's data on stack
+            self.stack.push(i.clone());
+        } else if chars[0] == '#' && chars[chars.len() - 1] == '#' {
+            // Processing comments
+            self.log_print(format!("* Comment \"{}\"\n", token.replace('#', "")));
+        } else {
+            // Else, execute as command
+            let position = self.position;
+            let outer = std::mem::take(&mut self.underflow);
+            // Trace each command only in verbose mode since it's frequent
+            let _span = (self.mode >= Mode::Debug)
+                .then(|| tracing::debug_span!("command", %token).entered());
+            self.execute_command(token.clone());
+
+            // Push the error if the command popped the empty stack, keeping the stack as it is
+            if std::mem::replace(&mut self.underflow, outer) {
+                self.position = position;
+                self.raise(
+                    "stack-underflow",
+                    format!(
+                        "\"{token}\" popped the empty stack at line {}, column {}",
+                        position.line, position.column
+                    ),
+                );
+            }
+        }
+    }
+
+    fn evaluate_program(&mut self, code: String) {
+        self.evaluate_tokens(&lexer::parse(&code), None, true);
+    }
+
+    fn evaluate_program_unsafe(&mut self, code: String) {
+        self.evaluate_tokens(&lexer::parse(&code), None, false);
+    }
+
+    /// Evaluate the tokens (located in the outer token if it's nested),
+    /// pushing error instead of the tokens in the black list if it's safe
+    fn evaluate_tokens(&mut self, tokens: &[Token], outer: Option<Span>, safe: bool) {
+        let black_list = if safe {
+            self.black_list
+                .get_list()
+                .iter()
+                .map(|i| i.get_string())
+                .collect::<Vec<String>>()
+        } else {
+            Vec::new()
+        };
+
+        for Token { text, span } in tokens {
+            // Stop if the exit is requested, or the thread is killed or timed out
+            if self.exit.is_some() || threads::is_cancelled() {
+                break;
+            }
+            self.position = outer.map(|outer| span.within(outer)).unwrap_or(*span);
+            if !black_list.contains(text) {
+                self.processing_token(text.clone());
+            } else {
+                self.stack.push(Type::error("eval-security-fault"))
+            }
+        }
+
+        // Show inside stack, after execution
+        let stack = self.show_stack();
+        self.log_print(format!("{stack}\n"));
+    }
+
+    /// execute string as commands
+    fn execute_command(&mut self, command: String) {
+        // Keep the dry-run listing the routes from touching the outside
+        if routes::is_skipped(&command) {
+            return;
+        }
+
+        // Execute the native command in the registry
+        if let Some(native) = commands::get(&command) {
+            if let Err(e) = native(self) {
+                self.error_print(format!("Error! {}\n", e.message));
+                self.stack.push(Type::Error(e.at(self.position)));
+            }
+            return;
+        }
+
+        // If it is not recognized as a command, use it as a string.
+        self.stack.push(Type::String(command.into()));
+    }
+
+    /// Apply change to the list, in place if it is a reference
+    fn update_list(&mut self, target: Type, change: impl FnOnce(&mut Vec<Type>) -> Option<()>) {
+        let result = if let Type::Ref(reference) = &target {
+            let mut value = reference.lock();
+            let Type::List(list) = &mut *value else {
+                drop(value);
+                self.raise("not-list", "the referenced value is not a list");
+                return;
+            };
+            change(list).map(|_| target.clone())
+        } else {
+            let mut list = target.into_list();
+            change(&mut list).map(|_| Type::List(list.into()))
+        };
+
+        match result {
+            Some(value) => self.stack.push(value),
+            None => {
+                self.raise("index-out-range", "Index specification is out of range");
+            }
+        }
+    }
+
+    /// Push path of the created temporary
+    fn push_temporary(&mut self, path: io::Result<PathBuf>, auto_delete: bool, kind: &str) {
+        match path {
+            Ok(path) => {
+                if auto_delete {
+                    TEMPORARIES
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .push(path.clone());
+                    // Temporaries of the request don't outlive it
+                    if self.request.is_some() {
+                        REQUEST_TEMPORARIES.with(|x| x.borrow_mut().push(path.clone()));
+                    }
+                    #[cfg(unix)]
+                    cleanup_temporaries_on_signal();
+                }
+                self.stack
+                    .push(Type::String(path.to_string_lossy().to_string().into()));
+            }
+            Err(e) => self.raise(kind, e),
+        }
+    }
+
+    /// Report the error, and push it as the error value
+    fn raise(&mut self, name: &str, message: impl std::fmt::Display) {
+        let err = StackError::new(name, message).at(self.position);
+        self.error_print(format!("Error! {}\n", err.message));
+        self.stack.push(Type::Error(err));
+    }
+
+    /// Get time zone by the name, or push error
+    fn get_zone(&mut self, name: &str) -> Option<Zone> {
+        let zone = Zone::parse(name);
+        if zone.is_none() {
+            self.raise("time-zone", format!("unknown time zone \"{name}\""));
+        }
+        zone
+    }
+
+    /// Pop SQL query and its parameters if they are given as list
+    fn pop_query(&mut self) -> (String, Vec<Type>) {
+        match self.pop_stack() {
+            Type::List(params) => (self.pop_stack().get_string(), params.into_inner()),
+            query => (query.get_string(), vec![]),
+        }
+    }
+
+    /// Push the value on the stack
+    pub fn push_stack(&mut self, value: Type) {
+        self.stack.push(value);
+    }
+
+    /// Pop stack's top value
+    pub fn pop_stack(&mut self) -> Type {
+        if let Some(value) = self.stack.pop() {
+            value
+        } else if self.strict {
+            // Report it after the command
+            self.underflow = true;
+            Type::String("".into())
+        } else {
+            self.error_print(
+                "Error! There are not enough values on the stack. returns default value\n"
+                    .to_string(),
+            );
+            Type::String("".into())
+        }
+    }
+
+    /// Http request handler
+    fn handle(&mut self, mut stream: TcpStream, site: &Site) {
+        let time = chrono::Local::now();
+        let started = Instant::now();
+        let (head, rest, rejection) = match request::read_head(&mut stream, site.buffer_size) {
+            Ok((head, rest)) => (head, rest, None),
+            Err(rejection) => (String::new(), Vec::new(), Some(rejection)),
+        };
+
+        let request_line = head.lines().next().unwrap_or_default();
+        let (method, path) = parse_request_line(request_line, " ");
+        let (path, query) = parse_request_line(&path, "?");
+
+        // Keep the event stream of the page open to reload it after the restart
+        if livereload::is_enabled() && path == livereload::PATH && rejection.is_none() {
+            livereload::connect(stream);
+            return;
+        }
+
+        let span = tracing::info_span!("request", %method, %path);
+        telemetry::set_parent(
+            &span,
+            header_value(&head, "traceparent"),
+            header_value(&head, "tracestate"),
+        );
+        let _span = span.entered();
+
+        let trace = self.mode >= Mode::Trace;
+        let peer = stream
+            .peer_addr()
+            .map(|x| x.to_string())
+            .unwrap_or_default();
+        let mut stream = Wire {
+            stream,
+            trace,
+            started: false,
+            head: Some(Vec::new()),
+            status: 0,
+            sent: 0,
+        };
+        stats::begin();
+
+        // Read the body up to the limit of the route or the server unless the headers are refused
+        let matching = format!("{method} {path}");
+        let route = site.route(&method, &path);
+        let limit = route
+            .and_then(|x| x.max_body_size)
+            .or_else(|| Some(route?.upload.as_ref()?.max_size? as usize))
+            .unwrap_or(site.max_body_size);
+        let body = match rejection {
+            Some(rejection) => Err(rejection),
+            None => request::read_body(&mut stream.stream, &head, rest, limit),
+        };
+
+        match body {
+            Ok(body) => {
+                let request_str = format!("{head}{}", String::from_utf8_lossy(&body));
+                // Dump the request and the response in trace mode
+                if trace {
+                    println!("[Request]\n{request_str}");
+                }
+                self.request = Some(Arc::new(request::Request {
+                    method: method.clone(),
+                    path: path.clone(),
+                    query: query.clone(),
+                    head: head.clone(),
+                    body,
+                }));
+                self.respond(&mut stream, &request_str, &method, &path, &query, site);
+                self.request = None;
+
+                // Roll back the transaction the handler left open not to keep its connection
+                database::release();
+                cleanup_request_temporaries();
+            }
+            Err(rejection) => {
+                let status = rejection.status();
+                let reason = status.split_once(' ').map_or(status, |(_, x)| x);
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\n{reason}"
+                );
+                stream.write_all(response.as_bytes()).ok();
+                stream.flush().ok();
+                request::linger(&mut stream.stream);
+            }
+        }
+
+        let duration = started.elapsed();
+        let route = site
+            .routes
+            .contains_key(&matching)
+            .then_some(matching.as_str());
+        stats::finish(route, stream.status, duration);
+        telemetry::record(route.and(Some(&path)), &method, stream.status, duration);
+
+        // Warn the request taking longer than the threshold
+        if site.slow_request > 0.0 && duration.as_secs_f64() > site.slow_request {
+            let duration_ms = duration.as_secs_f64() * 1000.0;
+            tracing::warn!(route = %matching, duration_ms, "slow request");
+            logger::write(&format!(
+                "Warning! slow request \"{matching}\" took {duration_ms:.1}ms"
+            ))
+            .ok();
+        }
+
+        // Write access log when the log file is configured
+        if logger::is_enabled() {
+            let access = logger::Access {
+                time,
+                peer: &peer,
+                method: &method,
+                path: &path,
+                query: &query,
+                status: stream.status,
+                bytes: stream.sent,
+                referer: header_value(&head, "Referer"),
+                user_agent: header_value(&head, "User-Agent"),
+                duration,
+            };
+            logger::access(site.access_log, &access).ok();
+        }
+    }
+
+    /// Write the response of the route (or the probe) matching the request
+    fn respond(
+        &mut self,
+        stream: &mut Wire,
+        request_str: &str,
+        method: &str,
+        path: &str,
+        query: &str,
+        site: &Site,
+    ) {
+        let routes = &site.routes;
+
+        // Generate string to match handler option
+        let matching = [method.to_string(), path.to_string()].join(" ");
+
+        let mut lines = request_str.lines().skip(1);
+
+        // Find the empty line separating headers and body
+        for line in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+        }
+
+        // Get request body
+        let mut body = percent_decode_str(query)
+            .decode_utf8()
+            .unwrap_or_default()
+            .trim()
+            .trim_end_matches(char::from(0))
+            .to_string();
+
+        for line in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            body.push_str(
+                percent_decode_str(line)
+                    .decode_utf8()
+                    .unwrap_or_default()
+                    .trim()
+                    .trim_end_matches(char::from(0)),
+            );
+        }
+
+        // Answer the health and readiness probes and the statistics unless the routes handle them
+        if !routes.contains_key(&matching) {
+            let response = site
+                .probes
+                .respond(self, method, path)
+                .or_else(|| stats::respond(self, site.stats_path.as_deref(), method, path));
+            if let Some(response) = response {
+                stream.write_all(response.as_bytes()).ok();
+                stream.flush().ok();
+                return;
+            }
+        }
+
+        if let Some(handler) = site.route(method, path) {
+            if let Some(kind) = &handler.auth {
+                // Refuse the user or the address locked out by the failed logins
+                let ip = stream
+                    .stream
+                    .peer_addr()
+                    .map(|x| x.ip().to_string())
+                    .unwrap_or_default();
+                let credentials = match kind {
+                    Auth::Basic => basic_credentials(request_str),
+                    Auth::ApiKey => None,
+                };
+                let mut keys = vec![format!("ip:{ip}")];
+                if let Some((user, _)) = &credentials {
+                    keys.push(format!("user:{user}"));
+                }
+                if let Some(wait) = lockout::locked(&keys) {
+                    let response = format!(
+                        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: {}\r\nContent-Type: text/plain\r\n\r\nToo Many Requests",
+                        wait.as_secs_f64().ceil()
+                    );
+                    stream.write_all(response.as_bytes()).ok();
+                    stream.flush().ok();
+                    return;
+                }
+
+                let auth: &Type = &{
+                    self.evaluate_program(handler.users.to_owned());
+                    self.pop_stack()
+                };
+
+                // Check the key sent by the machine instead of the user and the password
+                if let Auth::ApiKey = kind {
+                    let sent = apikey::presented(request_str, handler.api_key_header.as_deref());
+                    let meta = sent
+                        .as_deref()
+                        .and_then(|x| apikey::find(&apikey::load(auth.clone()), x));
+                    // Count the wrong key (the valid one doesn't forgive the address,
+                    // or it could be slipped between the guesses never to be locked out)
+                    if meta.is_none() && sent.is_some() {
+                        lockout::fail(&keys);
+                    }
+                    let Some(meta) = meta else {
+                        let response = "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Bearer\r\nContent-Type: text/plain\r\n\r\nUnauthorized";
+                        stream.write_all(response.as_bytes()).ok();
+                        stream.flush().ok();
+                        return;
+                    };
+
+                    // Push the metadata of the key on the stack
+                    self.stack.push(meta);
+                } else {
+                    // Generate user database from the pairs or the rows of the table
+                    // with the user and the password (or its hash) columns
+                    let mut database: HashMap<String, String> = HashMap::new();
+                    for i in &mut auth.get_list() {
+                        if let Type::Object(_, row) = i {
+                            let column = |names: &[&str]| {
+                                names
+                                    .iter()
+                                    .find_map(|x| row.get(*x))
+                                    .map(|x| x.get_string())
+                            };
+                            if let (Some(user), Some(password)) = (
+                                column(&["user", "username", "name"]),
+                                column(&["password", "password_hash", "hash"]),
+                            ) {
+                                database.insert(user, password);
+                            }
+                            continue;
+                        }
+                        let i = i.get_list();
+                        database.insert(i[0].get_string(), i[1].get_string());
+                    }
+
+                    let (is_auth, (user, pass)) = authenticate(request_str, database);
+
+                    // Count the failure only when the credentials are sent, and forgive
+                    // only the user by the success (not the address trying the others)
+                    match is_auth {
+                        true => lockout::succeed(&keys[1..]),
+                        false if credentials.is_some() => lockout::fail(&keys),
+                        false => {}
+                    }
+
+                    // Processing when fault to authenticate
+                    if !is_auth {
+                        let response = "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Basic realm=\"Restricted area\"\r\nContent-Type: text/plain\r\n\r\nUnauthorized".to_string();
+                        stream.write_all(response.as_bytes()).unwrap();
+                        stream.flush().unwrap();
+                        return;
+                    }
+
+                    // Push user data on the stack
+                    let user_data = Type::List(
+                        vec![Type::String(user.into()), Type::String(pass.into())].into(),
+                    );
+                    self.stack.push(user_data);
+                }
+            }
+
+            // Serve the static files, the uploads, GraphQL or JSON-RPC instead of the handler
+            if handler.statics.is_some()
+                || handler.upload.is_some()
+                || handler.graphql.is_some()
+                || handler.json_rpc.is_some()
+            {
+                // Drop the user data pushed by the authentication
+                if handler.auth.is_some() {
+                    self.pop_stack();
+                }
+                if let Some(statics) = &handler.statics {
+                    if let Err(e) = statics.respond(stream, path) {
+                        self.error_print(format!("Error! {e}\n"));
+                    }
+                } else if let (Some(upload), Some(request)) =
+                    (&handler.upload, self.request.clone())
+                {
+                    let response = match upload.respond(method, path, &request.head, &request.body)
+                    {
+                        upload::Action::Respond(response) => response,
+                        upload::Action::Complete(info, size) => {
+                            // Let the handler take the completed file
+                            self.stack.push(info);
+                            self.evaluate_program(handler.code.to_owned());
+                            match self.pop_stack() {
+                                Type::Error(err) => format!(
+                                    "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\nerror:{}",
+                                    err.kind.status(),
+                                    err.name
+                                ),
+                                _ => upload::completed(size),
+                            }
+                        }
+                    };
+                    stream.write_all(response.as_bytes()).ok();
+                } else if let (Some(graphql), Some(request)) =
+                    (&handler.graphql, self.request.clone())
+                {
+                    let (status, json) = graphql.respond(self, method, query, &request.body);
+                    let response = format!(
+                        "HTTP/1.1 {status} {}\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{json}",
+                        match status {
+                            200 => "OK",
+                            400 => "Bad Request",
+                            _ => "Internal Server Error",
+                        }
+                    );
+                    stream.write_all(response.as_bytes()).ok();
+                } else if let (Some(methods), Some(request)) =
+                    (&handler.json_rpc, self.request.clone())
+                {
+                    // Nothing is responded to the notifications
+                    let response = match methods.respond(self, &request.body) {
+                        Some(json) => format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{json}"
+                        ),
+                        None => "HTTP/1.1 204 No Content\r\n\r\n".to_string(),
+                    };
+                    stream.write_all(response.as_bytes()).ok();
+                }
+                stream.flush().ok();
+                return;
+            }
+
+            // Refuse the request body violating the JSON Schema of the route
+            if let (Some(schema), Some(request)) = (&handler.schema, &self.request) {
+                if let Some(response) = schema.check(&request.body) {
+                    // Drop the user data pushed by the authentication
+                    if handler.auth.is_some() {
+                        self.pop_stack();
+                    }
+                    stream.write_all(response.as_bytes()).ok();
+                    stream.flush().ok();
+                    return;
+                }
+            }
+
+            // Choose the representation of the route by the Accept header
+            let mut code = &handler.code;
+            let mut media = None;
+            if !handler.representations.is_empty() {
+                let offered: Vec<String> = handler
+                    .representations
+                    .iter()
+                    .map(|(x, _)| x.clone())
+                    .collect();
+                let accept = header_value(request_str, "Accept");
+                let Some(chosen) = negotiate::choose(accept, &offered) else {
+                    // Drop the user data pushed by the authentication
+                    if handler.auth.is_some() {
+                        self.pop_stack();
+                    }
+                    let response = format!(
+                        "HTTP/1.1 406 Not Acceptable\r\nVary: Accept\r\nContent-Type: text/plain\r\n\r\nNot Acceptable (available: {})",
+                        offered.join(", ")
+                    );
+                    stream.write_all(response.as_bytes()).ok();
+                    stream.flush().ok();
+                    return;
+                };
+                if let Some((_, x)) = handler.representations.iter().find(|(x, _)| x == chosen) {
+                    code = x;
+                }
+                media = Some(chosen.to_string());
+            }
+
+            let body = Type::String(body.into());
+
+            // Depth of the stack before the auth user data and the body
+            let depth = self.stack.len() - handler.auth.is_some() as usize;
+
+            // Push request body on the stack
+            self.stack.push(body);
+
+            // Evaluate the handler, stopping it between tokens after the timeout
+            let mut evaluate = || {
+                tracing::info_span!("route", route = %matching)
+                    .in_scope(|| self.evaluate_program(code.to_owned()))
+            };
+            let timed_out = match handler.timeout {
+                Some(timeout) => threads::with_timeout(timeout, evaluate).1,
+                None => {
+                    evaluate();
+                    false
+                }
+            };
+            if timed_out {
+                let timeout = handler.timeout.unwrap_or_default();
+                tracing::warn!(route = %matching, timeout, "handler timed out");
+                logger::write(&format!(
+                    "Warning! handler of \"{matching}\" is timed out after {timeout}s"
+                ))
+                .ok();
+                // Drop what the aborted handler left with the auth user data and the body
+                self.stack.truncate(depth);
+                let response = "HTTP/1.1 504 Gateway Timeout\r\nContent-Type: text/plain\r\n\r\nGateway Timeout";
+                stream.write_all(response.as_bytes()).ok();
+                stream.flush().ok();
+                return;
+            }
+
+            let response_value = self.pop_stack();
+
+            // Representation pushes only the body, responded as its media type
+            // (the handle of the opened file is streamed with the content type instead)
+            let structured = matches!(
+                response_value,
+                Type::Json(_) | Type::List(_) | Type::Object(..)
+            ) && !files::is_opened(&response_value);
+            if let Some(media) = &media {
+                if !structured && !matches!(response_value, Type::Error(_)) {
+                    self.stack.push(Type::String(media.clone().into()));
+                }
+            }
+            if let Type::Binary(i) = response_value {
+                // Binary is written after the header as it is without joining them
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {};\r\nContent-Length: {}\r\n\r\n",
+                    self.pop_stack().get_string(),
+                    i.len()
+                );
+                stream
+                    .write_all(header.as_bytes())
+                    .and_then(|_| stream.write_all(&i))
+                    .unwrap();
+            } else if let Some(file) = files::take(&response_value) {
+                // File handle is streamed from the current position without loading it
+                let content_type = self.pop_stack().get_string();
+                let header = |length| {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {length}\r\n\r\n"
+                    )
+                };
+                // Write into the socket directly unless it's dumped,
+                // so that the kernel can copy the file into it (like sendfile)
+                let result = match stream.trace {
+                    true => files::copy(&file, header, stream),
+                    false => files::copy(&file, header, &mut stream.stream),
+                };
+                match result {
+                    // The data written into the socket directly is counted here
+                    Ok(size) => (stream.status, stream.sent) = (200, size),
+                    Err(e) => self.error_print(format!("Error! {e}\n")),
+                }
+            } else if let Type::Error(err) = response_value {
+                // Report the error with the request identifier given by the client or generated
+                if let Some(url) = &site.error_webhook {
+                    let request_id = header_value(request_str, "X-Request-Id")
+                        .map(|x| x.to_string())
+                        .unwrap_or_else(|| format!("{:016x}", rand::random::<u64>()));
+                    webhook::report(url, &err, &matching, &request_id, &self.stack);
+                }
+
+                // Error value is responded as error status without its message
+                stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\nerror:{}",
+                            err.kind.status(),
+                            err.name
+                        )
+                        .as_bytes(),
+                    )
+                    .unwrap();
+            } else if structured {
+                // Json, list and object values are serialized into JSON
+                // as the negotiated media type of the route or "application/json",
+                // dropping what the handler left under them
+                self.stack.truncate(depth);
+                let content_type = media
+                    .clone()
+                    .unwrap_or_else(|| "application/json".to_string());
+                stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: {content_type}; charset=utf-8\r\n\r\n{}",
+                            serde_json::to_string(&response_value.to_json()).unwrap_or_default()
+                        )
+                        .as_bytes(),
+                    )
+                    .unwrap();
+            } else {
+                let content_type = self.pop_stack().get_string();
+                let mut body = response_value.get_string();
+                if livereload::is_enabled() && content_type.starts_with("text/html") {
+                    body = livereload::inject(&body);
+                }
+                stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: {content_type}; charset=utf-8\r\n\r\n{body}"
+                        )
+                        .as_bytes(),
+                    )
+                    .unwrap();
+            }
+            stream.flush().unwrap();
+        } else {
+            // Processing when user access pages that not exist
+
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 404 NOT FOUND\r\nContent-Type: {1}; charset=utf-8\r\n\r\n{0}",
+                        if let Some(handler) = routes.get("not-found") {
+                            self.evaluate_program(handler.code.to_owned());
+                            self.pop_stack().get_string()
+                        } else {
+                            "404 - Not found".to_string()
+                        },
+                        self.pop_stack().get_string()
+                    )
+                    .as_bytes(),
+                )
+                .unwrap();
+            stream.flush().unwrap();
+        };
+    }
+
+    // Main web server function
+    fn server(&mut self, option: Type, code: Type) {
+        // Fill the options missing in the script by the configuration file
+        let option = config::server_option(option);
+
+        let (name, address, buffer_size, black_list, rate_limit): (
+            String,
+            String,
+            usize,
+            Type,
+            f64,
+        ) = if let Type::Object(name, value) = &option {
+            (
+                name.to_owned(),
+                value
+                    .get("address")
+                    .unwrap_or(&Type::String(config::DEFAULT_ADDRESS.to_string().into()))
+                    .get_string(),
+                value
+                    .get("buffer-size")
+                    .unwrap_or(&Type::Number(8192f64))
+                    .get_number() as usize,
+                value
+                    .get("black-list")
+                    .unwrap_or(&Type::List(vec![].into()))
+                    .to_owned(),
+                value
+                    .get("rate-limit")
+                    .unwrap_or(&Type::Number(0f64))
+                    .get_number(),
+            )
+        } else {
+            (
+                "app".to_string(),
+                (option.get_string()),
+                8192,
+                Type::List(vec![].into()),
+                0f64,
+            )
+        };
+
+        // Apply the listen address given at the launch
+        let address = config::address(&address);
+
+        // Show the routes instead of listening in the dry-run
+        if routes::is_dry_run() {
+            routes::print(&name, &address, &code);
+            return;
+        }
+
+        // Set security black-list
+        self.black_list = black_list.clone();
+
+        // Open the key-value store
+        let (_, value) = option.get_object();
+        if let Some(path) = value.get("kv-store") {
+            if let Err(e) = kv::open(&path.get_string()) {
+                println!("Error! {e}");
+            }
+        }
+
+        // Load templates in the directory
+        if let Some(dir) = value.get("template-dir") {
+            let reload = value
+                .get("template-reload")
+                .map(|x| x.get_bool())
+                .unwrap_or(self.mode >= Mode::Debug);
+            if let Err(e) = templates::load_dir(&dir.get_string(), reload) {
+                println!("Error! {e}");
+            }
+        }
+
+        // Load the translations of the locales
+        if let Some(dir) = value.get("locale-dir") {
+            if let Err(e) = i18n::load_dir(&dir.get_string()) {
+                println!("Error! {e}");
+            }
+        }
+        if let Some(locale) = value.get("default-locale") {
+            i18n::set_default(&locale.get_string());
+        }
+
+        // Set database connection pool
+        if let Some(path) = value.get("database") {
+            let get = |key: &str, default: Type| value.get(key).unwrap_or(&default).to_owned();
+            database::configure_pool(
+                &path.get_string(),
+                get("pool-size", Type::Number(4f64)).get_number() as usize,
+                get("busy-timeout", Type::Number(5f64)).get_number(),
+                get("wal", Type::Bool(true)).get_bool(),
+            );
+        }
+
+        // Get route handler options in the Stack code
+        let mut hashmap: HashMap<String, Handler> = HashMap::new();
+        for i in code.get_list() {
+            let matching = i.get_list()[0].get_list();
+            let route = matching[0].get_string();
+            let route_path = route.split_once(' ').map_or("", |x| x.1).trim().to_string();
+            let mut auth = None;
+            let mut user_data = "".to_string();
+
+            if let Some(i) = matching.get(2) {
+                user_data = i.to_owned().get_string();
+                auth = match matching[1].get_string().as_str() {
+                    "auth" => Some(Auth::Basic),
+                    "api-key" => Some(Auth::ApiKey),
+                    // Refuse to serve the route open by the misspelled authentication
+                    kind => {
+                        self.raise(
+                            "start-server",
+                            format!("unknown authentication \"{kind}\" of the route \"{route}\""),
+                        );
+                        return;
+                    }
+                };
+            }
+            // Options of the route like `[(max-body-size) 1024]` after the handler
+            let options = i.get_list().get(2).map(route_options).unwrap_or_default();
+
+            // Handler is the code, or the pairs of the media type and the code
+            // like `[[(application/json) (...)] [(text/html) (...)]]` chosen by Accept
+            let (value, mut representations) = match &i.get_list()[1] {
+                Type::List(pairs) => (
+                    String::new(),
+                    pairs
+                        .iter()
+                        .filter_map(|pair| {
+                            let pair = pair.get_list();
+                            Some((pair.first()?.get_string(), pair.get(1)?.get_string()))
+                        })
+                        .collect(),
+                ),
+                code => (code.get_string(), Vec::new()),
+            };
+
+            // Pairs of the GraphQL route are the fields like "Query.users" and their resolvers
+            let graphql = options.get("graphql").map(|schema| {
+                graphql::Endpoint::new(&schema.get_string(), representations.split_off(0))
+            });
+            // Pairs of the JSON-RPC route are the method names and their code
+            let json_rpc = options
+                .get("json-rpc")
+                .filter(|x| x.get_bool())
+                .map(|_| jsonrpc::Methods::new(representations.split_off(0)));
+            hashmap.insert(
+                route,
+                Handler {
+                    code: value,
+                    representations,
+                    auth,
+                    users: user_data,
+                    max_body_size: options
+                        .get("max-body-size")
+                        .map(|x| x.get_number() as usize),
+                    timeout: options
+                        .get("timeout")
+                        .map(|x| x.get_number())
+                        .filter(|x| x.is_finite()),
+                    api_key_header: options.get("api-key-header").map(|x| x.get_string()),
+                    graphql,
+                    json_rpc,
+                    schema: options.get("schema").map(schema::Schema::new),
+                    upload: options.get("upload").map(|dir| upload::Upload {
+                        mount: route_path.clone(),
+                        dir: dir.get_string(),
+                        max_size: options.get("max-size").map(|x| x.get_number() as u64),
+                    }),
+                    statics: options.get("static").map(|dir| statics::Static {
+                        mount: route_path.clone(),
+                        dir: dir.get_string(),
+                        index: options.get("index").is_some_and(|x| x.get_bool()),
+                        template: options.get("index-template").map(|x| x.get_string()),
+                    }),
+                },
+            );
+        }
+
+        let listener = TcpListener::bind(address.clone()).unwrap();
+        if self.mode >= Mode::Script {
+            print!("Server '{name}' is started on http://{address}. ");
+            println!("Access rate-limit is {rate_limit} seconds. ");
+            print!("The request is read in chunks of {buffer_size} bytes. ");
+            println!(
+                "Security black-list are {}.",
+                black_list
+                    .get_list()
+                    .iter()
+                    .map(|x| x.get_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+        }
+
+        let site = Site {
+            routes: hashmap,
+            probes: Probes::new(&value),
+            buffer_size,
+            max_body_size: value
+                .get("max-body-size")
+                .map_or(request::DEFAULT_MAX_BODY_SIZE, |x| x.get_number() as usize),
+            access_log: match value.get("access-log-format") {
+                Some(name) => {
+                    logger::AccessFormat::parse(&name.get_string()).unwrap_or_else(|| {
+                        println!("Error! unknown access log format \"{}\"", name.get_string());
+                        logger::AccessFormat::Basic
+                    })
+                }
+                None => logger::AccessFormat::Basic,
+            },
+            stats_path: value.get("stats-path").map(|x| x.get_string()),
+            slow_request: value
+                .get("slow-request")
+                .map(|x| x.get_number())
+                .unwrap_or(0.0),
+            error_webhook: value.get("error-webhook").map(|x| x.get_string()),
+        };
+        lockout::configure(
+            value.get("login-attempts").map(|x| x.get_number()),
+            value.get("lockout-time").map(|x| x.get_number()),
+        );
+        stats::start();
+
+        // Keep the state of the application to start each request from it if isolated
+        let isolate = value.get("isolate").is_some_and(|x| x.get_bool());
+        let app = isolate.then(|| Executor {
+            stack: Vec::new(),
+            ..self.clone()
+        });
+
+        let mut last_time: f64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64()
+            - rate_limit;
+
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if (SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs_f64()
+                        - last_time)
+                        > rate_limit
+                    {
+                        last_time = SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap()
+                            .as_secs_f64();
+                        let peer = format!("{:?}", stream.peer_addr().unwrap());
+                        let _span = tracing::info_span!("connection", %peer).entered();
+                        match &app {
+                            // Handle on a fresh stack and a copy of the variables
+                            Some(app) => {
+                                let mut executor = app.clone();
+                                executor.stack.push(Type::String(peer.into()));
+                                executor.handle(stream, &site)
+                            }
+                            None => {
+                                self.stack.push(Type::String(peer.into()));
+                                self.handle(stream, &site)
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    println!("Error: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/// Register the built-in commands of the interpreter
+pub(crate) fn register_builtins(commands: &mut commands::Commands) {
+    // Commands of string processing
+
+    // Repeat string a number of times
+    commands.add("repeat", |executor| {
+        let count = executor.pop_stack().get_number(); // Count
+        let text = executor.pop_stack().get_string(); // String
+        executor
+            .stack
+            .push(Type::String(text.repeat(count as usize).into()));
+        Ok(())
+    });
+
+    // Get unicode character form number
+    commands.add("decode", |executor| {
+        let code = executor.pop_stack().get_number();
+        let result = char::from_u32(code as u32);
+        match result {
+            Some(c) => executor.stack.push(Type::String(c.to_string().into())),
+            None => {
+                executor.raise("number-decoding", "failed of number decoding");
+            }
+        }
+        Ok(())
+    });
+
+    // Encode string by UTF-8
+    commands.add("encode", |executor| {
+        let string = executor.pop_stack().get_string();
+        if let Some(first_char) = string.chars().next() {
+            executor
+                .stack
+                .push(Type::Number((first_char as u32) as f64));
+        } else {
+            executor.raise("string-encoding", "failed of string encoding");
+        }
+        Ok(())
+    });
+
+    // Concatenate the string
+    commands.add("concat", |executor| {
+        let b = executor.pop_stack().get_string();
+        let a = executor.pop_stack().get_string();
+        executor.stack.push(Type::String((a + &b).into()));
+        Ok(())
+    });
+
+    // Escape HTML special characters
+    commands.add("html-escape", |executor| {
+        let text = executor.pop_stack().get_string();
+        executor
+            .stack
+            .push(Type::String(tera::escape_html(&text).into()));
+        Ok(())
+    });
+
+    // Replacing string
+    commands.add("replace", |executor| {
+        let after = executor.pop_stack().get_string();
+        let before = executor.pop_stack().get_string();
+        let text = executor.pop_stack().get_string();
+        executor
+            .stack
+            .push(Type::String(text.replace(&before, &after).into()));
+        Ok(())
+    });
+
+    // Split string by the key
+    commands.add("split", |executor| {
+        let key = executor.pop_stack().get_string();
+        let text = executor.pop_stack().get_string();
+        executor.stack.push(Type::List(
+            text.split(&key)
+                .map(|x| Type::String(x.to_string().into()))
+                .collect(),
+        ));
+        Ok(())
+    });
+
+    // Change string style case
+    commands.add("case", |executor| {
+        let types = executor.pop_stack().get_string();
+        let text = executor.pop_stack().get_string();
+
+        executor.stack.push(Type::String(
+            match types.as_str() {
+                "lower" => text.to_lowercase(),
+                "upper" => text.to_uppercase(),
+                _ => text,
+            }
+            .into(),
+        ));
+        Ok(())
+    });
+
+    // Generate a string by concat list
+    commands.add("join", |executor| {
+        let key = executor.pop_stack().get_string();
+        let mut list = executor.pop_stack().into_list();
+        executor.stack.push(Type::String(
+            list.iter_mut()
+                .map(|x| x.get_string())
+                .collect::<Vec<String>>()
+                .join(&key)
+                .into(),
+        ));
+        Ok(())
+    });
+
+    // Judge is it find in string
+    commands.add("find", |executor| {
+        let word = executor.pop_stack().get_string();
+        let text = executor.pop_stack().get_string();
+        executor.stack.push(Type::Bool(text.contains(&word)));
+        Ok(())
+    });
+
+    // Search by regular expression
+    commands.add("regex", |executor| {
+        let pattern = executor.pop_stack().get_string();
+        let text = executor.pop_stack().get_string();
+
+        let pattern: Regex = match Regex::new(pattern.as_str()) {
+            Ok(i) => i,
+            Err(e) => {
+                executor.raise("regex", e.to_string().replace("Error", ""));
+                return Ok(());
+            }
+        };
+
+        let mut list: Vec<Type> = Vec::new();
+        for i in pattern.captures_iter(text.as_str()) {
+            list.push(Type::String(i[0].to_string().into()))
+        }
+        executor.stack.push(Type::List(list.into()));
+        Ok(())
+    });
+
+    // Commands of I/O
+
+    // Write string in the file
+    commands.add("write-file", |executor| {
+        let mut file = match File::create(Path::new(&executor.pop_stack().get_string())) {
+            Ok(file) => file,
+            Err(e) => {
+                executor.raise("create-file", e);
+                return Ok(());
+            }
+        };
+        if let Err(e) = file.write_all(executor.pop_stack().get_string().as_bytes()) {
+            executor.raise("write-file", e);
+        }
+        Ok(())
+    });
+
+    // Read string in the file
+    commands.add("read-file", |executor| {
+        let name = Path::new(&executor.pop_stack().get_string()).to_owned();
+        match get_file_contents(&name) {
+            Ok(s) => executor.stack.push(Type::String(s.into())),
+            Err(e) => {
+                executor.raise("read-file", e);
+            }
+        };
+        Ok(())
+    });
+
+    commands.add("read-binary", |executor| {
+        fn read_binary_file(path: String) -> io::Result<Vec<u8>> {
+            let file = File::open(Path::new(&path))?;
+            let mut buf_reader = BufReader::new(file);
+            let mut buffer = Vec::new();
+            buf_reader.read_to_end(&mut buffer)?;
+            Ok(buffer)
+        }
+
+        let binary = if let Ok(i) = read_binary_file(executor.pop_stack().get_string()) {
+            i
+        } else {
+            executor.stack.push(Type::error("read-binary"));
+            return Ok(());
+        };
+
+        executor.stack.push(Type::Binary(binary.into()));
+        Ok(())
+    });
+
+    // Create a uniquely-named temporary file
+    commands.add("temp-file", |executor| {
+        let auto_delete = executor.pop_stack().get_bool();
+        let path = tempfile::Builder::new()
+            .prefix("stack-")
+            .tempfile()
+            .and_then(|file| file.keep().map_err(|e| e.error));
+        executor.push_temporary(path.map(|(_, path)| path), auto_delete, "temp-file");
+        Ok(())
+    });
+
+    // Create a uniquely-named temporary directory
+    commands.add("temp-dir", |executor| {
+        let auto_delete = executor.pop_stack().get_bool();
+        let path = tempfile::Builder::new()
+            .prefix("stack-")
+            .tempdir()
+            .map(|dir| dir.keep());
+        executor.push_temporary(path, auto_delete, "temp-dir");
+        Ok(())
+    });
+
+    // Standard input
+    commands.add("input", |executor| {
+        let prompt = executor.pop_stack().get_string();
+        executor
+            .stack
+            .push(Type::String(input(prompt.as_str()).into()));
+        Ok(())
+    });
+
+    // Standard output
+    commands.add("print", |executor| {
+        let a = executor.pop_stack().get_string();
+
+        let a = a.replace("\\n", "\n");
+        let a = a.replace("\\t", "\t");
+        let a = a.replace("\\r", "\r");
+
+        if executor.mode >= Mode::Debug {
+            println!("[Output]: {a}");
+        } else {
+            print!("{a}");
+        }
+        Ok(())
+    });
+
+    // Standard output with new line
+    commands.add("println", |executor| {
+        let a = executor.pop_stack().get_string();
+
+        let a = a.replace("\\n", "\n");
+        let a = a.replace("\\t", "\t");
+        let a = a.replace("\\r", "\r");
+
+        if executor.mode >= Mode::Debug {
+            println!("[Output]: {a}");
+        } else {
+            println!("{a}");
+        }
+        Ok(())
+    });
+
+    // Set level of the output (quiet, normal, verbose or trace)
+    commands.add("log-level", |executor| {
+        let name = executor.pop_stack().get_string();
+        match Mode::parse(&name) {
+            Some(mode) => executor.mode = mode,
+            None => {
+                executor.raise("log-level", format!("unknown log level \"{name}\""));
+            }
+        }
+        Ok(())
+    });
+
+    // Make stack underflow an error or not
+    commands.add("strict", |executor| {
+        executor.strict = executor.pop_stack().get_bool();
+        Ok(())
+    });
+
+    // Configure the log file and its rotation
+    commands.add("log-file", |executor| {
+        let option = executor.pop_stack();
+        if let Err(e) = logger::configure(&option) {
+            executor.raise("log-file", e);
+        }
+        Ok(())
+    });
+
+    // Write message into the log
+    commands.add("log", |executor| {
+        let message = executor.pop_stack().get_string();
+        if let Err(e) = logger::write(&message) {
+            executor.raise("log", e);
+        }
+        Ok(())
+    });
+
+    // Load environment variables from .env file
+    commands.add("dotenv", |executor| {
+        let path = executor.pop_stack().get_string();
+        if let Err(e) = dotenvy::from_path(path) {
+            executor.raise("dotenv", e);
+        }
+        Ok(())
+    });
+
+    // Get environment variable
+    commands.add("get-env", |executor| {
+        let name = executor.pop_stack().get_string();
+        match env::var(&name) {
+            Ok(value) => executor.stack.push(Type::String(value.into())),
+            Err(e) => {
+                executor.raise("get-env", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Get arguments of the program given after "--"
+    commands.add("args", |executor| {
+        executor.stack.push(Type::List(
+            config::arguments()
+                .into_iter()
+                .map(|x| Type::String(x.into()))
+                .collect(),
+        ));
+        Ok(())
+    });
+
+    // Get raw command-line arguments of the process
+    commands.add("args-cmd", |executor| {
+        executor.stack.push(Type::List(
+            env::args()
+                .collect::<Vec<_>>()
+                .iter()
+                .map(|x| Type::String(x.to_string().into()))
+                .collect(),
+        ));
+        Ok(())
+    });
+
+    // Commands of control
+
+    // Push error value if the condition is false
+    commands.add("assert", |executor| {
+        let message = executor.pop_stack().get_string();
+        if !executor.pop_stack().get_bool() {
+            executor.raise("assert", format!("assertion failed: {message}"));
+        }
+        Ok(())
+    });
+
+    // Evaluate the code, and evaluate the handler with the error if it results error
+    commands.add("try", |executor| {
+        let handler = executor.pop_stack().get_string();
+        let code = executor.pop_stack().get_string();
+        let old_len = executor.stack.len();
+        executor.evaluate_program(code);
+
+        let result = executor.stack.split_off(old_len.min(executor.stack.len()));
+        match result.iter().rev().find(|x| matches!(x, Type::Error(_))) {
+            Some(err) => {
+                executor.stack.push(err.clone());
+                executor.evaluate_program(handler);
+            }
+            None => executor.stack.extend(result),
+        }
+        Ok(())
+    });
+
+    // Create error value by the name and the message
+    commands.add("error-new", |executor| {
+        let message = executor.pop_stack().get_string();
+        let name = executor.pop_stack().get_string();
+        let err = StackError::new(&name, message).at(executor.position);
+        executor.stack.push(Type::Error(err));
+        Ok(())
+    });
+
+    // Create error value caused by the error
+    commands.add("error-wrap", |executor| {
+        let message = executor.pop_stack().get_string();
+        let name = executor.pop_stack().get_string();
+        let err = StackError::new(&name, message).at(executor.position);
+        match executor.pop_stack() {
+            Type::Error(cause) => executor.stack.push(Type::Error(err.caused_by(cause))),
+            _ => executor.stack.push(Type::Error(err)),
+        }
+        Ok(())
+    });
+
+    // Get properties of the error value
+    for name in [
+        "error-kind",
+        "error-name",
+        "error-message",
+        "error-position",
+        "error-cause",
+    ] {
+        let command = name.to_string();
+        commands.add(name, move |executor| {
+            let Type::Error(err) = executor.pop_stack() else {
+                executor.stack.push(Type::Null);
+                return Ok(());
+            };
+            executor.stack.push(match command.as_str() {
+                "error-kind" => Type::String(err.kind.name().to_string().into()),
+                "error-name" => Type::String(err.name.into()),
+                "error-message" => Type::String(err.message.into()),
+                "error-position" => err
+                    .position
+                    .map(|x| {
+                        Type::List(
+                            vec![Type::Number(x.line as f64), Type::Number(x.column as f64)].into(),
+                        )
+                    })
+                    .unwrap_or(Type::Null),
+                _ => err.cause.map(|x| Type::Error(*x)).unwrap_or(Type::Null),
+            });
+            Ok(())
+        });
+    }
+
+    // Evaluate string as program
+    commands.add("eval", |executor| {
+        let code = executor.pop_stack().get_string();
+        executor.evaluate_program(code);
+        Ok(())
+    });
+
+    commands.add("eval-unsafe", |executor| {
+        let code = executor.pop_stack().get_string();
+        executor.evaluate_program_unsafe(code);
+        Ok(())
+    });
+
+    // Conditional branch
+    commands.add("if", |executor| {
+        let condition = executor.pop_stack().get_bool(); // Condition
+        let code_else = executor.pop_stack().get_string(); // Code of else
+        let code_if = executor.pop_stack().get_string(); // Code of If
+        if condition {
+            executor.evaluate_program(code_if)
+        } else {
+            executor.evaluate_program(code_else)
+        };
+        Ok(())
+    });
+
+    // Loop while condition is true
+    commands.add("while", |executor| {
+        let cond = executor.pop_stack().get_string();
+        let code = executor.pop_stack().get_string();
+        while {
+            executor.evaluate_program(cond.clone());
+            executor.pop_stack().get_bool()
+        } {
+            executor.evaluate_program(code.clone());
+        }
+        Ok(())
+    });
+
+    // Generate a thread
+    commands.add("thread", |executor| {
+        let code = executor.pop_stack().get_string();
+        threads::spawn(code, executor.clone(), None);
+        Ok(())
+    });
+
+    // Start thread, and push its handle to kill it
+    commands.add("thread-spawn", |executor| {
+        let code = executor.pop_stack().get_string();
+        executor
+            .stack
+            .push(threads::spawn(code, executor.clone(), None));
+        Ok(())
+    });
+
+    // Start thread that is killed after the seconds
+    commands.add("thread-timeout", |executor| {
+        let seconds = executor.pop_stack().get_number();
+        let code = executor.pop_stack().get_string();
+        executor
+            .stack
+            .push(threads::spawn(code, executor.clone(), Some(seconds)));
+        Ok(())
+    });
+
+    // Request the thread to stop
+    commands.add("thread-kill", |executor| {
+        let handle = executor.pop_stack();
+        executor.stack.push(Type::Bool(threads::kill(&handle)));
+        Ok(())
+    });
+
+    // Start code on a worker, and push the future of its result
+    commands.add("async", |executor| {
+        let code = executor.pop_stack().get_string();
+        executor.stack.push(future::spawn(code, executor.clone()));
+        Ok(())
+    });
+
+    // Wait the future, and push its result
+    commands.add("await", |executor| {
+        let future = executor.pop_stack();
+        match future::wait(&future) {
+            Ok(result) => executor.stack.push(result),
+            Err(e) => {
+                executor.raise("await", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Run code whenever the path changes
+    commands.add("watch", |executor| {
+        let code = executor.pop_stack().get_string();
+        let path = executor.pop_stack().get_string();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let watcher = notify::recommended_watcher(sender).and_then(|mut watcher| {
+            watcher.watch(Path::new(&path), notify::RecursiveMode::Recursive)?;
+            Ok(watcher)
+        });
+        let watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                executor.raise("watch", e);
+                return Ok(());
+            }
+        };
+
+        let mut executor = executor.clone();
+        thread::spawn(move || {
+            // Keep the watcher alive while the thread is running
+            let _watcher = watcher;
+            for event in receiver.into_iter().flatten() {
+                let kind = match event.kind {
+                    notify::EventKind::Create(_) => "create",
+                    notify::EventKind::Modify(_) => "modify",
+                    notify::EventKind::Remove(_) => "remove",
+                    _ => continue,
+                };
+                let paths = event
+                    .paths
+                    .iter()
+                    .map(|x| Type::String(x.to_string_lossy().to_string().into()))
+                    .collect();
+
+                // Push event details on the stack
+                executor.stack.push(Type::Object(
+                    "event".to_string(),
+                    HashMap::from([
+                        ("kind".to_string(), Type::String(kind.to_string().into())),
+                        ("paths".to_string(), Type::List(paths)),
+                    ]),
+                ));
+                executor.evaluate_program(code.clone());
+            }
+        });
+        Ok(())
+    });
+
+    // Run code when the process receives the signal
+    #[cfg(unix)]
+    commands.add("on-signal", |executor| {
+        use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+
+        let code = executor.pop_stack().get_string();
+        let name = executor.pop_stack().get_string();
+        let signal = match name.to_uppercase().trim_start_matches("SIG") {
+            "INT" => SIGINT,
+            "TERM" => SIGTERM,
+            "HUP" => SIGHUP,
+            _ => {
+                executor.raise("on-signal", format!("unsupported signal \"{name}\""));
+                return Ok(());
+            }
+        };
+
+        let mut signals = match signal_hook::iterator::Signals::new([signal]) {
+            Ok(signals) => signals,
+            Err(e) => {
+                executor.raise("on-signal", e);
+                return Ok(());
+            }
+        };
+        HANDLED_SIGNALS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(signal);
+        let mut executor = executor.clone();
+        thread::spawn(move || {
+            for _ in signals.forever() {
+                executor.evaluate_program(code.clone());
+            }
+        });
+        Ok(())
+    });
+
+    #[cfg(not(unix))]
+    commands.add("on-signal", |executor| {
+        executor.pop_stack();
+        executor.pop_stack();
+        executor.raise("on-signal", "signal handling is not supported");
+        Ok(())
+    });
+
+    // Exit a process
+    commands.add("exit", |executor| {
+        let status = executor.pop_stack().get_number() as i32;
+        if EXIT_PROCESS.load(Ordering::Relaxed) {
+            cleanup_temporaries();
+            telemetry::shutdown();
+            std::process::exit(status);
+        }
+        // Stop the evaluation and leave the exit to the host
+        executor.exit = Some(status);
+        Ok(())
+    });
+
+    // Commands of list processing
+
+    // Get list value by index
+    commands.add("get", |executor| {
+        let index = executor.pop_stack().get_number() as usize;
+        let list: Vec<Type> = executor.pop_stack().into_list();
+        if list.len() > index {
+            executor.stack.push(list[index].clone());
+        } else {
+            executor.raise("index-out-range", "Index specification is out of range");
+        }
+        Ok(())
+    });
+
+    // Set list value by index
+    commands.add("set", |executor| {
+        let value = executor.pop_stack();
+        let index = executor.pop_stack().get_number() as usize;
+        let list = executor.pop_stack();
+        executor.update_list(list, |list| {
+            let item = list.get_mut(index)?;
+            *item = value;
+            Some(())
+        });
+        Ok(())
+    });
+
+    // Delete list value by index
+    commands.add("del", |executor| {
+        let index = executor.pop_stack().get_number() as usize;
+        let list = executor.pop_stack();
+        executor.update_list(list, |list| {
+            (list.len() > index).then(|| {
+                list.remove(index);
+            })
+        });
+        Ok(())
+    });
+
+    // Append value in the list
+    commands.add("append", |executor| {
+        let data = executor.pop_stack();
+        let list = executor.pop_stack();
+        executor.update_list(list, |list| {
+            list.push(data);
+            Some(())
+        });
+        Ok(())
+    });
+
+    // Insert value in the list
+    commands.add("insert", |executor| {
+        let data = executor.pop_stack();
+        let index = executor.pop_stack().get_number() as usize;
+        let list = executor.pop_stack();
+        executor.update_list(list, |list| {
+            (list.len() >= index).then(|| list.insert(index, data))
+        });
+        Ok(())
+    });
+
+    // Get index of the list
+    commands.add("index", |executor| {
+        let target = executor.pop_stack();
+        let list = executor.pop_stack().into_list();
+
+        for (index, item) in list.iter().enumerate() {
+            if target == *item {
+                executor.stack.push(Type::Number(index as f64));
+                return Ok(());
+            }
+        }
+        executor.raise("item-not-found", "item not found in the list");
+        Ok(())
+    });
+
+    // Sorting in the list
+    commands.add("sort", |executor| {
+        let mut list: Vec<String> = executor
+            .pop_stack()
+            .get_list()
+            .iter()
+            .map(|x| x.to_owned().get_string())
+            .collect();
+        list.sort();
+        executor.stack.push(Type::List(
+            list.iter()
+                .map(|x| Type::String(x.to_string().into()))
+                .collect(),
+        ));
+        Ok(())
+    });
+
+    // reverse in the list
+    commands.add("reverse", |executor| {
+        let mut list = executor.pop_stack().into_list();
+        list.reverse();
+        executor.stack.push(Type::List(list.into()));
+        Ok(())
+    });
+
+    // Iteration for the list
+    commands.add("for", |executor| {
+        let code = executor.pop_stack().get_string();
+        let vars = executor.pop_stack().get_string();
+        let list = executor.pop_stack().into_list();
+
+        list.iter().for_each(|x| {
+            executor
+                .memory
+                .entry(vars.clone())
+                .and_modify(|value| *value = x.clone())
+                .or_insert(x.clone());
+            executor.evaluate_program(code.clone());
+        });
+        Ok(())
+    });
+
+    // Generate a range
+    commands.add("range", |executor| {
+        let step = executor.pop_stack().get_number();
+        let max = executor.pop_stack().get_number();
+        let min = executor.pop_stack().get_number();
+
+        let mut range: Vec<Type> = Vec::new();
+        let mut i = min;
+
+        while i < max {
+            range.push(Type::Number(i));
+            i += step;
+        }
+
+        executor.stack.push(Type::List(range.into()));
+        Ok(())
+    });
+
+    // Get length of list
+    commands.add("len", |executor| {
+        let data = executor.pop_stack().into_list();
+        executor.stack.push(Type::Number(data.len() as f64));
+        Ok(())
+    });
+
+    // Commands of functional programming
+
+    // Mapping a list
+    commands.add("map", |executor| {
+        let code = executor.pop_stack().get_string();
+        let vars = executor.pop_stack().get_string();
+        let list = executor.pop_stack().into_list();
+
+        let mut result_list = Vec::new();
+        for x in list.iter() {
+            executor
+                .memory
+                .entry(vars.clone())
+                .and_modify(|value| *value = x.clone())
+                .or_insert(x.clone());
+
+            executor.evaluate_program(code.clone());
+            result_list.push(executor.pop_stack());
+        }
+
+        executor.stack.push(Type::List(result_list.into()));
+        Ok(())
+    });
+
+    // Filtering a list value
+    commands.add("filter", |executor| {
+        let code = executor.pop_stack().get_string();
+        let vars = executor.pop_stack().get_string();
+        let list = executor.pop_stack().into_list();
+
+        let mut result_list = Vec::new();
+
+        for x in list.iter() {
+            executor
+                .memory
+                .entry(vars.clone())
+                .and_modify(|value| *value = x.clone())
+                .or_insert(x.clone());
+
+            executor.evaluate_program(code.clone());
+            if executor.pop_stack().get_bool() {
+                result_list.push(x.clone());
+            }
+        }
+
+        executor.stack.push(Type::List(result_list.into()));
+        Ok(())
+    });
+
+    // Generate value from list
+    commands.add("reduce", |executor| {
+        let code = executor.pop_stack().get_string();
+        let now = executor.pop_stack().get_string();
+        let acc = executor.pop_stack().get_string();
+        let list = executor.pop_stack().into_list();
+
+        executor
+            .memory
+            .entry(acc.clone())
+            .and_modify(|value| *value = Type::String("".into()))
+            .or_insert(Type::String("".into()));
+
+        for x in list.iter() {
+            executor
+                .memory
+                .entry(now.clone())
+                .and_modify(|value| *value = x.clone())
+                .or_insert(x.clone());
+
+            executor.evaluate_program(code.clone());
+            let result = executor.pop_stack();
+
+            executor
+                .memory
+                .entry(acc.clone())
+                .and_modify(|value| *value = result.clone())
+                .or_insert(result);
+        }
+
+        let result = executor.memory.get(&acc);
+        executor
+            .stack
+            .push(result.unwrap_or(&Type::String("".into())).clone());
+
+        executor
+            .memory
+            .entry(acc.clone())
+            .and_modify(|value| *value = Type::String("".into()))
+            .or_insert(Type::String("".into()));
+        Ok(())
+    });
+
+    // Commands of memory manage
+
+    // Pop in the stack
+    commands.add("pop", |executor| {
+        executor.pop_stack();
+        Ok(())
+    });
+
+    // Remove all values in the stack
+    commands.add("clear-stack", |executor| {
+        executor.stack.clear();
+        Ok(())
+    });
+
+    // Remove all variables in the memory
+    commands.add("clear-memory", |executor| {
+        executor.memory.clear();
+        executor.show_variables();
+        Ok(())
+    });
+
+    // Get size of stack
+    commands.add("size-stack", |executor| {
+        let len: f64 = executor.stack.len() as f64;
+        executor.stack.push(Type::Number(len));
+        Ok(())
+    });
+
+    // Define variable at memory
+    commands.add("var", |executor| {
+        let name = executor.pop_stack().get_string();
+        let data = executor.pop_stack();
+        executor
+            .memory
+            .entry(name)
+            .and_modify(|value| *value = data.clone())
+            .or_insert(data);
+        executor.show_variables();
+        Ok(())
+    });
+
+    // Define variable shared by all of executors
+    commands.add("global-set", |executor| {
+        let name = executor.pop_stack().get_string();
+        let data = executor.pop_stack();
+        global::set(&name, data);
+        Ok(())
+    });
+
+    // Get variable shared by all of executors
+    commands.add("global-get", |executor| {
+        let name = executor.pop_stack().get_string();
+        match global::get(&name) {
+            Some(value) => executor.stack.push(value),
+            None => {
+                executor.raise(
+                    "global-not-found",
+                    format!("\"{name}\" is not defined globally"),
+                );
+            }
+        }
+        Ok(())
+    });
+
+    // Update shared variable atomically by the result of code
+    commands.add("global-update", |executor| {
+        let code = executor.pop_stack().get_string();
+        let name = executor.pop_stack().get_string();
+        let result = global::update(&name, |value| {
+            executor.stack.push(value);
+            executor.evaluate_program(code);
+            executor.pop_stack()
+        });
+        executor.stack.push(result);
+        Ok(())
+    });
+
+    // Get data type of value
+    commands.add("type", |executor| {
+        let result = match executor.pop_stack() {
+            Type::Number(_) => "number".to_string(),
+            Type::String(_) => "string".to_string(),
+            Type::Bool(_) => "bool".to_string(),
+            Type::List(_) => "list".to_string(),
+            Type::Json(_) => "json".to_string(),
+            Type::Error(_) => "error".to_string(),
+            Type::Binary(_) => "binary".to_string(),
+            Type::Ref(_) => "ref".to_string(),
+            Type::Null => "null".to_string(),
+            Type::Object(name, _) => name.to_string(),
+        };
+
+        executor.stack.push(Type::String(result.into()));
+        Ok(())
+    });
+
+    // Explicit data type casting
+    commands.add("cast", |executor| {
+        let types = executor.pop_stack().get_string();
+        let mut value = executor.pop_stack();
+        match types.as_str() {
+            "number" => executor.stack.push(Type::Number(value.get_number())),
+            "string" => executor.stack.push(Type::String(value.get_string().into())),
+            "bool" => executor.stack.push(Type::Bool(value.get_bool())),
+            "list" => executor.stack.push(Type::List(value.get_list().into())),
+            "json" => executor.stack.push(Type::Json(value.get_json().into())),
+            "error" => executor.stack.push(Type::Error(StackError::parse(
+                value.get_string().trim_start_matches("error:"),
+            ))),
+            _ => executor.stack.push(value),
+        }
+        Ok(())
+    });
+
+    // Get memory information
+    commands.add("mem", |executor| {
+        let list = executor
+            .memory
+            .keys()
+            .map(|name| Type::String(name.as_str().into()));
+        executor.stack.push(Type::List(list.collect()));
+        Ok(())
+    });
+
+    // Free up memory space of variable
+    commands.add("free", |executor| {
+        let name = executor.pop_stack().get_string();
+        executor.memory.remove(name.as_str());
+        executor.show_variables();
+        Ok(())
+    });
+
+    // Copy stack's top value
+    commands.add("copy", |executor| {
+        let data = executor.pop_stack();
+        executor.stack.push(data.clone());
+        executor.stack.push(data);
+        Ok(())
+    });
+
+    // Swap stack's top 2 value
+    commands.add("swap", |executor| {
+        let b = executor.pop_stack();
+        let a = executor.pop_stack();
+        executor.stack.push(b);
+        executor.stack.push(a);
+        Ok(())
+    });
+
+    // Copy the second value to the top
+    commands.add("over", |executor| {
+        let b = executor.pop_stack();
+        let a = executor.pop_stack();
+        executor.stack.push(a.clone());
+        executor.stack.push(b);
+        executor.stack.push(a);
+        Ok(())
+    });
+
+    // Rotate stack's top 3 value
+    commands.add("rot", |executor| {
+        let c = executor.pop_stack();
+        let b = executor.pop_stack();
+        let a = executor.pop_stack();
+        executor.stack.push(b);
+        executor.stack.push(c);
+        executor.stack.push(a);
+        Ok(())
+    });
+
+    // Copy the value of depth n to the top
+    commands.add("pick", |executor| {
+        let depth = executor.pop_stack().get_number() as usize;
+        if depth < executor.stack.len() {
+            let data = executor.stack[executor.stack.len() - 1 - depth].clone();
+            executor.stack.push(data);
+        } else {
+            executor.raise(
+                "stack-out-range",
+                "Stack depth specification is out of range",
+            );
+        }
+        Ok(())
+    });
+
+    // Move the value of depth n to the top
+    commands.add("roll", |executor| {
+        let depth = executor.pop_stack().get_number() as usize;
+        if depth < executor.stack.len() {
+            let data = executor.stack.remove(executor.stack.len() - 1 - depth);
+            executor.stack.push(data);
+        } else {
+            executor.raise(
+                "stack-out-range",
+                "Stack depth specification is out of range",
+            );
+        }
+        Ok(())
+    });
+
+    // Copy stack's top n value
+    commands.add("dup-n", |executor| {
+        let count = executor.pop_stack().get_number() as usize;
+        if count <= executor.stack.len() {
+            let data = executor.stack[executor.stack.len() - count..].to_vec();
+            executor.stack.extend(data);
+        } else {
+            executor.raise(
+                "stack-out-range",
+                "Stack depth specification is out of range",
+            );
+        }
+        Ok(())
+    });
+
+    // Pop stack's top n value
+    commands.add("drop-n", |executor| {
+        let count = executor.pop_stack().get_number() as usize;
+        for _ in 0..count {
+            executor.pop_stack();
+        }
+        Ok(())
+    });
+
+    // Make a shared reference of the value
+    commands.add("ref", |executor| {
+        let data = executor.pop_stack();
+        executor.stack.push(Type::Ref(Reference::new(data)));
+        Ok(())
+    });
+
+    // Get copy of the referenced value
+    commands.add("deref", |executor| {
+        let data = match executor.pop_stack() {
+            Type::Ref(reference) => reference.get(),
+            other => other,
+        };
+        executor.stack.push(data);
+        Ok(())
+    });
+
+    // Commands of times
+
+    // Get now time as unix epoch
+    commands.add("now-time", |executor| {
+        executor.stack.push(Type::Number(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs_f64(),
+        ));
+        Ok(())
+    });
+
+    // Format unix epoch time by the pattern
+    for name in ["time-format", "time-format-tz"] {
+        let command = name.to_string();
+        commands.add(name, move |executor| {
+            let zone = if command == "time-format-tz" {
+                executor.pop_stack().get_string()
+            } else {
+                "local".to_string()
+            };
+            let pattern = executor.pop_stack().get_string();
+            let epoch = executor.pop_stack().get_number();
+            let Some(zone) = executor.get_zone(&zone) else {
+                return Ok(());
+            };
+            match time_format(epoch, &pattern, &zone) {
+                Some(text) => executor.stack.push(Type::String(text.into())),
+                None => {
+                    executor.raise("time-format", format!("invalid time format \"{pattern}\""));
+                }
+            }
+            Ok(())
+        });
+    }
+
+    // Parse string into unix epoch time by the pattern
+    for name in ["time-parse", "time-parse-tz"] {
+        let command = name.to_string();
+        commands.add(name, move |executor| {
+            let zone = if command == "time-parse-tz" {
+                executor.pop_stack().get_string()
+            } else {
+                "local".to_string()
+            };
+            let pattern = executor.pop_stack().get_string();
+            let text = executor.pop_stack().get_string();
+            let Some(zone) = executor.get_zone(&zone) else {
+                return Ok(());
+            };
+            match time_parse(&text, &pattern, &zone) {
+                Ok(epoch) => executor.stack.push(Type::Number(epoch)),
+                Err(e) => {
+                    executor.raise("time-parse", e);
+                }
+            }
+            Ok(())
+        });
+    }
+
+    // Get offset seconds of the time zone from UTC
+    commands.add("time-offset", |executor| {
+        let zone = executor.pop_stack().get_string();
+        let epoch = executor.pop_stack().get_number();
+        let Some(zone) = executor.get_zone(&zone) else {
+            return Ok(());
+        };
+        match zone.offset(epoch) {
+            Some(offset) => executor.stack.push(Type::Number(offset as f64)),
+            None => executor.stack.push(Type::error("time-offset")),
+        }
+        Ok(())
+    });
+
+    // Run code periodically by interval seconds or cron expression
+    commands.add("schedule", |executor| {
+        let code = executor.pop_stack().get_string();
+        let spec = executor.pop_stack();
+        match scheduler::schedule(&spec, code, executor.clone()) {
+            Ok(id) => executor.stack.push(Type::Number(id as f64)),
+            Err(e) => {
+                executor.raise("schedule", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Run code once after the seconds, or repeatedly at the interval
+    for name in ["after", "every"] {
+        let command = name.to_string();
+        commands.add(name, move |executor| {
+            let code = executor.pop_stack().get_string();
+            let seconds = executor.pop_stack().get_number();
+            let result = if command == "after" {
+                scheduler::after(seconds, code, executor.clone())
+            } else {
+                scheduler::every(seconds, code, executor.clone())
+            };
+            let id = result.map_err(|e| StackError::new(&command, e))?;
+            executor.stack.push(Type::Number(id as f64));
+            Ok(())
+        });
+    }
+
+    // Get list of scheduled tasks
+    commands.add("schedule-list", |executor| {
+        executor.stack.push(Type::List(scheduler::list().into()));
+        Ok(())
+    });
+
+    // Cancel the scheduled task or timer
+    for name in ["schedule-cancel", "cancel"] {
+        commands.add(name, |executor| {
+            let id = executor.pop_stack().get_number() as usize;
+            executor.stack.push(Type::Bool(scheduler::cancel(id)));
+            Ok(())
+        });
+    }
+
+    // Run code in the background job queue
+    commands.add("job-enqueue", |executor| {
+        let code = executor.pop_stack().get_string();
+        let payload = executor.pop_stack();
+        // Use the code of the function if it is named
+        let code = match executor.memory.get(&code) {
+            Some(Type::String(function)) => function.to_string(),
+            _ => code,
+        };
+        let id = jobs::enqueue(code, payload, executor.clone());
+        executor.stack.push(Type::Number(id as f64));
+        Ok(())
+    });
+
+    // Get status of the background job
+    commands.add("job-status", |executor| {
+        let id = executor.pop_stack().get_number() as usize;
+        match jobs::status(id) {
+            Some(status) => executor.stack.push(status),
+            None => {
+                executor.raise("job-not-found", format!("job {id} is not found"));
+            }
+        }
+        Ok(())
+    });
+
+    // Configure workers, retry policy and TTL of the finished jobs of the job queue
+    commands.add("job-config", |executor| {
+        let option = executor.pop_stack();
+        jobs::configure(&option);
+        Ok(())
+    });
+
+    // Sleep fixed time
+    commands.add("sleep", |executor| {
+        sleep(Duration::from_secs_f64(executor.pop_stack().get_number()));
+        Ok(())
+    });
+
+    // Commands of object oriented system
+
+    // Generate a instance of object
+    commands.add("instance", |executor| {
+        let data = executor.pop_stack().into_list();
+        let class = executor.pop_stack().into_list();
+        let mut object: HashMap<String, Type> = HashMap::new();
+
+        let name = if !class.is_empty() {
+            class[0].get_string()
+        } else {
+            executor.raise("instance-name", "the type name is not found.");
+            return Ok(());
+        };
+
+        let mut index = 0;
+        for item in &mut class.to_owned()[1..class.len()].iter() {
+            let item = item.to_owned();
+            if item.get_list().len() == 1 {
+                let element = match data.get(index) {
+                    Some(value) => value,
+                    None => {
+                        executor.raise("instance-shortage", "initial data is shortage");
+                        return Ok(());
+                    }
+                };
+                object.insert(
+                    item.get_list()[0].to_owned().get_string(),
+                    element.to_owned(),
+                );
+                index += 1;
+            } else if item.get_list().len() >= 2 {
+                let item = item.get_list();
+                object.insert(item[0].clone().get_string(), item[1].clone());
+            } else {
+                executor.raise("instance-default", "the class data structure is wrong.");
+            }
+        }
+
+        // Call the constructor method if it is defined
+        if let Some(init) = object.get("init") {
+            let program = init.get_string();
+            let data = Type::Object(name, object);
+            executor
+                .memory
+                .entry("executor".to_string())
+                .and_modify(|value| *value = data.clone())
+                .or_insert(data.clone());
+
+            let old_len = executor.stack.len();
+            executor.evaluate_program(program);
+
+            // Error left by the constructor takes the place of the instance
+            let result: Vec<Type> = executor
+                .stack
+                .drain(old_len.min(executor.stack.len())..)
+                .collect();
+            if let Some(err) = result
+                .into_iter()
+                .rev()
+                .find(|x| matches!(x, Type::Error(_)))
+            {
+                executor.stack.push(err);
+            } else {
+                executor
+                    .stack
+                    .push(executor.memory.get("executor").cloned().unwrap_or(data));
+            }
+            return Ok(());
+        }
+
+        executor.stack.push(Type::Object(name, object));
+        Ok(())
+    });
+
+    // Get property of object
+    commands.add("property", |executor| {
+        let name = executor.pop_stack().get_string();
+        let (_, object) = executor.pop_stack().get_object();
+        executor.stack.push(
+            object
+                .get(name.as_str())
+                .unwrap_or(&Type::error("property"))
+                .clone(),
+        );
+        Ok(())
+    });
+
+    // Call the method of object
+    commands.add("method", |executor| {
+        let method = executor.pop_stack().get_string();
+        let (name, value) = executor.pop_stack().get_object();
+        let data = Type::Object(name, value.clone());
+        executor
+            .memory
+            .entry("executor".to_string())
+            .and_modify(|value| *value = data.clone())
+            .or_insert(data);
+
+        let program: String = match value.get(&method) {
+            Some(i) => i.to_owned().get_string().to_string(),
+            None => "".to_string(),
+        };
+
+        executor.evaluate_program(program);
+        Ok(())
+    });
+
+    // Modify the property of object
+    commands.add("modify", |executor| {
+        let data = executor.pop_stack();
+        let property = executor.pop_stack().get_string();
+        let target = executor.pop_stack();
+
+        // Modify in place if the object is referenced
+        if let Type::Ref(reference) = &target {
+            if let Type::Object(_, object) = &mut *reference.lock() {
+                object.insert(property, data);
+            }
+            executor.stack.push(target);
+            return Ok(());
+        }
+
+        let (name, mut value) = target.get_object();
+        value
+            .entry(property)
+            .and_modify(|value| *value = data.clone())
+            .or_insert(data.clone());
+
+        executor.stack.push(Type::Object(name, value));
+        Ok(())
+    });
+
+    // Get value by dotted path
+    commands.add("get-path", |executor| {
+        let path = executor.pop_stack().get_string();
+        let data = executor.pop_stack();
+        let keys: Vec<&str> = path.split('.').filter(|x| !x.is_empty()).collect();
+        match data.get_path(&keys) {
+            Some(value) => executor.stack.push(value),
+            None => {
+                executor.raise(
+                    "path-not-found",
+                    format!("the path \"{path}\" is not found"),
+                );
+            }
+        }
+        Ok(())
+    });
+
+    // Set value by dotted path
+    commands.add("set-path", |executor| {
+        let value = executor.pop_stack();
+        let path = executor.pop_stack().get_string();
+        let mut data = executor.pop_stack();
+        let keys: Vec<&str> = path.split('.').filter(|x| !x.is_empty()).collect();
+        match data.set_path(&keys, value) {
+            Ok(()) => executor.stack.push(data),
+            Err(PathError::NotFound) => {
+                executor.raise(
+                    "path-not-found",
+                    format!("the path \"{path}\" is not found"),
+                );
+            }
+            Err(PathError::Cyclic) => {
+                executor.raise(
+                    "cyclic-reference",
+                    format!("the path \"{path}\" goes through the reference in itself"),
+                );
+            }
+        }
+        Ok(())
+    });
+
+    // Get all of properties
+    commands.add("all", |executor| {
+        let (_, value) = executor.pop_stack().get_object();
+        executor.stack.push(Type::List(
+            value
+                .keys()
+                .map(|x| Type::String(x.to_owned().into()))
+                .collect(),
+        ));
+        Ok(())
+    });
+
+    // Get system information
+    commands.add("sys-info", |executor| {
+        let option = executor.pop_stack().get_string();
+        executor.stack.push(match option.as_str() {
+            "os-release" => Type::String(os_release().unwrap_or("".to_string()).into()),
+            "os-type" => Type::String(os_type().unwrap_or("".to_string()).into()),
+            "cpu-num" => Type::Number(cpu_num().unwrap_or(0) as f64),
+            "cpu-speed" => Type::Number(cpu_speed().unwrap_or(0) as f64),
+            "host-name" => Type::String(hostname().unwrap_or("".to_string()).into()),
+            "mem-size" => match mem_info() {
+                Ok(info) => Type::Number(info.total as f64),
+                Err(_) => Type::error("sys-info"),
+            },
+            "mem-used" => match mem_info() {
+                Ok(info) => Type::Number((info.total - info.free) as f64),
+                Err(_) => Type::error("sys-info"),
+            },
+            _ => Type::error("sys-info"),
+        });
+        Ok(())
+    });
+
+    // Commands of web server
+
+    // Get value from json
+    commands.add("get-json", |executor| {
+        let key = executor.pop_stack().get_string();
+        let json = executor.pop_stack().get_json();
+        if let (Value::Array(array), Ok(index)) = (&json, key.parse::<usize>()) {
+            executor.stack.push(Type::Json(
+                array.get(index).cloned().unwrap_or(Value::Null).into(),
+            ))
+        } else {
+            executor.stack.push(Type::Json(json[key].clone().into()))
+        }
+        Ok(())
+    });
+
+    // Parse string as json strictly
+    commands.add("json-parse", |executor| {
+        let text = executor.pop_stack().get_string();
+        match serde_json::from_str::<Value>(&text) {
+            Ok(json) => executor.stack.push(Type::Json(json.into())),
+            Err(e) => {
+                executor.raise("json-parse", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Serialize value into json string
+    commands.add("json-string", |executor| {
+        let pretty = executor.pop_stack().get_bool();
+        let json = executor.pop_stack().to_json();
+        executor.stack.push(Type::String(
+            if pretty {
+                serde_json::to_string_pretty(&json)
+            } else {
+                serde_json::to_string(&json)
+            }
+            .unwrap_or_default()
+            .into(),
+        ));
+        Ok(())
+    });
+
+    // Parse string as yaml
+    commands.add("yaml-parse", |executor| {
+        let text = executor.pop_stack().get_string();
+        match serde_yaml::from_str::<Value>(&text) {
+            Ok(json) => executor.stack.push(Type::Json(json.into())),
+            Err(e) => {
+                executor.raise("yaml-parse", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Serialize value into yaml string
+    commands.add("yaml-string", |executor| {
+        let json = executor.pop_stack().to_json();
+        match serde_yaml::to_string(&json) {
+            Ok(yaml) => executor.stack.push(Type::String(yaml.into())),
+            Err(e) => {
+                executor.raise("yaml-string", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Parse string as toml
+    commands.add("toml-parse", |executor| {
+        let text = executor.pop_stack().get_string();
+        match toml::from_str::<Value>(&text) {
+            Ok(json) => executor.stack.push(Type::Json(json.into())),
+            Err(e) => {
+                let position = e.to_string();
+                let position = position
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim_start_matches("TOML parse error ");
+                let message = match e.message().trim() {
+                    "" => "invalid syntax",
+                    message => message,
+                };
+                executor.raise("toml-parse", format!("{message} {position}"));
+            }
+        }
+        Ok(())
+    });
+
+    // Serialize value into toml string
+    commands.add("toml-string", |executor| {
+        let json = executor.pop_stack().to_json();
+        match toml::to_string(&json) {
+            Ok(text) => executor.stack.push(Type::String(text.into())),
+            Err(e) => {
+                executor.raise("toml-string", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Parse csv into list of table rows
+    commands.add("csv-parse", |executor| {
+        let text = executor.pop_stack().get_string();
+        match csv_parse(&text) {
+            Ok(table) => executor.stack.push(table),
+            Err(e) => {
+                executor.raise("csv-parse", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Generate csv from list of table rows
+    commands.add("csv-string", |executor| {
+        let table = executor.pop_stack().into_list();
+        match csv_string(&table) {
+            Ok(text) => executor.stack.push(Type::String(text.into())),
+            Err(e) => {
+                executor.raise("csv-string", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Parse xml into nested objects
+    commands.add("xml-parse", |executor| {
+        let text = executor.pop_stack().get_string();
+        match xml_parse(&text) {
+            Ok(element) => executor.stack.push(element),
+            Err(e) => {
+                executor.raise("xml-parse", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Generate xml from nested objects
+    commands.add("xml-string", |executor| {
+        let element = executor.pop_stack();
+        executor
+            .stack
+            .push(Type::String(xml_string(&element).into()));
+        Ok(())
+    });
+
+    // Get length of json array or object
+    commands.add("json-len", |executor| {
+        let json = executor.pop_stack().get_json();
+        executor.stack.push(Type::Number(match json {
+            Value::Array(array) => array.len() as f64,
+            Value::Object(object) => object.len() as f64,
+            Value::String(s) => s.chars().count() as f64,
+            _ => 0f64,
+        }));
+        Ok(())
+    });
+
+    // Query value from json by path expression
+    commands.add("json-path", |executor| {
+        let path = executor.pop_stack().get_string();
+        let json = executor.pop_stack().get_json();
+        match json_path(&json, &path) {
+            Some(value) => executor.stack.push(Type::Json(value.into())),
+            None => {
+                executor.raise("json-path", format!("the json path \"{path}\" is invalid"));
+            }
+        }
+        Ok(())
+    });
+
+    // Set value of json
+    commands.add("set-json", |executor| {
+        let value = executor.pop_stack().get_json();
+        let key = executor.pop_stack().get_string();
+        let mut json = executor.pop_stack().get_json();
+        json[key] = value;
+        executor.stack.push(Type::Json(json.into()));
+        Ok(())
+    });
+
+    // Convert value into json
+    commands.add("to-json", |executor| {
+        let data = executor.pop_stack();
+        executor.stack.push(Type::Json(data.to_json().into()));
+        Ok(())
+    });
+
+    // Convert json into native value
+    commands.add("from-json", |executor| {
+        let json = executor.pop_stack().get_json();
+        executor.stack.push(Type::from_json(&json));
+        Ok(())
+    });
+
+    // Serialize value into MessagePack binary
+    commands.add("pack", |executor| {
+        let data = executor.pop_stack();
+        match rmp_serde::to_vec(&data) {
+            Ok(binary) => executor.stack.push(Type::Binary(binary.into())),
+            Err(e) => {
+                executor.raise("pack", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Deserialize value from MessagePack binary
+    commands.add("unpack", |executor| {
+        let binary = match executor.pop_stack() {
+            Type::Binary(binary) => binary,
+            other => other.get_string().into_bytes().into(),
+        };
+        match rmp_serde::from_slice::<Type>(&binary) {
+            Ok(data) => executor.stack.push(data),
+            Err(e) => {
+                executor.raise("unpack", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Control SQL
+    commands.add("sql", |executor| {
+        let path = executor.pop_stack().get_string();
+        let query = executor.pop_stack().get_string();
+        executor.stack.push(database::sql(&path, &query));
+        Ok(())
+    });
+
+    // Open persistent database connection
+    commands.add("db-open", |executor| {
+        let path = executor.pop_stack().get_string();
+        executor.stack.push(database::open(&path));
+        Ok(())
+    });
+
+    // Query rows by the database connection
+    commands.add("db-query", |executor| {
+        let (query, params) = executor.pop_query();
+        let connection = executor.pop_stack();
+        executor
+            .stack
+            .push(database::query(&connection, &query, &params));
+        Ok(())
+    });
+
+    // Query rows by the database connection as JSON
+    commands.add("db-query-json", |executor| {
+        let (query, params) = executor.pop_query();
+        let connection = executor.pop_stack();
+        executor
+            .stack
+            .push(database::query_json(&connection, &query, &params));
+        Ok(())
+    });
+
+    // Execute statement by the database connection
+    commands.add("db-execute", |executor| {
+        let (query, params) = executor.pop_query();
+        let connection = executor.pop_stack();
+        executor
+            .stack
+            .push(database::execute(&connection, &query, &params));
+        Ok(())
+    });
+
+    // Get statistics of the prepared statement cache
+    commands.add("db-stats", |executor| {
+        executor.stack.push(database::stats());
+        Ok(())
+    });
+
+    // Control transaction of the database connection
+    for name in ["db-begin", "db-commit", "db-rollback"] {
+        let command = name.to_string();
+        commands.add(name, move |executor| {
+            let connection = executor.pop_stack();
+            let statement = match command.as_str() {
+                "db-begin" => "BEGIN",
+                "db-commit" => "COMMIT",
+                _ => "ROLLBACK",
+            };
+            if let Some(err) = database::transaction(&connection, statement) {
+                executor.error_print(format!("Error! failed to {statement}\n"));
+                executor.stack.push(err);
+            }
+            Ok(())
+        });
+    }
+
+    // Run code in transaction, and roll back if it results error
+    commands.add("db-transaction", |executor| {
+        let code = executor.pop_stack().get_string();
+        let connection = executor.pop_stack();
+        if let Some(err) = database::transaction(&connection, "BEGIN") {
+            executor.stack.push(err);
+            return Ok(());
+        }
+
+        let old_len = executor.stack.len();
+        executor.evaluate_program(code);
+        let failed = executor.stack[old_len.min(executor.stack.len())..]
+            .iter()
+            .any(|x| matches!(x, Type::Error(_)));
+
+        let statement = if failed { "ROLLBACK" } else { "COMMIT" };
+        if let Some(err) = database::transaction(&connection, statement) {
+            executor.stack.push(err);
+        }
+        Ok(())
+    });
+
+    // Get connection handle of the server's pool
+    commands.add("db-pool", |executor| {
+        executor.stack.push(database::pool_handle());
+        Ok(())
+    });
+
+    // Close the database connection
+    commands.add("db-close", |executor| {
+        let connection = executor.pop_stack();
+        executor
+            .stack
+            .push(Type::Bool(database::close(&connection)));
+        Ok(())
+    });
+
+    // Open the key-value store file
+    commands.add("kv-store", |executor| {
+        let path = executor.pop_stack().get_string();
+        if let Err(e) = kv::open(&path) {
+            executor.raise("kv-store", e);
+        }
+        Ok(())
+    });
+
+    // Set value in the key-value store
+    commands.add("kv-set", |executor| {
+        let value = executor.pop_stack();
+        let key = executor.pop_stack().get_string();
+        if let Err(e) = kv::set(&key, &value) {
+            executor.raise("kv-store", e);
+        }
+        Ok(())
+    });
+
+    // Get value from the key-value store
+    commands.add("kv-get", |executor| {
+        let key = executor.pop_stack().get_string();
+        match kv::get(&key) {
+            Ok(Some(value)) => executor.stack.push(value),
+            Ok(None) => executor.stack.push(Type::error("kv-not-found")),
+            Err(e) => {
+                executor.raise("kv-store", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Delete value from the key-value store
+    commands.add("kv-del", |executor| {
+        let key = executor.pop_stack().get_string();
+        match kv::delete(&key) {
+            Ok(existed) => executor.stack.push(Type::Bool(existed)),
+            Err(e) => {
+                executor.raise("kv-store", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Get all of keys in the key-value store
+    commands.add("kv-keys", |executor| {
+        match kv::keys() {
+            Ok(keys) => executor.stack.push(Type::List(
+                keys.into_iter().map(|x| Type::String(x.into())).collect(),
+            )),
+            Err(e) => {
+                executor.raise("kv-store", e);
+            }
+        };
+        Ok(())
+    });
+
+    // Templates processing by jinja2
+    commands.add("template", |executor| {
+        // Get render value from object
+        let render_object = if let Type::Object(_, obj) = executor.pop_stack() {
+            obj
+        } else {
+            executor.stack.push(Type::error("not-object"));
+            return Ok(());
+        };
+        let template_string = executor.pop_stack().get_string();
+        let context = templates::context(render_object);
+
+        // rendering string
+        let locale = i18n::locale(executor);
+        match i18n::scoped(locale, || templates::render_str(&template_string, &context)) {
+            Ok(rendered) => executor.stack.push(Type::String(rendered.into())),
+            Err(e) => {
+                executor.raise("template", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Register template by the name to extend or include it
+    commands.add("template-add", |executor| {
+        let source = executor.pop_stack().get_string();
+        let name = executor.pop_stack().get_string();
+        if let Err(e) = templates::add(&name, &source) {
+            executor.raise("template", e);
+        }
+        Ok(())
+    });
+
+    // Register template filter implemented by the code
+    commands.add("template-filter", |executor| {
+        let code = executor.pop_stack().get_string();
+        let name = executor.pop_stack().get_string();
+        templates::add_filter(&name, code, executor.clone());
+        Ok(())
+    });
+
+    // Load templates in the directory
+    commands.add("template-dir", |executor| {
+        let dir = executor.pop_stack().get_string();
+        if let Err(e) = templates::load_dir(&dir, executor.mode >= Mode::Debug) {
+            executor.raise("template", e);
+        }
+        Ok(())
+    });
+
+    // Render template loaded from the directory by the name
+    commands.add("template-file", |executor| {
+        let render_object = if let Type::Object(_, obj) = executor.pop_stack() {
+            obj
+        } else {
+            executor.stack.push(Type::error("not-object"));
+            return Ok(());
+        };
+        let name = executor.pop_stack().get_string();
+        let context = templates::context(render_object);
+        let locale = i18n::locale(executor);
+        match i18n::scoped(locale, || templates::render_file(&name, &context)) {
+            Ok(rendered) => executor.stack.push(Type::String(rendered.into())),
+            Err(e) => {
+                executor.raise("template", e);
+            }
+        }
+        Ok(())
+    });
+
+    // start web server
+    commands.add("start-server", |executor| {
+        let code: Type = executor.pop_stack();
+        let option: Type = executor.pop_stack();
+        executor.server(option, code);
+        Ok(())
+    });
+
+    // Get the server settings given at the launch
+    commands.add("server-config", |executor| {
+        executor.stack.push(config::server_config());
+        Ok(())
+    });
+
+    // Get the loaded configuration file
+    commands.add("config", |executor| {
+        executor.stack.push(config::file());
+        Ok(())
+    });
+
+    // Send mail through SMTP server
+    commands.add("send-mail", |executor| {
+        let option = executor.pop_stack();
+        match mail::send(&option) {
+            Ok(()) => executor.stack.push(Type::Bool(true)),
+            Err(e) => {
+                executor.raise("send-mail", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Connect to WebSocket server
+    commands.add("ws-connect", |executor| {
+        let option = executor.pop_stack();
+        match ws::connect(&option) {
+            Ok(socket) => executor.stack.push(socket),
+            Err(e) => {
+                executor.raise("websocket", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Send message by the WebSocket
+    commands.add("ws-send", |executor| {
+        let data = executor.pop_stack();
+        let socket = executor.pop_stack();
+        if let Err(e) = ws::send(&socket, data) {
+            executor.raise("websocket", e);
+        }
+        Ok(())
+    });
+
+    // Receive message by the WebSocket
+    commands.add("ws-receive", |executor| {
+        let socket = executor.pop_stack();
+        match ws::receive(&socket) {
+            Ok(message) => executor.stack.push(message.unwrap_or(Type::Null)),
+            Err(e) => {
+                executor.raise("websocket", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Close the WebSocket
+    commands.add("ws-close", |executor| {
+        let socket = executor.pop_stack();
+        executor.stack.push(Type::Bool(ws::close(&socket)));
+        Ok(())
+    });
+
+    // Connect to MQTT broker
+    commands.add("mqtt-connect", |executor| {
+        let option = executor.pop_stack();
+        match mqtt::connect(&option) {
+            Ok(client) => executor.stack.push(client),
+            Err(e) => {
+                executor.raise("mqtt", e);
+            }
+        }
+        Ok(())
+    });
+
+    // Publish message to the MQTT topic
+    commands.add("mqtt-publish", |executor| {
+        let payload = executor.pop_stack();
+        let topic = executor.pop_stack().get_string();
+        let client = executor.pop_stack();
+        if let Err(e) = mqtt::publish(&client, &topic, payload) {
+            executor.raise("mqtt", e);
+        }
+        Ok(())
+    });
+
+    // Subscribe to the MQTT topic, running code with each message
+    commands.add("mqtt-subscribe", |executor| {
+        let code = executor.pop_stack().get_string();
+        let filter = executor.pop_stack().get_string();
+        let client = executor.pop_stack();
+        if let Err(e) = mqtt::subscribe(&client, &filter, code, executor.clone()) {
+            executor.raise("mqtt", e);
+        }
+        Ok(())
+    });
+
+    // Disconnect from MQTT broker
+    commands.add("mqtt-disconnect", |executor| {
+        let client = executor.pop_stack();
+        executor.stack.push(Type::Bool(mqtt::disconnect(&client)));
+        Ok(())
+    });
+
+    // If it is not recognized as a command, use it as a string.
+}
+
+impl Site {
+    /// Get the handler of the route matching exactly, or the static route or the upload route
+    /// of the longest path the request is under
+    fn route(&self, method: &str, path: &str) -> Option<&Handler> {
+        self.routes.get(&format!("{method} {path}")).or_else(|| {
+            self.routes
+                .iter()
+                .filter_map(|(route, handler)| {
+                    let same_method = route.split_once(' ').is_some_and(|(x, _)| x == method);
+                    let mount = match (&handler.statics, &handler.upload) {
+                        (Some(statics), _) if same_method => &statics.mount,
+                        (_, Some(upload)) => &upload.mount,
+                        // GraphQL is queried by GET as well as by POST
+                        _ if handler.graphql.is_some() && matches!(method, "GET" | "POST") => {
+                            let mount = route.split_once(' ')?.1;
+                            return (mount == path).then_some((mount.len(), handler));
+                        }
+                        _ => return None,
+                    };
+                    statics::mounted(mount, path).then_some((mount.len(), handler))
+                })
+                .max_by_key(|(length, _)| *length)
+                .map(|(_, handler)| handler)
+        })
+    }
+}
+
+/// Handler of the route and its options
+struct Handler {
+    code: String,                           // Code evaluated for the request
+    representations: Vec<(String, String)>, // Media types and their code chosen by Accept
+    auth: Option<Auth>,                     // How to authenticate the client
+    users: String,                          // Code pushing the users or the API keys
+    max_body_size: Option<usize>,           // Limit of the request body overriding the server's
+    timeout: Option<f64>,                   // Seconds to stop the handler and respond 504
+    api_key_header: Option<String>,         // Header of the API key instead of the default ones
+    statics: Option<statics::Static>,       // Directory served under the path of the route
+    upload: Option<upload::Upload>,         // Resumable uploads under the path of the route
+    graphql: Option<graphql::Endpoint>,     // GraphQL schema and resolvers of the route
+    json_rpc: Option<jsonrpc::Methods>,     // JSON-RPC methods of the route
+    schema: Option<schema::Schema>,         // JSON Schema of the request body
+}
+
+/// Authentication of the route
+enum Auth {
+    Basic,  // User and password by the Basic Authorization header
+    ApiKey, // Key by the Bearer Authorization header or the X-API-Key header
+}
+
+/// Get the options of the route from the object or the list of key-value pairs
+fn route_options(value: &Type) -> HashMap<String, Type> {
+    match value {
+        Type::Object(_, object) => object.clone(),
+        _ => value
+            .get_list()
+            .into_iter()
+            .filter_map(|pair| {
+                let pair = pair.get_list();
+                Some((pair.first()?.get_string(), pair.get(1)?.clone()))
+            })
+            .collect(),
+    }
+}
+
+/// Settings of the server to handle the requests
+struct Site {
+    routes: HashMap<String, Handler>, // Handlers by "METHOD /path"
+    probes: Probes,                   // Health and readiness endpoints
+    buffer_size: usize,               // Size of each read of the request
+    max_body_size: usize,             // Limit of the request body
+    access_log: logger::AccessFormat, // Format of the access log lines
+    stats_path: Option<String>,       // Path of the statistics (disabled without it)
+    slow_request: f64,                // Seconds to warn the request (0 is disabled)
+    error_webhook: Option<String>,    // URL to report the errors of the handlers
+}
+
+/// Stream of the response dumping the written data in trace mode
+struct Wire {
+    stream: TcpStream,     // Connection to the client
+    trace: bool,           // Whether to dump the data
+    started: bool,         // Whether the dump header is shown
+    head: Option<Vec<u8>>, // Header written so far (None after the header ends)
+    status: u16,           // Status code in the header
+    sent: u64,             // Size of the body written
+}
+
+impl Wire {
+    /// Count the written data as the header until the empty line, and the rest as the body
+    fn record(&mut self, data: &[u8]) {
+        let Some(head) = self.head.as_mut() else {
+            self.sent += data.len() as u64;
+            return;
+        };
+        head.extend_from_slice(data);
+        if let Some(end) = head.windows(4).position(|x| x == b"\r\n\r\n") {
+            self.status = String::from_utf8_lossy(head)
+                .split_whitespace()
+                .nth(1)
+                .and_then(|x| x.parse().ok())
+                .unwrap_or(0);
+            self.sent = (head.len() - end - 4) as u64;
+            self.head = None;
+        }
+    }
+}
+
+impl Write for Wire {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let size = self.stream.write(buffer)?;
+        self.record(&buffer[..size]);
+        if self.trace {
+            if !self.started {
+                println!("[Response]");
+                self.started = true;
+            }
+            print!("{}", String::from_utf8_lossy(&buffer[..size]));
+        }
+        Ok(size)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.trace && self.started {
+            println!();
+        }
+        self.stream.flush()
+    }
+}
+
+/// To processing
+fn parse_request_line(request_line: &str, key: &str) -> (String, String) {
+    let parts: Vec<&str> = request_line.trim().split(key).collect();
+    let method = parts.first().unwrap_or(&"").to_string();
+    let path = parts.get(1).unwrap_or(&"").to_string();
+
+    (method, path)
+}
+
+/// Time zone to handle the time
+enum Zone {
+    Local,                // Local time zone of the system
+    Named(chrono_tz::Tz), // Time zone of IANA database
+}
+
+impl Zone {
+    /// Get time zone by the name
+    fn parse(name: &str) -> Option<Zone> {
+        if name.is_empty() || name == "local" {
+            Some(Zone::Local)
+        } else {
+            name.parse().ok().map(Zone::Named)
+        }
+    }
+
+    /// Convert local date time in the zone into unix epoch time
+    fn to_epoch(&self, time: chrono::NaiveDateTime) -> Option<f64> {
+        use chrono::{LocalResult, Offset, TimeDelta, TimeZone};
+
+        fn resolve<Z: TimeZone>(zone: &Z, time: chrono::NaiveDateTime) -> Option<i64> {
+            match zone.from_local_datetime(&time) {
+                LocalResult::Single(x) | LocalResult::Ambiguous(x, _) => Some(x.timestamp_millis()),
+                // Time skipped by the transition (like the start of DST) is shifted forward
+                // by the gap, reading it in the offset before the transition
+                LocalResult::None => {
+                    let before = zone
+                        .from_local_datetime(&(time - TimeDelta::days(1)))
+                        .earliest()?;
+                    let offset = before.offset().fix().local_minus_utc();
+                    Some(
+                        (time - TimeDelta::seconds(offset.into()))
+                            .and_utc()
+                            .timestamp_millis(),
+                    )
+                }
+            }
+        }
+
+        let millis = match self {
+            Zone::Local => resolve(&chrono::Local, time),
+            Zone::Named(tz) => resolve(tz, time),
+        }?;
+        Some(millis as f64 / 1000.0)
+    }
+
+    /// Get offset seconds from UTC at the unix epoch time
+    fn offset(&self, epoch: f64) -> Option<i32> {
+        use chrono::{DateTime, Local, Offset};
+
+        let time = DateTime::from_timestamp_millis((epoch * 1000.0) as i64)?;
+        Some(match self {
+            Zone::Local => time.with_timezone(&Local).offset().fix().local_minus_utc(),
+            Zone::Named(tz) => time.with_timezone(tz).offset().fix().local_minus_utc(),
+        })
+    }
+}
+
+/// Format unix epoch time in the zone by strftime pattern
+fn time_format(epoch: f64, pattern: &str, zone: &Zone) -> Option<String> {
+    use chrono::format::{Item, StrftimeItems};
+    use chrono::{DateTime, Local};
+
+    if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+        return None;
+    }
+    let time = DateTime::from_timestamp_millis((epoch * 1000.0) as i64)?;
+    Some(match zone {
+        Zone::Local => time.with_timezone(&Local).format(pattern).to_string(),
+        Zone::Named(tz) => time.with_timezone(tz).format(pattern).to_string(),
+    })
+}
+
+/// Parse string in the zone (unless it has offset) into unix epoch time
+fn time_parse(text: &str, pattern: &str, zone: &Zone) -> Result<f64, String> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+    if let Ok(time) = DateTime::parse_from_str(text, pattern) {
+        return Ok(time.timestamp_millis() as f64 / 1000.0);
+    }
+    let time = match NaiveDateTime::parse_from_str(text, pattern) {
+        Ok(time) => time,
+        Err(e) => match NaiveDate::parse_from_str(text, pattern) {
+            Ok(date) => date.and_hms_opt(0, 0, 0).unwrap_or_default(),
+            Err(_) => return Err(e.to_string()),
+        },
+    };
+    zone.to_epoch(time)
+        .ok_or_else(|| format!("\"{text}\" doesn't exist in the time zone"))
+}
+
+/// Get the value of the header in the request (the name is case-insensitive)
+fn header_value<'a>(request_str: &'a str, name: &str) -> Option<&'a str> {
+    request_str
+        .lines()
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+/// Get the user and the password in the Basic Authorization header
+fn basic_credentials(request_str: &str) -> Option<(String, String)> {
+    let (scheme, encoded_credentials) =
+        header_value(request_str, "Authorization")?.split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("Basic") {
+        return None;
+    }
+    let decoded_credentials = STANDARD
+        .decode(encoded_credentials.trim())
+        .unwrap_or_default();
+    let credentials = String::from_utf8_lossy(&decoded_credentials);
+    let (username, password) = credentials.split_once(':')?;
+    Some((username.to_string(), password.to_string()))
+}
+
+// Basic user authenticate
+fn authenticate(request_str: &str, database: HashMap<String, String>) -> (bool, (String, String)) {
+    // authenticate username and password
+    if let Some((username, password)) = basic_credentials(request_str) {
+        if let Some(expected_password) = database.get(&username) {
+            return (
+                password::check(&password, expected_password),
+                (username, password),
+            );
+        }
+    }
+    (false, ("".to_string(), "".to_string()))
+}
+
+// Query json value by path expression like `$.items[2].name`
+fn json_path(json: &Value, path: &str) -> Option<Value> {
+    let chars: Vec<char> = path.trim().chars().collect();
+    let mut index = 0;
+    if chars.first() == Some(&'$') {
+        index += 1;
+    }
+
+    let mut current = vec![json.clone()];
+    let mut wildcard = false;
+    while index < chars.len() {
+        // Parse a segment of the path
+        let segment = match chars[index] {
+            '.' => {
+                index += 1;
+                let start = index;
+                while index < chars.len() && chars[index] != '.' && chars[index] != '[' {
+                    index += 1;
+                }
+                chars[start..index].iter().collect::<String>()
+            }
+            '[' => {
+                let end = index + chars[index..].iter().position(|c| *c == ']')?;
+                let inner: String = chars[index + 1..end].iter().collect();
+                index = end + 1;
+                inner.trim_matches(|c| c == '\'' || c == '"').to_string()
+            }
+            _ => return None,
+        };
+
+        // Step into the children
+        current = if segment == "*" {
+            wildcard = true;
+            current
+                .iter()
+                .flat_map(|value| match value {
+                    Value::Array(array) => array.clone(),
+                    Value::Object(object) => object.values().cloned().collect(),
+                    _ => vec![],
+                })
+                .collect()
+        } else {
+            current
+                .iter()
+                .filter_map(|value| match (value, segment.parse::<usize>()) {
+                    (Value::Array(array), Ok(i)) => array.get(i).cloned(),
+                    (value, _) => value.get(&segment).cloned(),
+                })
+                .collect()
+        };
+    }
+
+    if wildcard {
+        Some(Value::Array(current))
+    } else {
+        current.into_iter().next()
+    }
+}
+
+// Parse csv text using the header row as property names
+fn csv_parse(text: &str) -> Result<Type, csv::Error> {
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let headers = reader.headers()?.clone();
+
+    let mut result = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let object = headers
+            .iter()
+            .zip(record.iter())
+            .map(|(key, value)| (key.to_string(), Type::String(value.to_string().into())))
+            .collect();
+        result.push(Type::Object("table".to_string(), object));
+    }
+    Ok(Type::List(result.into()))
+}
+
+// Generate csv text from objects (or lists as raw records)
+fn csv_string(table: &[Type]) -> Result<String, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+
+    // Collect the header from all of the objects
+    let mut headers: Vec<String> = Vec::new();
+    for row in table {
+        if let Type::Object(_, object) = row {
+            for key in object.keys() {
+                if !headers.contains(key) {
+                    headers.push(key.to_owned());
+                }
+            }
+        }
+    }
+    headers.sort();
+    if !headers.is_empty() {
+        writer.write_record(&headers)?;
+    }
+
+    for row in table {
+        match row {
+            Type::Object(_, object) => writer.write_record(headers.iter().map(|key| {
+                object
+                    .get(key)
+                    .map(|value| value.get_string())
+                    .unwrap_or_default()
+            }))?,
+            row => writer.write_record(row.get_list().iter().map(|value| value.get_string()))?,
+        }
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+// Make object of xml element
+fn xml_element(tag: String, attrs: HashMap<String, Type>, children: Vec<Type>) -> Type {
+    Type::Object(
+        "xml".to_string(),
+        HashMap::from([
+            ("tag".to_string(), Type::String(tag.into())),
+            (
+                "attrs".to_string(),
+                Type::Object("attrs".to_string(), attrs),
+            ),
+            ("children".to_string(), Type::List(children.into())),
+        ]),
+    )
+}
+
+// Parse xml text into element objects
+fn xml_parse(text: &str) -> Result<Type, Box<dyn std::error::Error>> {
+    use quick_xml::events::{BytesStart, Event};
+    use quick_xml::Reader;
+
+    fn parse_start(
+        start: &BytesStart,
+    ) -> Result<(String, HashMap<String, Type>), Box<dyn std::error::Error>> {
+        let tag = String::from_utf8_lossy(start.name().as_ref()).to_string();
+        let mut attrs = HashMap::new();
+        for attr in start.attributes() {
+            let attr = attr?;
+            attrs.insert(
+                String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+                Type::String(attr.unescape_value()?.to_string().into()),
+            );
+        }
+        Ok((tag, attrs))
+    }
+
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+
+    // Elements which are not closed yet
+    let mut open: Vec<(String, HashMap<String, Type>, Vec<Type>)> = Vec::new();
+    let mut root: Option<Type> = None;
+    loop {
+        let element = match reader.read_event()? {
+            Event::Start(start) => {
+                let (tag, attrs) = parse_start(&start)?;
+                open.push((tag, attrs, Vec::new()));
+                continue;
+            }
+            Event::Empty(start) => {
+                let (tag, attrs) = parse_start(&start)?;
+                xml_element(tag, attrs, Vec::new())
+            }
+            Event::End(_) => match open.pop() {
+                Some((tag, attrs, children)) => xml_element(tag, attrs, children),
+                None => return Err("unexpected closing tag".into()),
+            },
+            Event::Text(text) => Type::String(text.unescape()?.to_string().into()),
+            Event::CData(data) => Type::String(String::from_utf8_lossy(&data).to_string().into()),
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        match open.last_mut() {
+            Some((_, _, children)) => children.push(element),
+            None if root.is_none() && matches!(element, Type::Object(..)) => root = Some(element),
+            None => {}
+        }
+    }
+
+    if !open.is_empty() {
+        return Err("unclosed element".into());
+    }
+    root.ok_or_else(|| "root element is not found".into())
+}
+
+// Generate xml text from element objects
+fn xml_string(element: &Type) -> String {
+    use quick_xml::escape::escape;
+
+    let Type::Object(_, object) = element else {
+        return escape(element.get_string().as_str()).to_string();
+    };
+    let tag = object
+        .get("tag")
+        .map(|x| x.get_string())
+        .unwrap_or_default();
+
+    let mut attrs: Vec<(String, Type)> = object
+        .get("attrs")
+        .map(|x| x.get_object().1.into_iter().collect())
+        .unwrap_or_default();
+    attrs.sort_by(|a, b| a.0.cmp(&b.0));
+    let attrs: String = attrs
+        .iter()
+        .map(|(key, value)| format!(" {key}=\"{}\"", escape(value.get_string().as_str())))
+        .collect();
+
+    let children = object
+        .get("children")
+        .map(|x| x.get_list())
+        .unwrap_or_default();
+    if children.is_empty() {
+        format!("<{tag}{attrs}/>")
+    } else {
+        let inner: String = children.iter().map(xml_string).collect();
+        format!("<{tag}{attrs}>{inner}</{tag}>")
+    }
+}
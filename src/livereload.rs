@@ -0,0 +1,52 @@
+use std::env;
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::LazyLock;
+use std::thread;
+use std::time::Duration;
+
+/// Environment variable set for the program restarted by the watch mode
+pub const ENV: &str = "STACK_LIVE_RELOAD";
+
+/// Path of the event stream the pages connect to
+pub const PATH: &str = "/__live-reload";
+
+/// Interval of the comments keeping the event stream open
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Script reloading the page when the event stream is connected again,
+/// that is, when the program is restarted by the change
+const SCRIPT: &str = r#"<script>(() => {
+  let lost = false;
+  const events = new EventSource("/__live-reload");
+  events.onerror = () => { lost = true; };
+  events.onopen = () => { if (lost) location.reload(); };
+})();</script>"#;
+
+static ENABLED: LazyLock<bool> = LazyLock::new(|| env::var_os(ENV).is_some());
+
+/// Whether the pages are reloaded by the changes
+pub fn is_enabled() -> bool {
+    *ENABLED
+}
+
+/// Insert the script before the end of the body of the HTML
+pub fn inject(html: &str) -> String {
+    match html.to_ascii_lowercase().rfind("</body>") {
+        Some(index) => format!("{}{SCRIPT}{}", &html[..index], &html[index..]),
+        None => format!("{html}{SCRIPT}"),
+    }
+}
+
+/// Keep the event stream of the page open in the background until the program stops
+pub fn connect(mut stream: TcpStream) {
+    thread::spawn(move || {
+        let header = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\nretry: 500\n\n";
+        if stream.write_all(header.as_bytes()).is_err() {
+            return;
+        }
+        while stream.write_all(b": keep-alive\n\n").is_ok() {
+            thread::sleep(KEEP_ALIVE);
+        }
+    });
+}
@@ -0,0 +1,184 @@
+use crate::commands::Commands;
+use crate::Type;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Longest lockout however many times the login fails
+const MAX_LOCKOUT: Duration = Duration::from_secs(60 * 60);
+
+/// How long the failures are remembered after the last one
+const FORGET: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Number of the keys remembered at most (the oldest ones are forgotten first)
+const MAX_RECORDS: usize = 10000;
+
+/// How many locked records are kept instead of being forgotten when the keys are too many
+const KEEP_LOCKED: usize = 8;
+
+/// When to lock out the key and for how long
+#[derive(Clone, Copy)]
+struct Policy {
+    attempts: u32,     // Number of the failures allowed before the lockout
+    lockout: Duration, // Lockout after the allowed failures (doubled by each further failure)
+}
+
+/// Failed logins of the key like "user:alice" or "ip:127.0.0.1"
+struct Record {
+    failures: u32,                 // Number of the consecutive failures
+    last: Instant,                 // Time of the last failure
+    locked_until: Option<Instant>, // End of the lockout
+}
+
+/// Lockout policy and the failures by the key
+struct Lockout {
+    policy: Policy,
+    records: HashMap<String, Record>,
+    order: VecDeque<String>, // Keys of the records in the order of the first failure
+}
+
+static LOCKOUT: LazyLock<Mutex<Lockout>> = LazyLock::new(|| {
+    Mutex::new(Lockout {
+        policy: Policy {
+            attempts: 5,
+            lockout: Duration::from_secs(30),
+        },
+        records: HashMap::new(),
+        order: VecDeque::new(),
+    })
+});
+
+/// Lock the lockout state
+fn lockout() -> MutexGuard<'static, Lockout> {
+    LOCKOUT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Set the number of the allowed failures and the first lockout in seconds
+pub fn configure(attempts: Option<f64>, lockout_time: Option<f64>) {
+    let mut lockout = lockout();
+    if let Some(attempts) = attempts {
+        lockout.policy.attempts = attempts.max(1.0) as u32;
+    }
+    if let Some(seconds) = lockout_time {
+        lockout.policy.lockout = Duration::from_secs_f64(seconds.max(0.0));
+    }
+}
+
+/// Get the longest remaining lockout of the keys (None unless any of them is locked out)
+pub fn locked(keys: &[String]) -> Option<Duration> {
+    let lockout = lockout();
+    let now = Instant::now();
+    keys.iter()
+        .filter_map(|key| lockout.records.get(key)?.locked_until)
+        .filter(|until| *until > now)
+        .map(|until| until - now)
+        .max()
+}
+
+impl Lockout {
+    /// Forget the oldest records, which are expired or over the limit, without scanning all
+    fn prune(&mut self, now: Instant) {
+        while let Some(key) = self.order.front() {
+            match self.records.get(key) {
+                Some(record) if now - record.last >= FORGET => {
+                    self.records.remove(key);
+                    self.order.pop_front();
+                }
+                _ => break,
+            }
+        }
+
+        // Keep a few of the locked ones, so that spraying the names can't unlock them
+        let mut kept = 0;
+        while self.records.len() > MAX_RECORDS {
+            let Some(key) = self.order.pop_front() else {
+                break;
+            };
+            let locked = self.records.get(&key).and_then(|x| x.locked_until);
+            if kept < KEEP_LOCKED && locked.is_some_and(|until| until > now) {
+                kept += 1;
+                self.order.push_back(key);
+                continue;
+            }
+            self.records.remove(&key);
+        }
+    }
+}
+
+/// Count the failure of the keys, locking them out after the allowed failures
+pub fn fail(keys: &[String]) {
+    let mut lockout = lockout();
+    let policy = lockout.policy;
+    let now = Instant::now();
+    for key in keys {
+        if !lockout.records.contains_key(key) {
+            lockout.order.push_back(key.clone());
+        }
+        let record = lockout.records.entry(key.clone()).or_insert(Record {
+            failures: 0,
+            last: now,
+            locked_until: None,
+        });
+        record.failures += 1;
+        record.last = now;
+        if record.failures >= policy.attempts {
+            let doubling = (record.failures - policy.attempts).min(16);
+            let duration = (policy.lockout * 2u32.pow(doubling)).min(MAX_LOCKOUT);
+            record.locked_until = Some(now + duration);
+        }
+    }
+    lockout.prune(now);
+}
+
+/// Forget the failures of the keys after the successful login
+/// (the record is left to be pruned in order)
+pub fn succeed(keys: &[String]) {
+    let mut lockout = lockout();
+    for key in keys {
+        if let Some(record) = lockout.records.get_mut(key) {
+            record.failures = 0;
+            record.locked_until = None;
+        }
+    }
+}
+
+/// Get the failures and the lockout of the key as an object
+fn status(key: &str) -> Type {
+    let failures = lockout().records.get(key).map_or(0, |x| x.failures);
+    let retry_after = locked(&[key.to_string()]);
+    Type::Object(
+        "login".to_string(),
+        HashMap::from([
+            ("failures".to_string(), Type::Number(failures as f64)),
+            ("locked".to_string(), Type::Bool(retry_after.is_some())),
+            (
+                "retry-after".to_string(),
+                Type::Number(retry_after.map_or(0.0, |x| x.as_secs_f64().ceil())),
+            ),
+        ]),
+    )
+}
+
+/// Register the commands of the login lockout
+pub fn register_commands(commands: &mut Commands) {
+    // Count the failed login of the key like the user name or "ip:address"
+    commands.add("login-failed", |executor| {
+        let key = executor.pop_stack().get_string();
+        fail(&[key]);
+        Ok(())
+    });
+
+    // Forget the failed logins of the key
+    commands.add("login-succeeded", |executor| {
+        let key = executor.pop_stack().get_string();
+        succeed(&[key]);
+        Ok(())
+    });
+
+    // Get the failures and the lockout of the key
+    commands.add("login-status", |executor| {
+        let key = executor.pop_stack().get_string();
+        executor.stack.push(status(&key));
+        Ok(())
+    });
+}
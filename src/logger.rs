@@ -0,0 +1,262 @@
+use crate::Type;
+use chrono::{DateTime, Local};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::json;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Period to rotate the log file
+#[derive(Clone, Copy, PartialEq)]
+enum Period {
+    Never,
+    Hourly,
+    Daily,
+}
+
+/// Log file with rotation settings
+struct FileLog {
+    path: String,   // Path of the current log file
+    max_size: u64,  // Rotate when the file gets over this size (0 is unlimited)
+    period: Period, // Rotate when the period is changed
+    keep: usize,    // Count of rotated files to retain
+    gzip: bool,     // Compress rotated files
+    stamp: String,  // Period stamp of the current file
+}
+
+static LOGGER: Mutex<Option<FileLog>> = Mutex::new(None);
+
+impl Period {
+    /// Get stamp of the period including the time
+    fn stamp(&self, time: DateTime<Local>) -> String {
+        match self {
+            Period::Never => String::new(),
+            Period::Hourly => time.format("%Y%m%d%H").to_string(),
+            Period::Daily => time.format("%Y%m%d").to_string(),
+        }
+    }
+}
+
+impl FileLog {
+    /// Path of the rotated file by the number
+    fn rotated(&self, number: usize) -> String {
+        if self.gzip {
+            format!("{}.{number}.gz", self.path)
+        } else {
+            format!("{}.{number}", self.path)
+        }
+    }
+
+    /// Shift rotated files and move the current file to the first one
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.keep == 0 {
+            fs::remove_file(&self.path).ok();
+            return Ok(());
+        }
+
+        fs::remove_file(self.rotated(self.keep)).ok();
+        for number in (1..self.keep).rev() {
+            fs::rename(self.rotated(number), self.rotated(number + 1)).ok();
+        }
+
+        if self.gzip {
+            let mut encoder =
+                GzEncoder::new(File::create(self.rotated(1))?, Compression::default());
+            encoder.write_all(&fs::read(&self.path)?)?;
+            encoder.finish()?;
+            fs::remove_file(&self.path)?;
+        } else {
+            fs::rename(&self.path, self.rotated(1))?;
+        }
+        Ok(())
+    }
+
+    /// Append the line, rotating the file if it is needed
+    fn write(&mut self, line: &str) -> io::Result<()> {
+        let size = fs::metadata(&self.path).map(|x| x.len()).unwrap_or(0);
+        let stamp = self.period.stamp(Local::now());
+        let over_size = self.max_size > 0 && size > 0 && size + line.len() as u64 > self.max_size;
+        if over_size || (size > 0 && stamp != self.stamp) {
+            self.rotate()?;
+        }
+        self.stamp = stamp;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())
+    }
+}
+
+/// Configure the log file by path or options object
+pub fn configure(option: &Type) -> Result<(), String> {
+    let (path, max_size, period, keep, gzip) = if let Type::Object(_, value) = option {
+        let get = |key: &str| value.get(key).cloned();
+        (
+            get("path").map(|x| x.get_string()).unwrap_or_default(),
+            get("max-size").map(|x| x.get_number() as u64).unwrap_or(0),
+            match get("rotate").map(|x| x.get_string()).as_deref() {
+                Some("hourly") => Period::Hourly,
+                Some("daily") => Period::Daily,
+                None | Some("") | Some("never") => Period::Never,
+                Some(other) => return Err(format!("unknown rotation period \"{other}\"")),
+            },
+            get("keep").map(|x| x.get_number() as usize).unwrap_or(5),
+            get("gzip").map(|x| x.get_bool()).unwrap_or(false),
+        )
+    } else {
+        (option.get_string(), 0, Period::Never, 5, false)
+    };
+
+    let mut logger = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+    if path.is_empty() {
+        *logger = None;
+        return Ok(());
+    }
+
+    // Use modified time of the existing file as the period stamp
+    let stamp = fs::metadata(&path)
+        .and_then(|x| x.modified())
+        .map(|time| period.stamp(time.into()))
+        .unwrap_or_else(|_| period.stamp(Local::now()));
+
+    *logger = Some(FileLog {
+        path,
+        max_size,
+        period,
+        keep,
+        gzip,
+        stamp,
+    });
+    Ok(())
+}
+
+/// Whether the log file is configured
+pub fn is_enabled() -> bool {
+    LOGGER.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// Append the line into the log file (or standard output)
+fn append(line: &str) -> io::Result<()> {
+    let mut logger = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+    match logger.as_mut() {
+        Some(log) => log.write(line),
+        None => io::stdout().write_all(line.as_bytes()),
+    }
+}
+
+/// Write message with timestamp into the log file (or standard output)
+pub fn write(message: &str) -> io::Result<()> {
+    append(&format!(
+        "[{}] {message}\n",
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    ))
+}
+
+/// Format of the access log lines
+#[derive(Clone, Copy, PartialEq)]
+pub enum AccessFormat {
+    Basic,    // Timestamped "peer method path"
+    Common,   // Common Log Format of Apache and nginx
+    Combined, // Common Log Format with the referer and the user agent
+    Json,     // One JSON object per line
+}
+
+impl AccessFormat {
+    /// Get the format by the name
+    pub fn parse(name: &str) -> Option<AccessFormat> {
+        match name {
+            "basic" => Some(AccessFormat::Basic),
+            "common" => Some(AccessFormat::Common),
+            "combined" => Some(AccessFormat::Combined),
+            "json" => Some(AccessFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Request and its response written into the access log
+pub struct Access<'a> {
+    pub time: DateTime<Local>,       // When the request is received
+    pub peer: &'a str,               // Address of the client
+    pub method: &'a str,             // Method of the request
+    pub path: &'a str,               // Path without the query
+    pub query: &'a str,              // Query string without "?"
+    pub status: u16,                 // Status code of the response (0 if nothing is sent)
+    pub bytes: u64,                  // Size of the response body
+    pub referer: Option<&'a str>,    // Referer header
+    pub user_agent: Option<&'a str>, // User-Agent header
+    pub duration: Duration,          // Time to respond
+}
+
+impl Access<'_> {
+    /// Request target including the query
+    fn target(&self) -> String {
+        match self.query.is_empty() {
+            true => self.path.to_string(),
+            false => format!("{}?{}", self.path, self.query),
+        }
+    }
+
+    /// Format the line (including the line break)
+    fn format(&self, format: AccessFormat) -> String {
+        // Host part of the peer like "127.0.0.1" for the log formats of Apache
+        let host = self
+            .peer
+            .rsplit_once(':')
+            .map(|(host, _)| host.trim_start_matches('[').trim_end_matches(']'))
+            .unwrap_or(self.peer);
+        let common = || {
+            format!(
+                "{host} - - [{}] \"{} {} HTTP/1.1\" {} {}",
+                self.time.format("%d/%b/%Y:%H:%M:%S %z"),
+                self.method,
+                self.target(),
+                self.status,
+                match self.bytes {
+                    0 => "-".to_string(),
+                    bytes => bytes.to_string(),
+                }
+            )
+        };
+        match format {
+            AccessFormat::Basic => format!(
+                "[{}] {} {} {}\n",
+                self.time.format("%Y-%m-%d %H:%M:%S"),
+                self.peer,
+                self.method,
+                self.path
+            ),
+            AccessFormat::Common => format!("{}\n", common()),
+            AccessFormat::Combined => format!(
+                "{} {:?} {:?}\n",
+                common(),
+                self.referer.unwrap_or("-"),
+                self.user_agent.unwrap_or("-")
+            ),
+            AccessFormat::Json => format!(
+                "{}\n",
+                json!({
+                    "time": self.time.to_rfc3339(),
+                    "peer": self.peer,
+                    "method": self.method,
+                    "path": self.path,
+                    "query": self.query,
+                    "status": self.status,
+                    "bytes": self.bytes,
+                    "duration_ms": self.duration.as_secs_f64() * 1000.0,
+                    "referer": self.referer,
+                    "user_agent": self.user_agent,
+                })
+            ),
+        }
+    }
+}
+
+/// Write the access in the format into the log file (or standard output)
+pub fn access(format: AccessFormat, access: &Access) -> io::Result<()> {
+    append(&access.format(format))
+}
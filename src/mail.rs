@@ -0,0 +1,108 @@
+use crate::Type;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{Message, SmtpTransport, Transport};
+
+/// Parse the mail address
+fn mailbox(address: &Type) -> Result<Mailbox, String> {
+    let address = address.get_string();
+    address
+        .parse()
+        .map_err(|e| format!("invalid address \"{address}\": {e}"))
+}
+
+/// Make attachment part from object that has name, content-type and data
+fn attachment(file: &Type) -> Result<SinglePart, String> {
+    let (_, file) = file.get_object();
+    let name = file.get("name").map(|x| x.get_string()).unwrap_or_default();
+    let content_type = file
+        .get("content-type")
+        .map(|x| x.get_string())
+        .unwrap_or("application/octet-stream".to_string());
+    let data = match file.get("data") {
+        Some(Type::Binary(data)) => data.to_vec(),
+        Some(other) => other.get_string().into_bytes(),
+        None => Vec::new(),
+    };
+    let content_type = ContentType::parse(&content_type).map_err(|e| e.to_string())?;
+    Ok(Attachment::new(name).body(data, content_type))
+}
+
+/// Send mail by the options object through SMTP server
+pub fn send(option: &Type) -> Result<(), String> {
+    let (_, value) = option.get_object();
+    let get = |key: &str| value.get(key).map(|x| x.get_string()).unwrap_or_default();
+
+    // Build the message
+    let mut builder = Message::builder()
+        .from(mailbox(
+            value.get("from").ok_or("sender is not specified")?,
+        )?)
+        .subject(get("subject"));
+    let recipients = match value.get("to") {
+        Some(Type::List(list)) => list.to_vec(),
+        Some(other) => vec![other.to_owned()],
+        None => return Err("recipient is not specified".to_string()),
+    };
+    for recipient in &recipients {
+        builder = builder.to(mailbox(recipient)?);
+    }
+
+    let html = value.get("html").map(|x| x.get_bool()).unwrap_or(false);
+    let body = SinglePart::builder()
+        .header(if html {
+            ContentType::TEXT_HTML
+        } else {
+            ContentType::TEXT_PLAIN
+        })
+        .body(get("body"));
+    let attachments = value
+        .get("attachments")
+        .map(|x| x.get_list())
+        .unwrap_or_default();
+    let message = if attachments.is_empty() {
+        builder.singlepart(body)
+    } else {
+        let mut parts = MultiPart::mixed().singlepart(body);
+        for file in &attachments {
+            parts = parts.singlepart(attachment(file)?);
+        }
+        builder.multipart(parts)
+    }
+    .map_err(|e| e.to_string())?;
+
+    // Connect to the server by the security mode
+    let host = get("host");
+    let mut transport = match get("security").as_str() {
+        "tls" => SmtpTransport::relay(&host).map_err(|e| e.to_string())?,
+        "" | "starttls" => SmtpTransport::starttls_relay(&host).map_err(|e| e.to_string())?,
+        "none" => SmtpTransport::builder_dangerous(&host),
+        other => return Err(format!("unknown security mode \"{other}\"")),
+    };
+    if let Some(port) = value.get("port") {
+        transport = transport.port(port.get_number() as u16);
+    }
+    if value.get("insecure").map(|x| x.get_bool()).unwrap_or(false) {
+        // Accept self-signed certificate of the development server
+        let parameters = TlsParameters::builder(host.clone())
+            .dangerous_accept_invalid_certs(true)
+            .build()
+            .map_err(|e| e.to_string())?;
+        transport = transport.tls(match get("security").as_str() {
+            "tls" => Tls::Wrapper(parameters),
+            "none" => Tls::None,
+            _ => Tls::Required(parameters),
+        });
+    }
+    if value.contains_key("username") {
+        transport = transport.credentials(Credentials::new(get("username"), get("password")));
+    }
+
+    transport
+        .build()
+        .send(&message)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
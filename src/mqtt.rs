@@ -0,0 +1,214 @@
+use crate::{tls, Executor, Type};
+use rumqttc::{Client, Event, MqttOptions, Packet, QoS, TlsConfiguration, Transport};
+use std::collections::HashMap;
+use std::process;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+/// Code subscribing to the topic filter
+struct Subscription {
+    filter: String,     // Topic filter that can include wildcards
+    code: String,       // Code to run with the message
+    executor: Executor, // Executor that subscribed
+}
+
+/// Client connected to the broker
+struct Session {
+    client: Client,                          // Client to send requests
+    subscriptions: Mutex<Vec<Subscription>>, // Code to deliver messages
+}
+
+/// Connected clients and the last identifier
+type Sessions = (usize, HashMap<usize, Arc<Session>>);
+
+static SESSIONS: LazyLock<Mutex<Sessions>> = LazyLock::new(|| Mutex::new((0, HashMap::new())));
+
+/// Lock the client registry
+fn sessions() -> MutexGuard<'static, Sessions> {
+    SESSIONS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Get identifier of the handle object
+fn id(handle: &Type) -> Result<usize, String> {
+    let (_, object) = handle.get_object();
+    object
+        .get("id")
+        .map(|x| x.get_number() as usize)
+        .ok_or("the value is not a mqtt client".to_string())
+}
+
+/// Get client of the handle object
+fn session(handle: &Type) -> Result<Arc<Session>, String> {
+    let id = id(handle)?;
+    sessions()
+        .1
+        .get(&id)
+        .cloned()
+        .ok_or(format!("mqtt client {id} is disconnected"))
+}
+
+/// Connect to the broker by "host:port" or options object, and return handle object
+pub fn connect(option: &Type) -> Result<Type, String> {
+    let (host, port, client_id, credentials, keep_alive, security) =
+        if let Type::Object(_, value) = option {
+            let get = |key: &str| value.get(key).map(|x| x.get_string());
+            let flag = |key: &str| value.get(key).map(|x| x.get_bool()).unwrap_or(false);
+            let secure = flag("tls");
+            (
+                get("host").unwrap_or("127.0.0.1".to_string()),
+                value
+                    .get("port")
+                    .map(|x| x.get_number() as u16)
+                    .unwrap_or(if secure { 8883 } else { 1883 }),
+                get("client-id"),
+                get("username").map(|username| (username, get("password").unwrap_or_default())),
+                value
+                    .get("keep-alive")
+                    .map(|x| x.get_number())
+                    .unwrap_or(30.0),
+                // Whether to accept any certificate when TLS is enabled
+                secure.then(|| flag("insecure")),
+            )
+        } else {
+            let address = option.get_string();
+            let (host, port) = address.rsplit_once(':').unwrap_or((&address, "1883"));
+            let port = port
+                .parse()
+                .map_err(|_| format!("invalid port \"{port}\""))?;
+            (host.to_string(), port, None, None, 30.0, None)
+        };
+    let tls_config = security.map(tls::config).transpose()?;
+
+    let mut registry = sessions();
+    registry.0 += 1;
+    let id = registry.0;
+
+    let client_id = client_id.unwrap_or(format!("stack-server-{}-{id}", process::id()));
+    let mut options = MqttOptions::new(client_id, host.clone(), port);
+    options.set_keep_alive(Duration::from_secs_f64(keep_alive.max(5.0)));
+    if let Some((username, password)) = credentials {
+        options.set_credentials(username, password);
+    }
+    if let Some(config) = tls_config {
+        options.set_transport(Transport::tls_with_config(TlsConfiguration::Rustls(config)));
+    }
+
+    let (client, mut connection) = Client::new(options, 64);
+    let session = Arc::new(Session {
+        client,
+        subscriptions: Mutex::new(Vec::new()),
+    });
+    registry.1.insert(id, session.clone());
+    drop(registry);
+
+    // Drive the connection and deliver messages in the background
+    thread::spawn(move || {
+        for event in connection.iter() {
+            match event {
+                Ok(Event::Incoming(Packet::Publish(publish))) => deliver(&session, publish),
+                Ok(_) => {}
+                Err(e) => {
+                    if !sessions().1.contains_key(&id) {
+                        break;
+                    }
+                    println!("Error! mqtt: {e}");
+                    thread::sleep(Duration::from_secs(1));
+                }
+            }
+        }
+    });
+
+    Ok(Type::Object(
+        "mqtt".to_string(),
+        HashMap::from([
+            ("id".to_string(), Type::Number(id as f64)),
+            (
+                "host".to_string(),
+                Type::String(format!("{host}:{port}").into()),
+            ),
+        ]),
+    ))
+}
+
+/// Run code of the subscriptions matching the message's topic
+fn deliver(session: &Session, publish: rumqttc::Publish) {
+    let payload = match String::from_utf8(publish.payload.to_vec()) {
+        Ok(text) => Type::String(text.into()),
+        Err(e) => Type::Binary(e.into_bytes().into()),
+    };
+    let message = Type::Object(
+        "message".to_string(),
+        HashMap::from([
+            (
+                "topic".to_string(),
+                Type::String(publish.topic.clone().into()),
+            ),
+            ("payload".to_string(), payload),
+        ]),
+    );
+
+    let subscriptions = session
+        .subscriptions
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    for subscription in subscriptions.iter() {
+        if rumqttc::matches(&publish.topic, &subscription.filter) {
+            let (code, mut executor) = (subscription.code.clone(), subscription.executor.clone());
+            let message = message.clone();
+            // Run apart from the connection so that the code can publish
+            thread::spawn(move || {
+                executor.stack.push(message);
+                executor.evaluate_program(code);
+            });
+        }
+    }
+}
+
+/// Publish the payload to the topic
+pub fn publish(handle: &Type, topic: &str, payload: Type) -> Result<(), String> {
+    let payload = match payload {
+        Type::Binary(data) => data.into_inner(),
+        other => other.get_string().into_bytes(),
+    };
+    session(handle)?
+        .client
+        .publish(topic, QoS::AtLeastOnce, false, payload)
+        .map_err(|e| e.to_string())
+}
+
+/// Subscribe to the topic filter, running the code with each message
+pub fn subscribe(
+    handle: &Type,
+    filter: &str,
+    code: String,
+    mut executor: Executor,
+) -> Result<(), String> {
+    let session = session(handle)?;
+    session
+        .client
+        .subscribe(filter, QoS::AtLeastOnce)
+        .map_err(|e| e.to_string())?;
+    executor.stack.clear();
+    session
+        .subscriptions
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Subscription {
+            filter: filter.to_string(),
+            code,
+            executor,
+        });
+    Ok(())
+}
+
+/// Disconnect from the broker, and return whether it was connected
+pub fn disconnect(handle: &Type) -> bool {
+    let Ok(id) = id(handle) else {
+        return false;
+    };
+    match sessions().1.remove(&id) {
+        Some(session) => session.client.disconnect().is_ok(),
+        None => false,
+    }
+}
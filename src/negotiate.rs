@@ -0,0 +1,103 @@
+/// Media range of the Accept header like "text/*;q=0.5"
+struct Range {
+    kind: String,    // Type like "text" or "*"
+    subtype: String, // Subtype like "html" or "*"
+    quality: f64,    // Preference from 0 to 1
+}
+
+impl Range {
+    /// How specifically the range matches the media type (None unless it matches)
+    fn matches(&self, media: &str) -> Option<u8> {
+        let media = media.split(';').next().unwrap_or_default().trim();
+        let (kind, subtype) = media.split_once('/').unwrap_or((media, ""));
+        match (self.kind.as_str(), self.subtype.as_str()) {
+            ("*", "*") => Some(0),
+            (k, "*") if k.eq_ignore_ascii_case(kind) => Some(1),
+            (k, s) if k.eq_ignore_ascii_case(kind) && s.eq_ignore_ascii_case(subtype) => Some(2),
+            _ => None,
+        }
+    }
+}
+
+/// Parse the media ranges of the Accept header
+fn parse(accept: &str) -> Vec<Range> {
+    accept
+        .split(',')
+        .filter_map(|item| {
+            let mut params = item.split(';');
+            let (kind, subtype) = params.next()?.trim().split_once('/')?;
+            let quality = params
+                .filter_map(|x| x.trim().strip_prefix("q="))
+                .find_map(|x| x.trim().parse::<f64>().ok())
+                .unwrap_or(1.0);
+            Some(Range {
+                kind: kind.trim().to_string(),
+                subtype: subtype.trim().to_string(),
+                quality: quality.clamp(0.0, 1.0),
+            })
+        })
+        .collect()
+}
+
+/// Choose the media type offered by the route that the client prefers the most by the
+/// Accept header, taking the quality of the most specific range matching each of them.
+/// The earlier one wins the tie, and None means nothing is acceptable (406)
+pub fn choose<'a>(accept: Option<&str>, offered: &'a [String]) -> Option<&'a str> {
+    let Some(accept) = accept.filter(|x| !x.trim().is_empty()) else {
+        return offered.first().map(|x| x.as_str());
+    };
+    let ranges = parse(accept);
+    let mut best: Option<(&str, f64)> = None;
+    for media in offered {
+        let quality = ranges
+            .iter()
+            .filter_map(|x| Some((x.matches(media)?, x.quality)))
+            .max_by_key(|(specificity, _)| *specificity)
+            .map_or(0.0, |(_, quality)| quality);
+        if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+            best = Some((media, quality));
+        }
+    }
+    best.map(|(media, _)| media)
+}
+
+/// Choose the locale available that the client prefers the most by the Accept-Language header
+/// like "fr-CH, fr;q=0.9, en;q=0.8". The language range matches the same locale, the locale
+/// of its language ("fr-CH" to "fr"), or the regional one ("fr" to "fr-FR") in this order,
+/// and None means the header doesn't match any of them (the wildcard "*" is left to the default)
+pub fn choose_language<'a>(accept: Option<&str>, available: &'a [String]) -> Option<&'a str> {
+    let mut ranges: Vec<(&str, f64)> = accept
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|item| {
+            let mut params = item.split(';');
+            let range = params.next()?.trim();
+            let quality = params
+                .filter_map(|x| x.trim().strip_prefix("q="))
+                .find_map(|x| x.trim().parse::<f64>().ok())
+                .unwrap_or(1.0);
+            (!range.is_empty() && range != "*" && quality > 0.0).then_some((range, quality))
+        })
+        .collect();
+    // Stable sort keeps the order of the header in the tie
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let find = |matches: &dyn Fn(&str) -> bool| {
+        available
+            .iter()
+            .find(|x| matches(x.as_str()))
+            .map(|x| x.as_str())
+    };
+    ranges.into_iter().find_map(|(range, _)| {
+        let language = range.split('-').next().unwrap_or(range);
+        find(&|x| x.eq_ignore_ascii_case(range))
+            .or_else(|| find(&|x| x.eq_ignore_ascii_case(language)))
+            .or_else(|| {
+                find(&|x| {
+                    x.split('-')
+                        .next()
+                        .is_some_and(|x| x.eq_ignore_ascii_case(language))
+                })
+            })
+    })
+}
@@ -0,0 +1,70 @@
+use crate::commands::Commands;
+use crate::{ErrorKind, StackError, Type};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+/// Prefixes of the bcrypt hashes
+const BCRYPT_PREFIXES: [&str; 4] = ["$2a$", "$2b$", "$2x$", "$2y$"];
+
+/// Hash the password by Argon2id into the PHC string like "$argon2id$v=19$..."
+pub fn hash(password: &str) -> Result<String, String> {
+    let salt = SaltString::encode_b64(&rand::random::<[u8; 16]>()).map_err(|e| e.to_string())?;
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|x| x.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the stored value is a hash of Argon2 or bcrypt rather than the password itself
+pub fn is_hash(stored: &str) -> bool {
+    stored.starts_with("$argon2") || BCRYPT_PREFIXES.iter().any(|x| stored.starts_with(x))
+}
+
+/// Verify the password with the hash of Argon2 or bcrypt
+pub fn verify(password: &str, hash: &str) -> Result<bool, String> {
+    if hash.starts_with("$argon2") {
+        let hash = PasswordHash::new(hash).map_err(|e| e.to_string())?;
+        Ok(Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok())
+    } else if BCRYPT_PREFIXES.iter().any(|x| hash.starts_with(x)) {
+        bcrypt::verify(password, hash).map_err(|e| e.to_string())
+    } else {
+        Err("the hash is neither Argon2 nor bcrypt".to_string())
+    }
+}
+
+/// Check the password with the stored hash, or with the stored plain password
+/// comparing all the bytes not to leak the matched length by the time
+pub fn check(password: &str, stored: &str) -> bool {
+    if is_hash(stored) {
+        return verify(password, stored).unwrap_or(false);
+    }
+    password.len() == stored.len()
+        && password
+            .bytes()
+            .zip(stored.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Register the commands of the password hashing
+pub fn register_commands(commands: &mut Commands) {
+    // Hash the password to store it
+    commands.add("password-hash", |executor| {
+        let password = executor.pop_stack().get_string();
+        let hash = hash(&password).map_err(|e| StackError::new("password", e))?;
+        executor.stack.push(Type::String(hash.into()));
+        Ok(())
+    });
+
+    // Verify the password with the stored hash
+    commands.add("password-verify", |executor| {
+        let hash = executor.pop_stack().get_string();
+        let password = executor.pop_stack().get_string();
+        let verified = verify(&password, &hash)
+            .map_err(|e| StackError::with_kind(ErrorKind::Parse, "password", e))?;
+        executor.stack.push(Type::Bool(verified));
+        Ok(())
+    });
+}
@@ -0,0 +1,186 @@
+use crate::commands::{register_command, Commands};
+use crate::{wasm, StackError, Type};
+use libloading::Library;
+use serde_json::Value;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Version of the plugin interface
+const API_VERSION: u32 = 1;
+
+/// Name of the registration function exported by the plugin
+const ENTRY: &[u8] = b"stack_plugin_register";
+
+/// Function writing the output of the command
+type Write = unsafe extern "C" fn(output: *mut c_void, data: *const c_char);
+
+/// Command implemented in the plugin
+type Command = unsafe extern "C" fn(args: *const c_char, output: *mut c_void, write: Write) -> i32;
+
+/// Interface given to `int32_t stack_plugin_register(const StackPluginApi *api)` of the plugin.
+/// The command receives the popped values as JSON array, and writes JSON array of the values
+/// to push (or the error message) by `write(output, data)`, returning 0 on success.
+/// The type is registered as the command pushing the object with the fields of JSON object.
+/// The registration returns -1 if the name is already taken by another command.
+#[repr(C)]
+struct PluginApi {
+    version: u32,
+    host: *mut c_void,
+    register_command: unsafe extern "C" fn(
+        host: *mut c_void,
+        name: *const c_char,
+        arity: usize,
+        command: Command,
+    ) -> i32,
+    register_type:
+        unsafe extern "C" fn(host: *mut c_void, name: *const c_char, fields: *const c_char) -> i32,
+}
+
+/// Libraries kept loaded while the commands are used
+static LIBRARIES: Mutex<Vec<(PathBuf, Library)>> = Mutex::new(Vec::new());
+
+/// Names registered by the plugin being loaded
+struct Host {
+    names: Vec<String>,
+}
+
+/// Read the string given by the plugin
+unsafe fn read_string(pointer: *const c_char) -> Option<String> {
+    if pointer.is_null() {
+        return None;
+    }
+    Some(CStr::from_ptr(pointer).to_string_lossy().to_string())
+}
+
+/// Store the output of the command
+unsafe extern "C" fn write_output(output: *mut c_void, data: *const c_char) {
+    if let Some(data) = read_string(data) {
+        *(output as *mut String) = data;
+    }
+}
+
+/// Register the command of the plugin
+unsafe extern "C" fn register_plugin_command(
+    host: *mut c_void,
+    name: *const c_char,
+    arity: usize,
+    command: Command,
+) -> i32 {
+    let Some(name) = read_string(name) else {
+        return -1;
+    };
+    let registered = register_command(&name, move |executor| {
+        let mut args: Vec<Value> = (0..arity).map(|_| executor.pop_stack().to_json()).collect();
+        args.reverse();
+        let args = CString::new(Value::Array(args).to_string())
+            .map_err(|e| StackError::new("plugin", e))?;
+
+        let mut output = String::new();
+        let status = command(
+            args.as_ptr(),
+            &mut output as *mut String as *mut c_void,
+            write_output,
+        );
+        if status != 0 {
+            return Err(StackError::new("plugin", output));
+        }
+
+        if output.trim().is_empty() {
+            return Ok(());
+        }
+        match serde_json::from_str::<Value>(&output) {
+            Ok(Value::Array(values)) => {
+                executor.stack.extend(values.iter().map(Type::from_json));
+                Ok(())
+            }
+            _ => Err(StackError::new(
+                "plugin",
+                format!("invalid output of the command \"{output}\""),
+            )),
+        }
+    });
+    if registered.is_err() {
+        return -1;
+    }
+    (*(host as *mut Host)).names.push(name);
+    0
+}
+
+/// Register the command creating the object of the plugin's type
+unsafe extern "C" fn register_plugin_type(
+    host: *mut c_void,
+    name: *const c_char,
+    fields: *const c_char,
+) -> i32 {
+    let Some(name) = read_string(name) else {
+        return -1;
+    };
+    let fields = match read_string(fields).map(|x| serde_json::from_str::<Value>(&x)) {
+        Some(Ok(Value::Object(fields))) => fields,
+        None => Default::default(),
+        _ => return -1,
+    };
+    let type_name = name.clone();
+    let registered = register_command(&name, move |executor| {
+        let object = fields
+            .iter()
+            .map(|(key, value)| (key.to_owned(), Type::from_json(value)))
+            .collect();
+        executor.stack.push(Type::Object(type_name.clone(), object));
+        Ok(())
+    });
+    if registered.is_err() {
+        return -1;
+    }
+    (*(host as *mut Host)).names.push(name);
+    0
+}
+
+/// Load the plugin (shared library or WASM module) and return the names of the registered commands
+pub fn load(path: &str) -> Result<Vec<String>, String> {
+    if path.ends_with(".wasm") || path.ends_with(".wat") {
+        return wasm::load(path);
+    }
+    let path_buf = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("{path}: {e}"))?;
+    let mut libraries = LIBRARIES.lock().unwrap_or_else(|e| e.into_inner());
+    if libraries.iter().any(|(loaded, _)| *loaded == path_buf) {
+        return Ok(Vec::new());
+    }
+
+    // Initializers of the library run on loading, and it's the contract of the plugin
+    let library = unsafe { Library::new(&path_buf) }.map_err(|e| format!("{path}: {e}"))?;
+    let mut host = Host { names: Vec::new() };
+    let api = PluginApi {
+        version: API_VERSION,
+        host: &mut host as *mut Host as *mut c_void,
+        register_command: register_plugin_command,
+        register_type: register_plugin_type,
+    };
+    let register = unsafe { library.get::<unsafe extern "C" fn(*const PluginApi) -> i32>(ENTRY) }
+        .map(|register| *register)
+        .map_err(|e| format!("{path}: {e}"))?;
+    let status = unsafe { register(&api) };
+
+    // Keep the library even if it failed, since the commands may be registered
+    libraries.push((path_buf, library));
+    if status != 0 {
+        return Err(format!("{path}: registration failed with status {status}"));
+    }
+    Ok(host.names)
+}
+
+/// Register the commands of the plugin
+pub fn register_commands(commands: &mut Commands) {
+    // Load the plugin and push the names of the added commands
+    commands.add("load-plugin", |executor| {
+        let path = executor.pop_stack().get_string();
+        let names = load(&path).map_err(|e| StackError::new("plugin", e))?;
+        executor.stack.push(Type::List(
+            names.into_iter().map(|x| Type::String(x.into())).collect(),
+        ));
+        Ok(())
+    });
+}
@@ -0,0 +1,246 @@
+use crate::commands::Commands;
+use crate::{ErrorKind, StackError, Type};
+use percent_encoding::{percent_decode, percent_decode_str};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::time::{Duration, Instant};
+
+/// Limit of the request line and the headers
+const HEAD_LIMIT: usize = 64 * 1024;
+
+/// Limit of the line of the chunk size
+const CHUNK_LINE_LIMIT: usize = 1024;
+
+/// How long to wait for the rest of the request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to discard the unread request after refusing it
+const LINGER: Duration = Duration::from_secs(1);
+
+/// Limit of the request body unless the server or the route sets it
+pub const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Request being handled, kept as it's sent to be decoded on demand
+#[derive(Debug)]
+pub struct Request {
+    pub method: String, // Method like "GET"
+    pub path: String,   // Path without the query
+    pub query: String,  // Query string without "?"
+    pub head: String,   // Request line and the headers
+    pub body: Vec<u8>,  // Body (the chunks are joined)
+}
+
+impl Request {
+    /// Object of the request with the raw and the percent-decoded path, query and body
+    fn to_object(&self) -> Type {
+        let string = |x: &str| Type::String(x.into());
+        let decode = |x: &str| string(&percent_decode_str(x).decode_utf8_lossy());
+
+        // Headers by the lowercase name (joined by comma if it's repeated)
+        let mut headers: HashMap<String, Type> = HashMap::new();
+        for (name, value) in self.head.lines().skip(1).filter_map(|x| x.split_once(':')) {
+            let name = name.trim().to_ascii_lowercase();
+            let value = match headers.remove(&name) {
+                Some(first) => format!("{}, {}", first.get_string(), value.trim()),
+                None => value.trim().to_string(),
+            };
+            headers.insert(name, string(&value));
+        }
+
+        Type::Object(
+            "request".to_string(),
+            HashMap::from([
+                ("method".to_string(), string(&self.method)),
+                ("path".to_string(), decode(&self.path)),
+                ("raw-path".to_string(), string(&self.path)),
+                ("query".to_string(), decode(&self.query)),
+                ("raw-query".to_string(), string(&self.query)),
+                (
+                    "body".to_string(),
+                    string(&percent_decode(&self.body).decode_utf8_lossy()),
+                ),
+                (
+                    "raw-body".to_string(),
+                    Type::Binary(self.body.clone().into()),
+                ),
+                (
+                    "headers".to_string(),
+                    Type::Object("headers".to_string(), headers),
+                ),
+            ]),
+        )
+    }
+}
+
+/// Reason to refuse the request before handling it
+pub enum Rejection {
+    HeadTooLarge, // Headers are over the limit
+    TooLarge,     // Body is over the limit
+    Malformed,    // Body doesn't match its length or chunks
+}
+
+impl Rejection {
+    /// Status line of the response
+    pub fn status(&self) -> &'static str {
+        match self {
+            Rejection::HeadTooLarge => "431 Request Header Fields Too Large",
+            Rejection::TooLarge => "413 Payload Too Large",
+            Rejection::Malformed => "400 Bad Request",
+        }
+    }
+}
+
+impl From<io::Error> for Rejection {
+    fn from(_: io::Error) -> Rejection {
+        Rejection::Malformed
+    }
+}
+
+/// Read the request line and the headers until the empty line by the chunk size,
+/// and return them with the data read after them
+pub fn read_head(stream: &mut TcpStream, chunk: usize) -> Result<(String, Vec<u8>), Rejection> {
+    // Don't wait forever for the client stopping in the middle of the request
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut data = Vec::new();
+    let mut buffer = vec![0; chunk.max(1)];
+    loop {
+        if let Some(end) = data.windows(4).position(|x| x == b"\r\n\r\n") {
+            let rest = data.split_off(end + 4);
+            return Ok((String::from_utf8_lossy(&data).to_string(), rest));
+        }
+        if data.len() > HEAD_LIMIT {
+            return Err(Rejection::HeadTooLarge);
+        }
+        let size = stream.read(&mut buffer)?;
+        if size == 0 {
+            // The connection is closed without the empty line
+            return Ok((String::from_utf8_lossy(&data).to_string(), Vec::new()));
+        }
+        data.extend_from_slice(&buffer[..size]);
+    }
+}
+
+/// Get the value of the header (the name is case-insensitive)
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+/// Read the body by Content-Length or chunked Transfer-Encoding, continuing the data
+/// read after the headers. The length is checked before reading the body
+pub fn read_body(
+    stream: &mut (impl Read + Write),
+    head: &str,
+    rest: Vec<u8>,
+    limit: usize,
+) -> Result<Vec<u8>, Rejection> {
+    let chunked = header(head, "Transfer-Encoding")
+        .is_some_and(|x| x.to_ascii_lowercase().contains("chunked"));
+    let length: Option<usize> = match header(head, "Content-Length") {
+        Some(length) => Some(length.parse().map_err(|_| Rejection::Malformed)?),
+        None => None,
+    };
+    if length.is_some_and(|x| x > limit) {
+        return Err(Rejection::TooLarge);
+    }
+    if !chunked && length.unwrap_or(0) == 0 {
+        return Ok(Vec::new());
+    }
+
+    // Let the client send the body it's waiting to send
+    if header(head, "Expect").is_some_and(|x| x.eq_ignore_ascii_case("100-continue")) {
+        stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+    }
+
+    let mut reader = BufReader::new(Cursor::new(rest).chain(stream));
+    if chunked {
+        return read_chunks(&mut reader, limit);
+    }
+    let length = length.unwrap_or(0);
+    let mut body = Vec::with_capacity(length);
+    reader.take(length as u64).read_to_end(&mut body)?;
+    if body.len() < length {
+        return Err(Rejection::Malformed);
+    }
+    Ok(body)
+}
+
+/// Read the chunks of the size in hexadecimal until the last empty chunk and the trailers
+fn read_chunks(reader: &mut impl BufRead, limit: usize) -> Result<Vec<u8>, Rejection> {
+    let mut body = Vec::new();
+    loop {
+        let line = read_line(reader, CHUNK_LINE_LIMIT)?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| Rejection::Malformed)?;
+        if size == 0 {
+            break;
+        }
+        // Huge sizes near the maximum mustn't overflow before the check
+        if body.len().checked_add(size).is_none_or(|x| x > limit) {
+            return Err(Rejection::TooLarge);
+        }
+
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        let mut end = [0; 2];
+        reader.read_exact(&mut end)?;
+        if &end != b"\r\n" {
+            return Err(Rejection::Malformed);
+        }
+    }
+
+    // Skip the trailers until the empty line, within the limit of the headers
+    let mut trailers = reader.take(HEAD_LIMIT as u64);
+    loop {
+        let line = read_line(&mut trailers, HEAD_LIMIT)?;
+        if line.trim().is_empty() {
+            return Ok(body);
+        }
+        if trailers.limit() == 0 {
+            return Err(Rejection::Malformed);
+        }
+    }
+}
+
+/// Read the line up to the limit, refusing the longer one
+fn read_line(reader: &mut impl BufRead, limit: usize) -> Result<String, Rejection> {
+    let mut line = String::new();
+    reader.take(limit as u64).read_line(&mut line)?;
+    if line.len() == limit && !line.ends_with('\n') {
+        return Err(Rejection::Malformed);
+    }
+    Ok(line)
+}
+
+/// Discard the rest of the refused request for a while, so that the connection isn't reset
+/// by the unread data before the client reads the response
+pub fn linger(stream: &mut TcpStream) {
+    stream.shutdown(Shutdown::Write).ok();
+    stream.set_read_timeout(Some(LINGER / 10)).ok();
+    let started = Instant::now();
+    let mut buffer = [0; 8192];
+    while started.elapsed() < LINGER && matches!(stream.read(&mut buffer), Ok(1..)) {}
+}
+
+/// Register the commands of the request
+pub fn register_commands(commands: &mut Commands) {
+    // Get the request being handled by the server
+    commands.add("request", |executor| {
+        let request = executor
+            .request
+            .as_ref()
+            .ok_or(StackError::with_kind(
+                ErrorKind::NotFound,
+                "request",
+                "no request is being handled",
+            ))?
+            .to_object();
+        executor.stack.push(request);
+        Ok(())
+    });
+}
@@ -0,0 +1,182 @@
+use crate::{route_options, Executor, Mode, Type};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Whether the servers print their routes instead of listening
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Number of the servers whose routes are printed
+static PRINTED: AtomicUsize = AtomicUsize::new(0);
+
+/// Commands affecting the outside of the program (or waiting for it), skipped in the dry-run
+const SIDE_EFFECTS: [&str; 43] = [
+    "write-file",
+    "temp-file",
+    "temp-dir",
+    "input",
+    "log-file",
+    "thread",
+    "thread-spawn",
+    "thread-timeout",
+    "async",
+    "watch",
+    "on-signal",
+    "exit",
+    "sleep",
+    "schedule",
+    "after",
+    "every",
+    "job-enqueue",
+    "sql",
+    "db-open",
+    "db-query",
+    "db-query-json",
+    "db-execute",
+    "db-transaction",
+    "db-begin",
+    "db-commit",
+    "db-rollback",
+    "kv-store",
+    "kv-set",
+    "kv-del",
+    "send-mail",
+    "ws-connect",
+    "ws-send",
+    "ws-receive",
+    "mqtt-connect",
+    "mqtt-publish",
+    "mqtt-subscribe",
+    "mqtt-disconnect",
+    "start-tcp-server",
+    "tcp-connect",
+    "tcp-read-line",
+    "tcp-read",
+    "tcp-write",
+    "load-plugin",
+];
+
+/// Whether the program is evaluated to list the routes
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Whether the command is skipped, since the program is evaluated only to list the routes.
+/// The values the command would push aren't available to the routes in the dry-run
+pub fn is_skipped(command: &str) -> bool {
+    is_dry_run() && SIDE_EFFECTS.contains(&command)
+}
+
+/// Row of the route table
+struct Route {
+    method: String,
+    path: String,
+    auth: String,
+    handler: String,
+}
+
+/// Read the route entry like `[[(GET /) (auth) (users)] (...)]`
+fn parse(entry: &Type) -> Option<Route> {
+    let Type::List(entry) = entry else {
+        return None;
+    };
+    let (Some(Type::List(matching)), Some(code)) = (entry.first(), entry.get(1)) else {
+        return None;
+    };
+    let route = matching.first()?.get_string();
+    let (method, path) = route.trim().split_once(' ').unwrap_or(("", route.trim()));
+
+    // Authentication is required when the second item is "auth" or "api-key"
+    let auth = match (matching.get(1).map(|x| x.get_string()), matching.get(2)) {
+        (Some(kind), Some(users)) if kind == "auth" => {
+            format!("basic ({})", users.get_string().trim())
+        }
+        (Some(kind), Some(keys)) if kind == "api-key" => {
+            format!("api-key ({})", keys.get_string().trim())
+        }
+        // Server refuses to start with the unknown authentication
+        (Some(kind), Some(_)) => format!("unknown ({kind})"),
+        _ => "-".to_string(),
+    };
+    let options = entry.get(2).map(route_options).unwrap_or_default();
+    Some(Route {
+        method: method.to_string(),
+        path: path.trim().to_string(),
+        auth,
+        handler: match (code, options.get("static"), options.get("upload")) {
+            // Directory served by the static route
+            (_, Some(dir), _) => format!("static ({})", dir.get_string()),
+            // Directory of the resumable uploads
+            (_, _, Some(dir)) => format!("upload ({})", dir.get_string()),
+            // Resolvers of the GraphQL schema
+            (Type::List(pairs), _, _) if options.contains_key("graphql") => {
+                format!("graphql ({} resolvers)", pairs.len())
+            }
+            // Methods of JSON-RPC
+            (Type::List(pairs), _, _) if options.get("json-rpc").is_some_and(|x| x.get_bool()) => {
+                format!("json-rpc ({} methods)", pairs.len())
+            }
+            // Representations chosen by the Accept header
+            (Type::List(pairs), None, None) => pairs
+                .iter()
+                .map(|x| {
+                    x.get_list()
+                        .first()
+                        .map(|x| x.get_string())
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<String>>()
+                .join(", "),
+            _ => format!("{} bytes", code.get_string().trim().len()),
+        },
+    })
+}
+
+/// Print the route table of the server
+pub fn print(name: &str, address: &str, code: &Type) {
+    let entries = code.get_list();
+    let routes: Vec<Route> = entries.iter().filter_map(parse).collect();
+
+    let mut rows = vec![[
+        "METHOD".to_string(),
+        "PATH".to_string(),
+        "AUTH".to_string(),
+        "HANDLER".to_string(),
+    ]];
+    rows.extend(
+        routes
+            .into_iter()
+            .map(|x| [x.method, x.path, x.auth, x.handler]),
+    );
+    let widths: Vec<usize> = (0..4)
+        .map(|i| rows.iter().map(|x| x[i].chars().count()).max().unwrap_or(0))
+        .collect();
+
+    if PRINTED.fetch_add(1, Ordering::Relaxed) > 0 {
+        println!();
+    }
+    println!("Server '{name}' on http://{address}");
+    for row in &rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+
+    let invalid = entries.len() + 1 - rows.len();
+    if invalid > 0 {
+        println!("Warning! {invalid} invalid route entries are ignored");
+    }
+}
+
+/// Evaluate the program without binding the port, and print the routes of its servers
+pub fn run(code: String, mode: Mode) -> Result<(), String> {
+    DRY_RUN.store(true, Ordering::Relaxed);
+    let mut executor = Executor::new(mode);
+    executor.evaluate_program(code);
+
+    if PRINTED.load(Ordering::Relaxed) == 0 {
+        return Err("server is not started in the program".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use crate::{Executor, Type};
+use chrono::Local;
+use cron::Schedule;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Condvar, LazyLock, Mutex, MutexGuard, Once};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Longest seconds to sleep at once, checking the tasks again after it
+const MAX_WAIT: f64 = 3600.0;
+
+/// Timing when the task should run
+#[derive(Clone)]
+enum Timing {
+    Cron(String, Box<Schedule>), // Cron expression
+    Interval(f64),               // Fixed interval seconds
+    Once(f64),                   // Delay seconds to run only once
+}
+
+/// Task registered in the scheduler
+struct Task {
+    id: usize,          // Identifier to cancel
+    timing: Timing,     // Timing to run
+    code: String,       // Code to run
+    executor: Executor, // Executor that keeps state between runs
+    next: f64,          // Next unix epoch time to run
+}
+
+/// Background scheduler of tasks
+struct Scheduler {
+    tasks: Mutex<(usize, Vec<Task>)>, // Last identifier and tasks
+    signal: Condvar,                  // Wake up when the tasks are changed
+    started: Once,                    // Start the thread only once
+}
+
+static SCHEDULER: LazyLock<Scheduler> = LazyLock::new(|| Scheduler {
+    tasks: Mutex::new((0, Vec::new())),
+    signal: Condvar::new(),
+    started: Once::new(),
+});
+
+/// Get now time as unix epoch
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+/// Lock the task list
+fn lock() -> MutexGuard<'static, (usize, Vec<Task>)> {
+    SCHEDULER.tasks.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Timing {
+    /// Parse interval seconds or cron expression
+    fn parse(spec: &Type) -> Result<Timing, String> {
+        if let Type::Number(seconds) = spec {
+            return Timing::interval(*seconds);
+        }
+
+        let expression = spec.get_string();
+        // Standard 5 fields expression doesn't have seconds
+        let full = if expression.split_whitespace().count() == 5 {
+            format!("0 {expression}")
+        } else {
+            expression.clone()
+        };
+        Schedule::from_str(&full)
+            .map(|schedule| Timing::Cron(expression, Box::new(schedule)))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Run once after the delay
+    fn once(seconds: f64) -> Result<Timing, String> {
+        if seconds.is_finite() && seconds >= 0.0 {
+            Ok(Timing::Once(seconds))
+        } else {
+            Err("delay must be a finite number not negative".to_string())
+        }
+    }
+
+    /// Repeat at the fixed interval
+    fn interval(seconds: f64) -> Result<Timing, String> {
+        if seconds.is_finite() && seconds > 0.0 {
+            Ok(Timing::Interval(seconds))
+        } else {
+            Err("interval must be a finite positive number".to_string())
+        }
+    }
+
+    /// Get next time to run after the time
+    fn next(&self, after: f64) -> Option<f64> {
+        match self {
+            Timing::Interval(seconds) | Timing::Once(seconds) => Some(after + seconds),
+            Timing::Cron(_, schedule) => schedule
+                .after(&Local::now())
+                .next()
+                .map(|time| time.timestamp_millis() as f64 / 1000.0),
+        }
+    }
+
+    /// Show the timing
+    fn display(&self) -> Type {
+        match self {
+            Timing::Interval(seconds) => Type::Number(*seconds),
+            Timing::Once(seconds) => Type::String(format!("after {seconds}").into()),
+            Timing::Cron(expression, _) => Type::String(expression.to_owned().into()),
+        }
+    }
+}
+
+/// Register code to run on the timing, and return the task id
+pub fn schedule(spec: &Type, code: String, executor: Executor) -> Result<usize, String> {
+    register(Timing::parse(spec)?, code, executor)
+}
+
+/// Register code to run once after the seconds, and return the task id
+pub fn after(seconds: f64, code: String, executor: Executor) -> Result<usize, String> {
+    register(Timing::once(seconds)?, code, executor)
+}
+
+/// Register code to run repeatedly at the interval, and return the task id
+pub fn every(seconds: f64, code: String, executor: Executor) -> Result<usize, String> {
+    register(Timing::interval(seconds)?, code, executor)
+}
+
+/// Register the task, and return its id
+fn register(timing: Timing, code: String, executor: Executor) -> Result<usize, String> {
+    let next = timing.next(now()).ok_or("the schedule never runs")?;
+
+    let id = {
+        let mut tasks = lock();
+        tasks.0 += 1;
+        let id = tasks.0;
+        tasks.1.push(Task {
+            id,
+            timing,
+            code,
+            executor,
+            next,
+        });
+        id
+    };
+
+    SCHEDULER.started.call_once(|| {
+        thread::spawn(run);
+    });
+    SCHEDULER.signal.notify_all();
+    Ok(id)
+}
+
+/// Cancel the task, and return whether it existed
+pub fn cancel(id: usize) -> bool {
+    let mut tasks = lock();
+    let len = tasks.1.len();
+    tasks.1.retain(|task| task.id != id);
+    SCHEDULER.signal.notify_all();
+    len != tasks.1.len()
+}
+
+/// Get list of registered tasks
+pub fn list() -> Vec<Type> {
+    lock()
+        .1
+        .iter()
+        .map(|task| {
+            Type::Object(
+                "task".to_string(),
+                HashMap::from([
+                    ("id".to_string(), Type::Number(task.id as f64)),
+                    ("schedule".to_string(), task.timing.display()),
+                    ("next".to_string(), Type::Number(task.next)),
+                ]),
+            )
+        })
+        .collect()
+}
+
+/// Main loop of the scheduler thread
+fn run() {
+    let mut tasks = lock();
+    loop {
+        let Some(task) = tasks
+            .1
+            .iter()
+            .min_by(|a, b| a.next.total_cmp(&b.next))
+            .map(|task| (task.id, task.next))
+        else {
+            // Wait until any task is registered
+            tasks = SCHEDULER
+                .signal
+                .wait(tasks)
+                .unwrap_or_else(|e| e.into_inner());
+            continue;
+        };
+
+        let (id, next) = task;
+        let wait = next - now();
+        if wait > 0.0 {
+            let wait = Duration::try_from_secs_f64(wait.min(MAX_WAIT)).unwrap_or_default();
+            tasks = SCHEDULER
+                .signal
+                .wait_timeout(tasks, wait)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+            continue;
+        }
+
+        // Run the task without locking the task list
+        let Some(index) = tasks.1.iter().position(|task| task.id == id) else {
+            continue;
+        };
+        let (code, mut executor) = (tasks.1[index].code.clone(), tasks.1[index].executor.clone());
+        drop(tasks);
+        executor.evaluate_program(code);
+        tasks = lock();
+
+        // Reschedule the task if it isn't cancelled while running
+        if let Some(index) = tasks.1.iter().position(|task| task.id == id) {
+            let task = &mut tasks.1[index];
+            task.executor = executor;
+            let next = match task.timing {
+                Timing::Once(_) => None,
+                _ => task.timing.next(next.max(now() - 1.0)),
+            };
+            match next {
+                Some(next) => task.next = next,
+                None => {
+                    tasks.1.remove(index);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::commands::Commands;
+use crate::{ErrorKind, StackError, Type};
+use jsonschema::Validator;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// JSON Schema checking the request bodies of the route
+pub struct Schema {
+    validator: Result<Validator, String>, // Compiled schema, or why it's invalid
+}
+
+/// Compile the JSON Schema
+fn compile(schema: &Value) -> Result<Validator, String> {
+    jsonschema::validator_for(schema).map_err(|e| e.to_string())
+}
+
+/// Get the violations of the value like `{"path": "/age", "message": "..."}`
+fn violations(validator: &Validator, value: &Value) -> Vec<Value> {
+    validator
+        .iter_errors(value)
+        .map(|e| json!({"path": e.instance_path.to_string(), "message": e.to_string()}))
+        .collect()
+}
+
+impl Schema {
+    /// Make the schema from the JSON, or from the file or the text of the JSON
+    pub fn new(schema: &Type) -> Schema {
+        let schema = match schema {
+            Type::String(_) => {
+                let schema = schema.get_string();
+                let source = match Path::new(schema.trim()).is_file() {
+                    true => fs::read_to_string(schema.trim()).map_err(|e| e.to_string()),
+                    false => Ok(schema),
+                };
+                source.and_then(|x| serde_json::from_str(&x).map_err(|e| e.to_string()))
+            }
+            schema => Ok(schema.to_json()),
+        };
+        let validator = schema.and_then(|x| compile(&x));
+        if let Err(e) = &validator {
+            println!("Error! JSON Schema of the route: {e}");
+        }
+        Schema { validator }
+    }
+
+    /// Check the request body, and get the response refusing it unless it's valid
+    pub fn check(&self, body: &[u8]) -> Option<String> {
+        let (status, error, violations) = match (&self.validator, serde_json::from_slice(body)) {
+            (Err(_), _) => ("500 Internal Server Error", "invalid schema", Vec::new()),
+            (Ok(_), Err(e)) => (
+                "400 Bad Request",
+                "invalid JSON",
+                vec![json!({"path": "", "message": e.to_string()})],
+            ),
+            (Ok(validator), Ok(value)) => match violations(validator, &value) {
+                violations if violations.is_empty() => return None,
+                violations => ("422 Unprocessable Entity", "invalid body", violations),
+            },
+        };
+        let json = json!({"error": error, "violations": violations});
+        Some(format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{json}"
+        ))
+    }
+}
+
+/// Register the commands of the JSON Schema
+pub fn register_commands(commands: &mut Commands) {
+    // Validate the value by the JSON Schema, getting the violations (empty if it's valid)
+    commands.add("json-validate", |executor| {
+        let schema = executor.pop_stack().to_json();
+        let value = executor.pop_stack().to_json();
+        let validator = compile(&schema)
+            .map_err(|e| StackError::with_kind(ErrorKind::Parse, "json-validate", e))?;
+        let violations = violations(&validator, &value)
+            .iter()
+            .map(|x| {
+                Type::Object(
+                    "violation".to_string(),
+                    HashMap::from([
+                        ("path".to_string(), Type::from_json(&x["path"])),
+                        ("message".to_string(), Type::from_json(&x["message"])),
+                    ]),
+                )
+            })
+            .collect::<Vec<Type>>();
+        executor.stack.push(Type::List(violations.into()));
+        Ok(())
+    });
+}
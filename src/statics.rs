@@ -0,0 +1,222 @@
+use crate::{livereload, templates};
+use chrono::{DateTime, Local};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tera::Context;
+
+/// Characters encoded in the links of the listing
+const LINK: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// File served in the directory instead of the listing
+const INDEX_FILE: &str = "index.html";
+
+/// Template of the directory listing unless the route sets it
+const LISTING: &str = r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>Index of {{ path }}</title></head>
+<body>
+<h1>Index of {{ path }}</h1>
+<table>
+<tr><th>Name</th><th>Last modified</th><th>Size</th></tr>
+{% if parent %}<tr><td><a href="../">../</a></td><td></td><td></td></tr>
+{% endif %}{% for entry in entries %}<tr><td><a href="{{ entry.href }}">{{ entry.name }}{% if entry.dir %}/{% endif %}</a></td><td>{{ entry.modified }}</td><td>{% if entry.dir %}-{% else %}{{ entry.size }}{% endif %}</td></tr>
+{% endfor %}</table>
+</body>
+</html>
+"#;
+
+/// Directory served by the static route
+pub struct Static {
+    pub mount: String,            // Path of the route like "/public"
+    pub dir: String,              // Directory of the files
+    pub index: bool,              // Whether to list the directory without index.html
+    pub template: Option<String>, // Template of the listing loaded from the template directory
+}
+
+/// Whether the path is the mount path of the route or under it
+pub fn mounted(mount: &str, path: &str) -> bool {
+    path == mount
+        || path
+            .strip_prefix(mount.trim_end_matches('/'))
+            .is_some_and(|x| x.starts_with('/'))
+}
+
+impl Static {
+    /// Get the file of the path under the mount path (None if it goes out of the directory)
+    fn resolve(&self, path: &str) -> Option<PathBuf> {
+        let rest = path.strip_prefix(self.mount.trim_end_matches('/'))?;
+        let rest = percent_decode_str(rest).decode_utf8().ok()?;
+        let mut file = PathBuf::from(&self.dir);
+        for part in rest.split('/').filter(|x| !x.is_empty() && *x != ".") {
+            if part == ".." || part.contains('\\') || part.contains('\0') {
+                return None;
+            }
+            file.push(part);
+        }
+        Some(file)
+    }
+
+    /// Respond the file, the index file or the listing of the directory of the path
+    pub fn respond(&self, stream: &mut impl Write, path: &str) -> io::Result<()> {
+        let Some(file) = self.resolve(path) else {
+            return plain(stream, "403 Forbidden");
+        };
+        let Ok(metadata) = fs::metadata(&file) else {
+            return plain(stream, "404 Not Found");
+        };
+        if !metadata.is_dir() {
+            return send_file(stream, &file);
+        }
+
+        // Let the relative links in the directory work
+        if !path.ends_with('/') {
+            let response = format!("HTTP/1.1 301 Moved Permanently\r\nLocation: {path}/\r\n\r\n");
+            return stream.write_all(response.as_bytes());
+        }
+        let index = file.join(INDEX_FILE);
+        if index.is_file() {
+            return send_file(stream, &index);
+        }
+        if !self.index {
+            return plain(stream, "403 Forbidden");
+        }
+        match self.listing(&file, path) {
+            Ok(mut html) => {
+                if livereload::is_enabled() {
+                    html = livereload::inject(&html);
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\n\r\n{html}",
+                    html.len()
+                );
+                stream.write_all(response.as_bytes())
+            }
+            Err(e) => {
+                println!("Error! directory listing of \"{path}\": {e}");
+                plain(stream, "500 Internal Server Error")
+            }
+        }
+    }
+
+    /// Render the listing of the directory with the names, the sizes and the modified times
+    fn listing(&self, dir: &Path, path: &str) -> Result<String, String> {
+        let mut entries: Vec<(bool, String, u64, String)> = Vec::new();
+        for entry in fs::read_dir(dir).map_err(|e| e.to_string())?.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let modified = metadata
+                .modified()
+                .map(|x| {
+                    DateTime::<Local>::from(x)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            entries.push((
+                metadata.is_dir(),
+                entry.file_name().to_string_lossy().to_string(),
+                metadata.len(),
+                modified,
+            ));
+        }
+        // Directories first, and then by the name
+        entries.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        let entries: Vec<serde_json::Value> = entries
+            .into_iter()
+            .map(|(dir, name, size, modified)| {
+                let href =
+                    utf8_percent_encode(&name, LINK).to_string() + if dir { "/" } else { "" };
+                serde_json::json!({
+                    "name": name,
+                    "href": href,
+                    "dir": dir,
+                    "size": size,
+                    "modified": modified,
+                })
+            })
+            .collect();
+        let mut context = Context::new();
+        context.insert("path", &percent_decode_str(path).decode_utf8_lossy());
+        context.insert(
+            "parent",
+            &(path.trim_end_matches('/') != self.mount.trim_end_matches('/')),
+        );
+        context.insert("entries", &entries);
+        match &self.template {
+            Some(name) => templates::render_file(name, &context),
+            None => templates::render_str(LISTING, &context),
+        }
+    }
+}
+
+/// Respond the status with its text
+fn plain(stream: &mut impl Write, status: &str) -> io::Result<()> {
+    let text = status.split_once(' ').map_or(status, |x| x.1);
+    let response = format!("HTTP/1.1 {status}\r\nContent-Type: text/plain\r\n\r\n{text}");
+    stream.write_all(response.as_bytes())
+}
+
+/// Respond the file with the content type by its extension
+fn send_file(stream: &mut impl Write, path: &Path) -> io::Result<()> {
+    let Ok(mut file) = File::open(path) else {
+        return plain(stream, "403 Forbidden");
+    };
+    let content_type = content_type(path);
+
+    // Let the page reload when the program is restarted by the change
+    if livereload::is_enabled() && content_type.starts_with("text/html") {
+        let html = livereload::inject(&io::read_to_string(file)?);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\r\n{html}",
+            html.len()
+        );
+        return stream.write_all(response.as_bytes());
+    }
+
+    let size = file.metadata()?.len();
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {size}\r\n\r\n"
+    );
+    stream.write_all(header.as_bytes())?;
+    io::copy(&mut file, stream).map(|_| ())
+}
+
+/// Get the content type by the extension of the file
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|x| x.to_string_lossy().to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js" | "mjs") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("txt" | "md" | "stk") => "text/plain; charset=utf-8",
+        Some("csv") => "text/csv; charset=utf-8",
+        Some("xml") => "application/xml",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("pdf") => "application/pdf",
+        Some("wasm") => "application/wasm",
+        Some("woff2") => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
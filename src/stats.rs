@@ -0,0 +1,187 @@
+use crate::commands::Commands;
+use crate::{Executor, Type};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the latency buckets in seconds
+const BUCKETS: [f64; 12] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// When the server is started
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+/// Number of the requests received
+static REQUESTS: AtomicU64 = AtomicU64::new(0);
+
+/// Number of the requests being handled
+static ACTIVE: AtomicU64 = AtomicU64::new(0);
+
+/// Number of the responses with the server error status
+static ERRORS: AtomicU64 = AtomicU64::new(0);
+
+/// Histogram of the durations to respond
+#[derive(Clone, Default)]
+struct Latency {
+    counts: [u64; BUCKETS.len() + 1], // Number of the requests in each bucket (the last is unbounded)
+    sum: f64,                         // Total of the durations in seconds
+    max: f64,                         // Longest duration in seconds
+}
+
+/// Requests by the matched route like "GET /"
+static ROUTES: LazyLock<Mutex<HashMap<String, Latency>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+impl Latency {
+    /// Count the duration into the bucket
+    fn record(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let bucket = BUCKETS
+            .iter()
+            .position(|x| seconds <= *x)
+            .unwrap_or(BUCKETS.len());
+        self.counts[bucket] += 1;
+        self.sum += seconds;
+        self.max = self.max.max(seconds);
+    }
+
+    /// Number of the requests
+    fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Summary with the cumulative counts by the upper bound like Prometheus
+    fn to_json(&self) -> Value {
+        let mut total = 0;
+        let buckets: serde_json::Map<String, Value> = self
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| {
+                total += count;
+                let bound = BUCKETS.get(i).map_or("+Inf".to_string(), |x| x.to_string());
+                (bound, json!(total))
+            })
+            .collect();
+        json!({
+            "count": total,
+            "sum": self.sum,
+            "mean": if total > 0 { self.sum / total as f64 } else { 0.0 },
+            "max": self.max,
+            "buckets": buckets,
+        })
+    }
+}
+
+/// Start counting the uptime of the server
+pub fn start() {
+    STARTED.get_or_init(Instant::now);
+}
+
+/// Count the request being handled
+pub fn begin() {
+    REQUESTS.fetch_add(1, Ordering::Relaxed);
+    ACTIVE.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count the handled request by the matched route, the status and the duration of the response
+pub fn finish(route: Option<&str>, status: u16, duration: Duration) {
+    ACTIVE.fetch_sub(1, Ordering::Relaxed);
+    if status >= 500 {
+        ERRORS.fetch_add(1, Ordering::Relaxed);
+    }
+    if let Some(route) = route {
+        ROUTES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(route.to_string())
+            .or_default()
+            .record(duration);
+    }
+}
+
+/// Estimate the bytes used by the value
+fn estimate(value: &Type) -> usize {
+    mem::size_of::<Type>()
+        + match value {
+            Type::String(s) => s.len(),
+            Type::List(list) => list.iter().map(estimate).sum(),
+            Type::Json(json) => json.to_string().len(),
+            Type::Object(name, object) => {
+                name.len()
+                    + object
+                        .iter()
+                        .map(|(key, value)| key.len() + estimate(value))
+                        .sum::<usize>()
+            }
+            Type::Error(err) => err.name.len() + err.message.len(),
+            Type::Binary(data) => data.len(),
+            _ => 0,
+        }
+}
+
+/// Resident memory of the process in bytes (only on Linux)
+fn resident() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|x| x.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Get the statistics of the server and the executor
+pub fn snapshot(executor: &Executor) -> Value {
+    let routes = ROUTES.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let hits: HashMap<&String, u64> = routes.iter().map(|(k, v)| (k, v.count())).collect();
+    let latency: HashMap<&String, Value> = routes.iter().map(|(k, v)| (k, v.to_json())).collect();
+    json!({
+        "uptime": STARTED.get().map(|x| x.elapsed().as_secs_f64()).unwrap_or(0.0),
+        "requests": REQUESTS.load(Ordering::Relaxed),
+        "active": ACTIVE.load(Ordering::Relaxed),
+        "errors": ERRORS.load(Ordering::Relaxed),
+        "routes": hits,
+        "latency": latency,
+        "stack-depth": executor.stack.len(),
+        "variables": executor.memory.len(),
+        "memory": {
+            "stack": executor.stack.iter().map(estimate).sum::<usize>(),
+            "variables": executor
+                .memory
+                .iter()
+                .map(|(name, value)| name.len() + estimate(value))
+                .sum::<usize>(),
+            "resident": resident(),
+        },
+    })
+}
+
+/// Get the response of the statistics if the request is for the admin path
+pub fn respond(
+    executor: &Executor,
+    path: Option<&str>,
+    method: &str,
+    target: &str,
+) -> Option<String> {
+    if method != "GET" || path? != target {
+        return None;
+    }
+    let body = snapshot(executor).to_string();
+    Some(format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\n\r\n{body}",
+        body.len()
+    ))
+}
+
+/// Register the commands of the statistics
+pub fn register_commands(commands: &mut Commands) {
+    // Get the statistics of the server and the executor
+    commands.add("server-stats", |executor| {
+        let stats = Type::from_json(&snapshot(executor));
+        executor.stack.push(stats);
+        Ok(())
+    });
+}
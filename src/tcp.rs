@@ -0,0 +1,331 @@
+use crate::commands::Commands;
+use crate::tls::{self, TlsStream};
+use crate::{proxy, Executor, Mode, StackError, Type};
+use rustls::ClientConnection;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::thread;
+
+/// Connection split into reading and writing halves
+struct Connection {
+    reader: Mutex<BufReader<Box<dyn Read + Send>>>, // Buffered reading half
+    writer: Mutex<Box<dyn Write + Send>>,           // Writing half
+    socket: TcpStream,                              // Socket to shut down
+}
+
+/// TLS session shared by the reading and writing halves
+type Session = Arc<Mutex<ClientConnection>>;
+
+/// Lock the TLS session
+fn session(tls: &Session) -> MutexGuard<'_, ClientConnection> {
+    tls.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Reading half of the TLS stream, which waits for the records on the socket
+/// without locking the session, so that the writing half isn't blocked meanwhile
+struct TlsReader {
+    tls: Session,
+    socket: TcpStream,
+}
+
+impl Read for TlsReader {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let mut records = vec![0; 16 * 1024];
+        loop {
+            match session(&self.tls).reader().read(buffer) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                result => return result,
+            }
+            let size = self.socket.read(&mut records)?;
+
+            // Decrypt the records (the end of the stream is given as the empty records),
+            // and send the replies like the key updates
+            let mut tls = session(&self.tls);
+            let mut received = &records[..size];
+            loop {
+                tls.read_tls(&mut received)?;
+                tls.process_new_packets()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                if received.is_empty() {
+                    break;
+                }
+            }
+            while tls.wants_write() {
+                tls.write_tls(&mut self.socket)?;
+            }
+        }
+    }
+}
+
+/// Writing half of the TLS stream
+struct TlsWriter {
+    tls: Session,
+    socket: TcpStream,
+}
+
+impl Write for TlsWriter {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let mut tls = session(&self.tls);
+        let size = tls.writer().write(buffer)?;
+        while tls.wants_write() {
+            tls.write_tls(&mut self.socket)?;
+        }
+        Ok(size)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut tls = session(&self.tls);
+        tls.writer().flush()?;
+        while tls.wants_write() {
+            tls.write_tls(&mut self.socket)?;
+        }
+        self.socket.flush()
+    }
+}
+
+/// Opened connections and the last identifier
+type Connections = (usize, HashMap<usize, Arc<Connection>>);
+
+static CONNECTIONS: LazyLock<Mutex<Connections>> =
+    LazyLock::new(|| Mutex::new((0, HashMap::new())));
+
+/// Lock the connection registry
+fn connections() -> MutexGuard<'static, Connections> {
+    CONNECTIONS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Get connection of the handle object
+fn connection(handle: &Type) -> Result<Arc<Connection>, String> {
+    let (_, object) = handle.get_object();
+    let id = object
+        .get("id")
+        .map(|x| x.get_number() as usize)
+        .ok_or("the value is not a connection")?;
+    connections()
+        .1
+        .get(&id)
+        .cloned()
+        .ok_or(format!("connection {id} is closed"))
+}
+
+/// Register the stream (encrypted by TLS if it is given), and return its handle object
+fn register(stream: TcpStream, tls: Option<TlsStream>) -> Result<Type, String> {
+    let peer = stream
+        .peer_addr()
+        .map(|x| x.to_string())
+        .unwrap_or_default();
+    let socket = stream.try_clone().map_err(|e| e.to_string())?;
+    let (reader, writer): (Box<dyn Read + Send>, Box<dyn Write + Send>) = match tls {
+        Some(tls) => {
+            let reader = TlsReader {
+                tls: Arc::new(Mutex::new(tls.conn)),
+                socket: tls.sock,
+            };
+            let writer = TlsWriter {
+                tls: reader.tls.clone(),
+                socket: reader.socket.try_clone().map_err(|e| e.to_string())?,
+            };
+            (Box::new(reader), Box::new(writer))
+        }
+        None => (
+            Box::new(stream.try_clone().map_err(|e| e.to_string())?),
+            Box::new(stream),
+        ),
+    };
+    let connection = Arc::new(Connection {
+        reader: Mutex::new(BufReader::new(reader)),
+        writer: Mutex::new(writer),
+        socket,
+    });
+
+    let mut connections = connections();
+    connections.0 += 1;
+    let id = connections.0;
+    connections.1.insert(id, connection);
+    Ok(Type::Object(
+        "tcp".to_string(),
+        HashMap::from([
+            ("id".to_string(), Type::Number(id as f64)),
+            ("peer".to_string(), Type::String(peer.into())),
+        ]),
+    ))
+}
+
+/// Accept connections and run the code with the connection on the stack
+pub fn serve(address: &str, code: String, executor: &Executor) -> Result<(), String> {
+    let listener = TcpListener::bind(address).map_err(|e| e.to_string())?;
+    if executor.mode >= Mode::Script {
+        println!("TCP server is started on {address}.");
+    }
+
+    for stream in listener.incoming() {
+        let handle = match stream
+            .map_err(|e| e.to_string())
+            .and_then(|stream| register(stream, None))
+        {
+            Ok(handle) => handle,
+            Err(e) => {
+                println!("Error! {e}");
+                continue;
+            }
+        };
+
+        let (code, mut executor) = (code.clone(), executor.clone());
+        thread::spawn(move || {
+            let peer = handle.get_object().1.get("peer").map(|x| x.get_string());
+            let _span =
+                tracing::info_span!("connection", peer = peer.unwrap_or_default()).entered();
+            executor.stack.clear();
+            executor.stack.push(handle.clone());
+            executor.evaluate_program(code);
+            close(&handle);
+        });
+    }
+    Ok(())
+}
+
+/// Connect to the server by "host:port" or options object, and return handle object
+pub fn connect(option: &Type) -> Result<Type, String> {
+    let (address, secure, insecure, server_name, proxy) = if let Type::Object(_, value) = option {
+        let address = value
+            .get("address")
+            .map(|x| x.get_string())
+            .unwrap_or_default();
+        let host = address
+            .rsplit_once(':')
+            .map(|(host, _)| host)
+            .unwrap_or(&address);
+        (
+            address.clone(),
+            value.get("tls").map(|x| x.get_bool()).unwrap_or(false),
+            value.get("insecure").map(|x| x.get_bool()).unwrap_or(false),
+            value
+                .get("server-name")
+                .map(|x| x.get_string())
+                .unwrap_or(host.to_string()),
+            value.get("proxy").map(|x| x.get_string()),
+        )
+    } else {
+        (option.get_string(), false, false, String::new(), None)
+    };
+
+    // Tunnel through the proxy only if the option specifies it. HTTP_PROXY and HTTPS_PROXY
+    // aren't applied, since the connection may carry any protocol other than HTTP
+    let stream = match proxy {
+        Some(proxy) => {
+            let (host, port) = address.rsplit_once(':').ok_or("port is not specified")?;
+            let port = port
+                .parse()
+                .map_err(|_| format!("invalid port \"{port}\""))?;
+            proxy::connect(host, port, Some(proxy))?
+        }
+        None => TcpStream::connect(&address).map_err(|e| e.to_string())?,
+    };
+    let tls = if secure {
+        let socket = stream.try_clone().map_err(|e| e.to_string())?;
+        Some(tls::connect(socket, &server_name, insecure)?)
+    } else {
+        None
+    };
+    register(stream, tls)
+}
+
+/// Read a line without the line break (None at the end of the stream)
+pub fn read_line(handle: &Type) -> Result<Option<String>, String> {
+    let connection = connection(handle)?;
+    let mut reader = connection.reader.lock().unwrap_or_else(|e| e.into_inner());
+    let mut line = String::new();
+    match reader.read_line(&mut line).map_err(|e| e.to_string())? {
+        0 => Ok(None),
+        _ => Ok(Some(line.trim_end_matches(['\r', '\n']).to_string())),
+    }
+}
+
+/// Read bytes up to the size (empty at the end of the stream)
+pub fn read(handle: &Type, size: usize) -> Result<Vec<u8>, String> {
+    let connection = connection(handle)?;
+    let mut reader = connection.reader.lock().unwrap_or_else(|e| e.into_inner());
+    let mut buffer = vec![0; size];
+    let size = reader.read(&mut buffer).map_err(|e| e.to_string())?;
+    buffer.truncate(size);
+    Ok(buffer)
+}
+
+/// Write the data into the connection
+pub fn write(handle: &Type, data: &[u8]) -> Result<(), String> {
+    let connection = connection(handle)?;
+    let mut writer = connection.writer.lock().unwrap_or_else(|e| e.into_inner());
+    writer
+        .write_all(data)
+        .and_then(|_| writer.flush())
+        .map_err(|e| e.to_string())
+}
+
+/// Close the connection, and return whether it was opened
+pub fn close(handle: &Type) -> bool {
+    let Ok(connection) = connection(handle) else {
+        return false;
+    };
+    let (_, object) = handle.get_object();
+    if let Some(id) = object.get("id") {
+        connections().1.remove(&(id.get_number() as usize));
+    }
+    connection.socket.shutdown(Shutdown::Both).ok();
+    true
+}
+
+/// Register the commands of TCP
+pub fn register_commands(commands: &mut Commands) {
+    // Start TCP server that runs code for each connection
+    commands.add("start-tcp-server", |executor| {
+        let code = executor.pop_stack().get_string();
+        let address = executor.pop_stack().get_string();
+        serve(&address, code, executor).map_err(|e| StackError::new("tcp", e))
+    });
+
+    // Connect to TCP server
+    commands.add("tcp-connect", |executor| {
+        let option = executor.pop_stack();
+        let connection = connect(&option).map_err(|e| StackError::new("tcp", e))?;
+        executor.stack.push(connection);
+        Ok(())
+    });
+
+    // Read a line from the TCP connection
+    commands.add("tcp-read-line", |executor| {
+        let connection = executor.pop_stack();
+        let line = read_line(&connection).map_err(|e| StackError::new("tcp", e))?;
+        executor
+            .stack
+            .push(line.map(|x| Type::String(x.into())).unwrap_or(Type::Null));
+        Ok(())
+    });
+
+    // Read bytes from the TCP connection
+    commands.add("tcp-read", |executor| {
+        let size = executor.pop_stack().get_number() as usize;
+        let connection = executor.pop_stack();
+        let data = read(&connection, size).map_err(|e| StackError::new("tcp", e))?;
+        executor.stack.push(Type::Binary(data.into()));
+        Ok(())
+    });
+
+    // Write string or binary into the TCP connection
+    commands.add("tcp-write", |executor| {
+        let data = match executor.pop_stack() {
+            Type::Binary(data) => data,
+            other => other.get_string().into_bytes().into(),
+        };
+        let connection = executor.pop_stack();
+        write(&connection, &data).map_err(|e| StackError::new("tcp", e))
+    });
+
+    // Close the TCP connection
+    commands.add("tcp-close", |executor| {
+        let connection = executor.pop_stack();
+        executor.stack.push(Type::Bool(close(&connection)));
+        Ok(())
+    });
+}
@@ -0,0 +1,200 @@
+use crate::commands::Commands;
+use crate::Type;
+use opentelemetry::metrics::Histogram;
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+/// Name of the service unless OTEL_SERVICE_NAME is set
+const SERVICE_NAME: &str = "stack-server";
+
+/// Format of the spans and events printed into standard error
+pub enum Format {
+    Full,    // One line per event with the span context
+    Compact, // Shorter lines
+    Pretty,  // Multiple lines per event
+    Json,    // One JSON object per event
+}
+
+impl Format {
+    /// Get the format by the name
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "full" => Some(Format::Full),
+            "compact" => Some(Format::Compact),
+            "pretty" => Some(Format::Pretty),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Providers exporting the spans and the metrics by OTLP
+struct Exporter {
+    tracer: SdkTracerProvider, // Provider of the tracer for the spans
+    meter: SdkMeterProvider,   // Provider of the meter for the metrics
+    duration: Histogram<f64>,  // Durations of the requests in seconds
+}
+
+static EXPORTER: OnceLock<Exporter> = OnceLock::new();
+
+/// Layer added to the subscriber
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Make the layer printing the spans into standard error in the format
+fn print_layer(filter: EnvFilter, format: Format) -> BoxedLayer {
+    let layer = tracing_subscriber::fmt::layer()
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal());
+    match format {
+        Format::Full => layer.with_filter(filter).boxed(),
+        Format::Compact => layer.compact().with_filter(filter).boxed(),
+        Format::Pretty => layer.pretty().with_filter(filter).boxed(),
+        Format::Json => layer.json().with_filter(filter).boxed(),
+    }
+}
+
+/// Start exporting the spans and the metrics to the collector by OTLP/HTTP
+/// like "http://localhost:4318", and make the layer sending the spans to it
+fn export_layer(filter: EnvFilter, endpoint: &str) -> Result<BoxedLayer, String> {
+    let endpoint = endpoint.trim_end_matches('/');
+    let mut resource = Resource::builder();
+    if env::var("OTEL_SERVICE_NAME").is_err() {
+        resource = resource.with_service_name(SERVICE_NAME);
+    }
+    let resource = resource.build();
+
+    let spans = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{endpoint}/v1/traces"))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let tracer = SdkTracerProvider::builder()
+        .with_batch_exporter(spans)
+        .with_resource(resource.clone())
+        .build();
+
+    let metrics = MetricExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{endpoint}/v1/metrics"))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let meter = SdkMeterProvider::builder()
+        .with_periodic_exporter(metrics)
+        .with_resource(resource)
+        .build();
+    global::set_meter_provider(meter.clone());
+    let duration = global::meter(SERVICE_NAME)
+        .f64_histogram("http.server.request.duration")
+        .with_unit("s")
+        .with_description("Duration of the HTTP requests")
+        .build();
+
+    // Continue the traces of the clients by the W3C trace context
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(tracer.tracer(SERVICE_NAME))
+        .with_filter(filter)
+        .boxed();
+    EXPORTER
+        .set(Exporter {
+            tracer,
+            meter,
+            duration,
+        })
+        .ok();
+    Ok(layer)
+}
+
+/// Print the spans (with their time when they are closed) and events enabled by the filter
+/// like "info" or "stack_server=debug", and export them to the OTLP endpoint if it's given
+pub fn init(filter: Option<&str>, format: Format, endpoint: Option<&str>) -> Result<(), String> {
+    let parse = |filter: &str| EnvFilter::try_new(filter).map_err(|e| e.to_string());
+    let mut layers: Vec<BoxedLayer> = Vec::new();
+    if let Some(filter) = filter {
+        layers.push(print_layer(parse(filter)?, format));
+    }
+    if let Some(endpoint) = endpoint {
+        layers.push(export_layer(parse(filter.unwrap_or("info"))?, endpoint)?);
+    }
+    tracing_subscriber::registry()
+        .with(layers)
+        .try_init()
+        .map_err(|e| e.to_string())
+}
+
+/// Continue the trace given by the "traceparent" and "tracestate" headers in the span
+pub fn set_parent(span: &tracing::Span, traceparent: Option<&str>, tracestate: Option<&str>) {
+    if EXPORTER.get().is_none() {
+        return;
+    }
+    let Some(traceparent) = traceparent else {
+        return;
+    };
+    let mut headers = HashMap::from([("traceparent".to_string(), traceparent.to_string())]);
+    if let Some(tracestate) = tracestate {
+        headers.insert("tracestate".to_string(), tracestate.to_string());
+    }
+    let context = TraceContextPropagator::new().extract(&headers);
+    span.set_parent(context).ok();
+}
+
+/// Get the "traceparent" header value of the current span (None unless it's exported)
+pub fn traceparent() -> Option<String> {
+    EXPORTER.get()?;
+    let mut headers = HashMap::new();
+    TraceContextPropagator::new().inject_context(&tracing::Span::current().context(), &mut headers);
+    headers.remove("traceparent")
+}
+
+/// Record the duration of the request by the route and the status
+pub fn record(route: Option<&str>, method: &str, status: u16, duration: Duration) {
+    let Some(exporter) = EXPORTER.get() else {
+        return;
+    };
+    let mut attributes = vec![
+        KeyValue::new("http.request.method", method.to_string()),
+        KeyValue::new("http.response.status_code", status as i64),
+    ];
+    if let Some(route) = route {
+        attributes.push(KeyValue::new("http.route", route.to_string()));
+    }
+    exporter
+        .duration
+        .record(duration.as_secs_f64(), &attributes);
+}
+
+/// Send the spans and the metrics not exported yet
+pub fn shutdown() {
+    if let Some(exporter) = EXPORTER.get() {
+        exporter.tracer.shutdown().ok();
+        exporter.meter.shutdown().ok();
+    }
+}
+
+/// Register the commands of the telemetry
+pub fn register_commands(commands: &mut Commands) {
+    // Get the trace context to pass to the services called by the handler
+    commands.add("traceparent", |executor| {
+        executor
+            .stack
+            .push(traceparent().map_or(Type::Null, |x| Type::String(x.into())));
+        Ok(())
+    });
+}
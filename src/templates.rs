@@ -0,0 +1,200 @@
+use crate::{i18n, Executor, Type};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::SystemTime;
+use tera::{Context, Filter, Tera, Value};
+
+/// Template engine that caches templates loaded from the directory.
+/// The templates are rendered out of the lock, since the Stack filters may render others
+struct Engine {
+    tera: Arc<Tera>,                       // Parsed templates
+    reload: bool,                          // Re-read templates when the files are changed
+    dir: Option<String>,                   // Directory of the templates
+    loaded: Option<SystemTime>,            // Time when the templates are loaded
+    raw: HashMap<String, String>,          // Templates added by the code (kept over reloading)
+    filters: HashMap<String, StackFilter>, // Filters defined in Stack code
+    inline: HashMap<String, String>,       // Names of the parsed template strings by the source
+    parsed: usize,                         // Count of the template strings parsed so far
+}
+
+/// Count of template strings to keep parsed
+const INLINE_CACHE: usize = 256;
+
+/// Template filter whose implementation is Stack code
+#[derive(Clone)]
+struct StackFilter {
+    code: String,       // Code that converts the value on the stack
+    executor: Executor, // Executor that defined the filter
+}
+
+impl Filter for StackFilter {
+    fn filter(&self, value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+        let mut executor = self.executor.clone();
+        executor.stack.push(Type::from_json(value));
+        executor.evaluate_program(self.code.clone());
+        match executor.stack.pop() {
+            Some(Type::Error(err)) => Err(tera::Error::msg(format!("filter failed: {err}"))),
+            Some(result) => Ok(result.to_json()),
+            None => Ok(Value::Null),
+        }
+    }
+}
+
+static ENGINE: LazyLock<RwLock<Engine>> = LazyLock::new(|| {
+    RwLock::new(Engine {
+        tera: Arc::new(prepare(Tera::default())),
+        reload: false,
+        dir: None,
+        loaded: None,
+        raw: HashMap::new(),
+        filters: HashMap::new(),
+        inline: HashMap::new(),
+        parsed: 0,
+    })
+});
+
+/// Escape HTML of values in every template (use `safe` filter to output raw value),
+/// and add the translation function `t`
+fn prepare(mut tera: Tera) -> Tera {
+    tera.autoescape_on(vec![""]);
+    tera.register_function("t", i18n::function);
+    tera
+}
+
+/// Get the latest modified time of files in the directory
+fn modified(path: &Path) -> Option<SystemTime> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return metadata.modified().ok();
+    }
+    fs::read_dir(path)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| modified(&entry.path()))
+        .max()
+}
+
+/// Show the template error with its root cause
+fn error(e: tera::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(&e);
+    while let Some(cause) = source {
+        message = format!("{message}: {cause}");
+        source = cause.source();
+    }
+    message
+}
+
+/// Convert the object into the template context
+pub fn context(object: HashMap<String, Type>) -> Context {
+    let mut context = Context::new();
+    for (key, value) in object {
+        context.insert(key, &value.to_json())
+    }
+    context
+}
+
+/// Load and cache the templates in the directory
+pub fn load_dir(dir: &str, reload: bool) -> Result<(), String> {
+    let mut tera = prepare(
+        Tera::new(&format!("{}/**/*", dir.trim_end_matches('/'))).map_err(|e| e.to_string())?,
+    );
+    let mut engine = ENGINE.write().unwrap_or_else(|e| e.into_inner());
+    tera.add_raw_templates(engine.raw.clone()).map_err(error)?;
+    tera.add_raw_templates(engine.inline.iter().map(|(source, name)| (name, source)))
+        .map_err(error)?;
+    for (name, filter) in &engine.filters {
+        tera.register_filter(name, filter.clone());
+    }
+    engine.tera = Arc::new(tera);
+    engine.reload = reload;
+    engine.dir = Some(dir.to_string());
+    engine.loaded = Some(SystemTime::now());
+    Ok(())
+}
+
+/// Re-read the templates in debug mode if the files are changed
+fn refresh() -> Result<(), String> {
+    let changed = {
+        let engine = ENGINE.read().unwrap_or_else(|e| e.into_inner());
+        match (&engine.dir, engine.loaded) {
+            (Some(dir), Some(loaded)) if engine.reload => {
+                modified(Path::new(dir)).is_some_and(|time| time > loaded)
+            }
+            _ => false,
+        }
+    };
+    if changed {
+        let mut engine = ENGINE.write().unwrap_or_else(|e| e.into_inner());
+        let mut tera = Tera::clone(&engine.tera);
+        tera.full_reload().map_err(error)?;
+        tera.add_raw_templates(engine.raw.clone()).map_err(error)?;
+        tera.add_raw_templates(engine.inline.iter().map(|(source, name)| (name, source)))
+            .map_err(error)?;
+        engine.tera = Arc::new(tera);
+        engine.loaded = Some(SystemTime::now());
+    }
+    Ok(())
+}
+
+/// Render the cached template by the name
+pub fn render_file(name: &str, context: &Context) -> Result<String, String> {
+    refresh()?;
+    let tera = ENGINE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .tera
+        .clone();
+    tera.render(name, context).map_err(error)
+}
+
+/// Register the template by the name, so that others can extend or include it
+pub fn add(name: &str, source: &str) -> Result<(), String> {
+    let mut engine = ENGINE.write().unwrap_or_else(|e| e.into_inner());
+    Arc::make_mut(&mut engine.tera)
+        .add_raw_template(name, source)
+        .map_err(error)?;
+    engine.raw.insert(name.to_string(), source.to_string());
+    Ok(())
+}
+
+/// Render the template string, which can extend or include registered templates
+pub fn render_str(source: &str, context: &Context) -> Result<String, String> {
+    refresh()?;
+
+    // Render the string parsed before without locking other renderings
+    let parsed = {
+        let engine = ENGINE.read().unwrap_or_else(|e| e.into_inner());
+        engine
+            .inline
+            .get(source)
+            .map(|name| (name.clone(), engine.tera.clone()))
+    };
+    if let Some((name, tera)) = parsed {
+        return tera.render(&name, context).map_err(error);
+    }
+
+    // Replace the oldest one by the name if the cache is full
+    let mut engine = ENGINE.write().unwrap_or_else(|e| e.into_inner());
+    let name = format!("__inline_{}", engine.parsed % INLINE_CACHE);
+    Arc::make_mut(&mut engine.tera)
+        .add_raw_template(&name, source)
+        .map_err(error)?;
+    engine.inline.retain(|_, x| *x != name);
+    engine.inline.insert(source.to_string(), name.clone());
+    engine.parsed += 1;
+    let tera = engine.tera.clone();
+    drop(engine);
+    tera.render(&name, context).map_err(error)
+}
+
+/// Register the filter whose implementation is the code
+pub fn add_filter(name: &str, code: String, mut executor: Executor) {
+    executor.stack.clear();
+    let filter = StackFilter { code, executor };
+    let mut engine = ENGINE.write().unwrap_or_else(|e| e.into_inner());
+    Arc::make_mut(&mut engine.tera).register_filter(name, filter.clone());
+    engine.filters.insert(name.to_string(), filter);
+}
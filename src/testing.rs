@@ -0,0 +1,85 @@
+use crate::{cleanup_temporaries, get_file_contents, Executor, Mode, Type};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory of the tests when no path is given
+const DEFAULT_DIR: &str = "tests";
+
+/// Whether the file is a test program
+fn is_test(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|x| x.to_string_lossy())
+        .unwrap_or_default();
+    name.ends_with(".test.stk") || name.ends_with("_test.stk")
+}
+
+/// Collect the test programs in the directory recursively
+fn collect(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let mut entries: Vec<PathBuf> = fs::read_dir(path)
+        .map_err(|e| format!("{}: {e}", path.display()))?
+        .flatten()
+        .map(|x| x.path())
+        .collect();
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            collect(&entry, files)?;
+        } else if is_test(&entry) {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
+
+/// Run each test program, and return whether all of them passed
+/// (a test fails when the error values are left on the stack)
+pub fn run(paths: &[&str], mode: Mode) -> Result<bool, String> {
+    let paths = if paths.is_empty() {
+        vec![DEFAULT_DIR]
+    } else {
+        paths.to_vec()
+    };
+    let mut files = Vec::new();
+    for path in paths {
+        collect(Path::new(path), &mut files)?;
+    }
+
+    let mut failed = Vec::new();
+    for file in &files {
+        let code = get_file_contents(file).map_err(|e| format!("{}: {e}", file.display()))?;
+        let mut executor = Executor::new(mode);
+        executor.evaluate_program(code);
+        cleanup_temporaries();
+
+        let errors: Vec<String> = executor
+            .stack
+            .iter()
+            .filter_map(|x| match x {
+                Type::Error(err) => Some(err.to_string()),
+                _ => None,
+            })
+            .collect();
+        if errors.is_empty() {
+            println!("test {} ... ok", file.display());
+        } else {
+            println!("test {} ... FAILED", file.display());
+            for err in &errors {
+                println!("    error:{err}");
+            }
+            failed.push(file);
+        }
+    }
+
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        if failed.is_empty() { "ok" } else { "FAILED" },
+        files.len() - failed.len(),
+        failed.len()
+    );
+    Ok(failed.is_empty())
+}
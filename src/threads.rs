@@ -0,0 +1,114 @@
+use crate::{Executor, Type};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Cancellation state of the thread
+struct Control {
+    cancelled: AtomicBool,        // Whether the thread is killed
+    deadline: Option<Instant>,    // Time when the thread is timed out
+    parent: Option<Arc<Control>>, // Control of the enclosing evaluation, which stops this too
+}
+
+/// Running threads and the last identifier
+type Threads = (usize, HashMap<usize, Arc<Control>>);
+
+static THREADS: LazyLock<Mutex<Threads>> = LazyLock::new(|| Mutex::new((0, HashMap::new())));
+
+thread_local! {
+    /// Cancellation state of the current thread
+    static CURRENT: RefCell<Option<Arc<Control>>> = const { RefCell::new(None) };
+}
+
+/// Lock the thread registry
+fn threads() -> MutexGuard<'static, Threads> {
+    THREADS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Control {
+    /// Whether the thread should stop
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            || self.parent.as_ref().is_some_and(|x| x.is_cancelled())
+    }
+}
+
+/// Get the time after the seconds, or None if it's not finite or too far to be reached
+fn deadline(seconds: f64) -> Option<Instant> {
+    let duration = Duration::try_from_secs_f64(seconds.max(0.0)).ok()?;
+    Instant::now().checked_add(duration)
+}
+
+/// Start the code on a thread that can be killed, and return its handle object
+pub fn spawn(code: String, mut executor: Executor, timeout: Option<f64>) -> Type {
+    let control = Arc::new(Control {
+        cancelled: AtomicBool::new(false),
+        deadline: timeout.and_then(deadline),
+        parent: None,
+    });
+
+    let id = {
+        let mut threads = threads();
+        threads.0 += 1;
+        let id = threads.0;
+        threads.1.insert(id, control.clone());
+        id
+    };
+
+    thread::spawn(move || {
+        CURRENT.with(|current| *current.borrow_mut() = Some(control));
+        executor.evaluate_program(code);
+        threads().1.remove(&id);
+    });
+
+    Type::Object(
+        "thread".to_string(),
+        HashMap::from([("id".to_string(), Type::Number(id as f64))]),
+    )
+}
+
+/// Request the thread to stop, and return whether it was running
+pub fn kill(handle: &Type) -> bool {
+    let (_, object) = handle.get_object();
+    let Some(id) = object.get("id").map(|x| x.get_number() as usize) else {
+        return false;
+    };
+    match threads().1.get(&id) {
+        Some(control) => {
+            control.cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Whether the current thread is killed or timed out (checked between tokens)
+pub fn is_cancelled() -> bool {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(|control| control.is_cancelled())
+    })
+}
+
+/// Run the function cancelling the evaluation in it after the timeout in seconds
+/// (or when the enclosing thread is killed), and return its result with whether it's timed out
+pub fn with_timeout<T>(timeout: f64, function: impl FnOnce() -> T) -> (T, bool) {
+    let parent = CURRENT.with(|current| current.borrow().clone());
+    let control = Arc::new(Control {
+        cancelled: AtomicBool::new(false),
+        deadline: deadline(timeout),
+        parent,
+    });
+    let previous = CURRENT.with(|current| current.replace(Some(control.clone())));
+    let result = function();
+    CURRENT.with(|current| *current.borrow_mut() = previous);
+    (result, control.is_cancelled())
+}
@@ -0,0 +1,105 @@
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{self, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    ClientConfig, ClientConnection, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use std::net::TcpStream;
+use std::sync::{Arc, LazyLock};
+
+/// Stream encrypted by TLS
+pub type TlsStream = rustls::StreamOwned<ClientConnection, TcpStream>;
+
+/// Certificates of the system's root authorities
+static ROOTS: LazyLock<Arc<RootCertStore>> = LazyLock::new(|| {
+    let mut roots = RootCertStore::empty();
+    let certificates = rustls_native_certs::load_native_certs();
+    for error in &certificates.errors {
+        println!("Error! failed to load root certificate: {error}");
+    }
+    roots.add_parsable_certificates(certificates.certs);
+    Arc::new(roots)
+});
+
+/// Verifier that accepts any certificate (for self-signed development servers)
+#[derive(Debug)]
+struct Insecure(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for Insecure {
+    fn verify_server_cert(
+        &self,
+        _: &CertificateDer<'_>,
+        _: &[CertificateDer<'_>],
+        _: &ServerName<'_>,
+        _: &[u8],
+        _: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Make client configuration verifying by the system's roots, or accepting any if insecure
+pub fn config(insecure: bool) -> Result<Arc<ClientConfig>, String> {
+    let provider = Arc::new(crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?;
+    let config = if insecure {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(Insecure(provider)))
+            .with_no_client_auth()
+    } else {
+        builder
+            .with_root_certificates(ROOTS.clone())
+            .with_no_client_auth()
+    };
+    Ok(Arc::new(config))
+}
+
+/// Start TLS on the stream, sending the server name by SNI
+pub fn connect(stream: TcpStream, server_name: &str, insecure: bool) -> Result<TlsStream, String> {
+    let name = ServerName::try_from(server_name.to_string()).map_err(|e| e.to_string())?;
+    let connection = ClientConnection::new(config(insecure)?, name).map_err(|e| e.to_string())?;
+    let mut stream = rustls::StreamOwned::new(connection, stream);
+    // Finish the handshake now to report the certificate error
+    while stream.conn.is_handshaking() {
+        stream
+            .conn
+            .complete_io(&mut stream.sock)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(stream)
+}
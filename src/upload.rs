@@ -0,0 +1,248 @@
+use crate::{header_value, Type};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+/// Version of the tus protocol
+const TUS_VERSION: &str = "1.0.0";
+
+/// Extensions of the tus protocol supported
+const TUS_EXTENSIONS: &str = "creation,termination";
+
+/// Limit of the size of each upload unless the route sets it (1 GiB)
+const DEFAULT_MAX_SIZE: u64 = 1 << 30;
+
+/// Time after the last chunk when the upload left unfinished is removed
+const EXPIRY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Uploads being written by PATCH, not to be written by another at once
+static BUSY: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Resumable uploads by the tus protocol under the path of the route
+pub struct Upload {
+    pub mount: String,         // Path of the route like "/uploads"
+    pub dir: String,           // Directory of the uploads in progress
+    pub max_size: Option<u64>, // Limit of the size of each upload
+}
+
+/// What to do after the request to the upload
+pub enum Action {
+    Respond(String),     // Respond as it is
+    Complete(Type, u64), // Hand the completed upload to the handler, and then respond 204
+}
+
+impl Upload {
+    /// Handle the tus request: OPTIONS to discover, POST to create, HEAD to get the offset,
+    /// PATCH to append the chunk and DELETE to terminate the upload
+    pub fn respond(&self, method: &str, path: &str, head: &str, body: &[u8]) -> Action {
+        if method == "OPTIONS" {
+            return Action::Respond(format!(
+                "HTTP/1.1 204 No Content\r\nTus-Resumable: {TUS_VERSION}\r\nTus-Version: {TUS_VERSION}\r\nTus-Extension: {TUS_EXTENSIONS}\r\nTus-Max-Size: {}\r\n\r\n",
+                self.max_size()
+            ));
+        }
+        if header_value(head, "Tus-Resumable") != Some(TUS_VERSION) {
+            return Action::Respond(format!(
+                "HTTP/1.1 412 Precondition Failed\r\nTus-Version: {TUS_VERSION}\r\n\r\n"
+            ));
+        }
+
+        let id = path
+            .strip_prefix(self.mount.trim_end_matches('/'))
+            .unwrap_or_default()
+            .trim_matches('/');
+        match (method, id) {
+            ("POST", "") => self.create(head),
+            (_, "") => status("405 Method Not Allowed"),
+            // The identifier is made of the hexadecimal digits only
+            (_, id) if !id.chars().all(|x| x.is_ascii_hexdigit()) => status("404 Not Found"),
+            ("HEAD", id) => match self.info(id) {
+                Some(info) => Action::Respond(format!(
+                    "HTTP/1.1 200 OK\r\nTus-Resumable: {TUS_VERSION}\r\nUpload-Offset: {}\r\nUpload-Length: {}\r\nCache-Control: no-store\r\n\r\n",
+                    self.offset(id),
+                    info["length"]
+                )),
+                None => status("404 Not Found"),
+            },
+            ("PATCH", id) => self.append(id, head, body),
+            ("DELETE", id) => {
+                if self.info(id).is_none() {
+                    return status("404 Not Found");
+                }
+                fs::remove_file(self.data_path(id)).ok();
+                fs::remove_file(self.info_path(id)).ok();
+                status("204 No Content")
+            }
+            _ => status("405 Method Not Allowed"),
+        }
+    }
+
+    /// Create the upload by its length and metadata, and give its location
+    fn create(&self, head: &str) -> Action {
+        let Some(length) = header_value(head, "Upload-Length").and_then(|x| x.parse::<u64>().ok())
+        else {
+            return status("400 Bad Request");
+        };
+        if length > self.max_size() {
+            return status("413 Payload Too Large");
+        }
+        self.sweep();
+
+        // Metadata like "filename ZmlsZS50eHQ=,type dGV4dC9wbGFpbg==" decoded by the key
+        let metadata: HashMap<String, String> = header_value(head, "Upload-Metadata")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|pair| {
+                let mut pair = pair.split_whitespace();
+                let key = pair.next()?.to_string();
+                let value = STANDARD.decode(pair.next().unwrap_or_default()).ok()?;
+                Some((key, String::from_utf8_lossy(&value).to_string()))
+            })
+            .collect();
+
+        let id = format!("{:032x}", rand::random::<u128>());
+        let info = json!({"length": length, "metadata": metadata}).to_string();
+        let created = fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(self.info_path(&id), info))
+            .and_then(|_| fs::write(self.data_path(&id), []));
+        if let Err(e) = created {
+            println!("Error! upload \"{id}\": {e}");
+            return status("500 Internal Server Error");
+        }
+        Action::Respond(format!(
+            "HTTP/1.1 201 Created\r\nTus-Resumable: {TUS_VERSION}\r\nLocation: {}/{id}\r\nUpload-Offset: 0\r\n\r\n",
+            self.mount.trim_end_matches('/')
+        ))
+    }
+
+    /// Append the chunk at the offset, and complete the upload when it's all received
+    fn append(&self, id: &str, head: &str, body: &[u8]) -> Action {
+        if header_value(head, "Content-Type") != Some("application/offset+octet-stream") {
+            return status("415 Unsupported Media Type");
+        }
+        let Some(info) = self.info(id) else {
+            return status("404 Not Found");
+        };
+        if !BUSY
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id.to_string())
+        {
+            return status("423 Locked");
+        }
+        let length = info["length"].as_u64().unwrap_or_default();
+        let offset = self.offset(id);
+        let written =
+            if header_value(head, "Upload-Offset").and_then(|x| x.parse().ok()) != Some(offset) {
+                Err(status("409 Conflict"))
+            } else if offset + body.len() as u64 > length {
+                Err(status("400 Bad Request"))
+            } else {
+                OpenOptions::new()
+                    .append(true)
+                    .open(self.data_path(id))
+                    .and_then(|mut file| file.write_all(body))
+                    .map_err(|e| {
+                        println!("Error! upload \"{id}\": {e}");
+                        status("500 Internal Server Error")
+                    })
+            };
+        BUSY.lock().unwrap_or_else(|e| e.into_inner()).remove(id);
+        if let Err(response) = written {
+            return response;
+        }
+
+        let offset = offset + body.len() as u64;
+        if offset < length {
+            return Action::Respond(format!(
+                "HTTP/1.1 204 No Content\r\nTus-Resumable: {TUS_VERSION}\r\nUpload-Offset: {offset}\r\n\r\n"
+            ));
+        }
+
+        // Hand the completed file over to the handler, forgetting it as an upload
+        fs::remove_file(self.info_path(id)).ok();
+        let upload = Type::Object(
+            "upload".to_string(),
+            HashMap::from([
+                ("id".to_string(), Type::String(id.into())),
+                (
+                    "path".to_string(),
+                    Type::String(self.data_path(id).to_string_lossy().as_ref().into()),
+                ),
+                ("size".to_string(), Type::Number(length as f64)),
+                ("metadata".to_string(), Type::from_json(&info["metadata"])),
+            ]),
+        );
+        Action::Complete(upload, length)
+    }
+
+    /// Get the limit of the size of each upload
+    fn max_size(&self) -> u64 {
+        self.max_size.unwrap_or(DEFAULT_MAX_SIZE)
+    }
+
+    /// Remove the uploads left unfinished longer than the expiry since their last chunk
+    fn sweep(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let busy = BUSY.lock().unwrap_or_else(|e| e.into_inner());
+        for path in entries.flatten().map(|x| x.path()) {
+            // The uploads in progress have the info, unlike the completed ones
+            let id = match (path.extension(), path.file_stem()) {
+                (Some(extension), Some(id)) if extension == "json" => id.to_string_lossy(),
+                _ => continue,
+            };
+            let modified = fs::metadata(self.data_path(&id))
+                .or_else(|_| fs::metadata(&path))
+                .and_then(|x| x.modified());
+            let stale = modified
+                .ok()
+                .and_then(|x| x.elapsed().ok())
+                .is_some_and(|x| x > EXPIRY);
+            if stale && !busy.contains(id.as_ref()) {
+                fs::remove_file(self.data_path(&id)).ok();
+                fs::remove_file(&path).ok();
+            }
+        }
+    }
+
+    /// Get the length and the metadata of the upload in progress
+    fn info(&self, id: &str) -> Option<Value> {
+        serde_json::from_str(&fs::read_to_string(self.info_path(id)).ok()?).ok()
+    }
+
+    /// Get the size received so far
+    fn offset(&self, id: &str) -> u64 {
+        fs::metadata(self.data_path(id)).map_or(0, |x| x.len())
+    }
+
+    /// Get the path of the data received
+    fn data_path(&self, id: &str) -> PathBuf {
+        PathBuf::from(&self.dir).join(id)
+    }
+
+    /// Get the path of the length and the metadata
+    fn info_path(&self, id: &str) -> PathBuf {
+        PathBuf::from(&self.dir).join(format!("{id}.json"))
+    }
+}
+
+/// Respond the status with the tus version
+fn status(status: &str) -> Action {
+    Action::Respond(format!(
+        "HTTP/1.1 {status}\r\nTus-Resumable: {TUS_VERSION}\r\n\r\n"
+    ))
+}
+
+/// Response after the completed upload is handled
+pub fn completed(size: u64) -> String {
+    format!(
+        "HTTP/1.1 204 No Content\r\nTus-Resumable: {TUS_VERSION}\r\nUpload-Offset: {size}\r\n\r\n"
+    )
+}
@@ -0,0 +1,192 @@
+use crate::commands::register_command;
+use crate::{StackError, Type};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use wasmtime::{
+    Caller, Config, Engine, ExternType, Instance, InstancePre, Linker, Memory, Module, Store,
+    StoreLimits, StoreLimitsBuilder, Trap,
+};
+
+/// Namespace of the host functions imported by the module
+const HOST: &str = "stack";
+
+/// Fuel given to each call of the command, roughly the number of the instructions it can run
+const FUEL: u64 = 1_000_000_000;
+
+/// Size of the memory the instance can grow up to
+const MAX_MEMORY: usize = 64 << 20;
+
+/// State shared with the module while its command runs
+#[derive(Default)]
+struct Host {
+    stack: Vec<Type>,      // Stack of the executor running the command
+    buffer: Vec<u8>,       // JSON of the popped value waiting to be read
+    error: Option<String>, // Error reported by the command
+    limits: StoreLimits,   // Limits of the memory and the tables of the instance
+}
+
+/// Module with its instances kept over the commands to hold their state
+struct Plugin {
+    module: InstancePre<Host>,
+    idle: Mutex<Vec<(Store<Host>, Instance)>>, // Instances not running any command
+}
+
+impl Plugin {
+    /// Make the new instance of the module with the limits
+    fn instantiate(&self) -> wasmtime::Result<(Store<Host>, Instance)> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY)
+            .instances(1)
+            .build();
+        let host = Host {
+            limits,
+            ..Host::default()
+        };
+        let mut store = Store::new(self.module.module().engine(), host);
+        store.limiter(|host| &mut host.limits);
+        let instance = self.module.instantiate(&mut store)?;
+        Ok((store, instance))
+    }
+}
+
+/// Get the exported memory of the module
+fn memory(caller: &mut Caller<'_, Host>) -> wasmtime::Result<Memory> {
+    caller
+        .get_export("memory")
+        .and_then(|x| x.into_memory())
+        .ok_or(wasmtime::Error::msg("memory is not exported"))
+}
+
+/// Read the string in the memory of the module
+fn read_string(caller: &mut Caller<'_, Host>, ptr: i32, len: i32) -> wasmtime::Result<String> {
+    let mut buffer = vec![0; len.max(0) as usize];
+    memory(caller)?.read(&*caller, ptr as usize, &mut buffer)?;
+    Ok(String::from_utf8_lossy(&buffer).to_string())
+}
+
+/// Define the host interface to operate the stack:
+/// `pop() -> len` takes the top value as JSON, `read(ptr)` copies it into the memory,
+/// `push(ptr, len)` pushes the value of JSON, `error(ptr, len)` fails the command,
+/// and `depth() -> len` gets the number of the values
+fn host_interface(engine: &Engine) -> wasmtime::Result<Linker<Host>> {
+    let mut linker = Linker::new(engine);
+    linker.func_wrap(HOST, "pop", |mut caller: Caller<'_, Host>| -> i32 {
+        let host = caller.data_mut();
+        let value = host.stack.pop().unwrap_or(Type::Null);
+        host.buffer = value.to_json().to_string().into_bytes();
+        host.buffer.len() as i32
+    })?;
+    linker.func_wrap(
+        HOST,
+        "read",
+        |mut caller: Caller<'_, Host>, ptr: i32| -> wasmtime::Result<()> {
+            let buffer = std::mem::take(&mut caller.data_mut().buffer);
+            memory(&mut caller)?.write(&mut caller, ptr as usize, &buffer)?;
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST,
+        "push",
+        |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> wasmtime::Result<()> {
+            let text = read_string(&mut caller, ptr, len)?;
+            let value = serde_json::from_str::<Value>(&text).unwrap_or(Value::String(text));
+            caller.data_mut().stack.push(Type::from_json(&value));
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST,
+        "error",
+        |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> wasmtime::Result<()> {
+            let message = read_string(&mut caller, ptr, len)?;
+            caller.data_mut().error = Some(message);
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(HOST, "depth", |caller: Caller<'_, Host>| -> i32 {
+        caller.data().stack.len() as i32
+    })?;
+    Ok(linker)
+}
+
+/// Load the module, and register its exported functions without parameters and results
+/// as the commands. The module can't access anything other than the stack, and each call
+/// is bounded by the fuel and the memory limit. Concurrent calls run on the separate instances
+/// instead of waiting for each other, so the state of the module is kept by the instance
+pub fn load(path: &str) -> Result<Vec<String>, String> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config).map_err(|e| e.to_string())?;
+    let module = Module::from_file(&engine, path).map_err(|e| format!("{path}: {e}"))?;
+    let linker = host_interface(&engine).map_err(|e| e.to_string())?;
+    let module = linker
+        .instantiate_pre(&module)
+        .map_err(|e| format!("{path}: {e}"))?;
+
+    let names: Vec<String> = module
+        .module()
+        .exports()
+        .filter(|export| match export.ty() {
+            ExternType::Func(func) => func.params().len() == 0 && func.results().len() == 0,
+            _ => false,
+        })
+        .map(|export| export.name().to_string())
+        .filter(|name| !matches!(name.as_str(), "_start" | "_initialize"))
+        .collect();
+
+    // Instantiate it once on loading so that the module failing to start is reported here
+    let plugin = Arc::new(Plugin {
+        module,
+        idle: Mutex::new(Vec::new()),
+    });
+    let instance = plugin.instantiate().map_err(|e| format!("{path}: {e}"))?;
+    plugin
+        .idle
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(instance);
+
+    for name in &names {
+        let (plugin, export) = (plugin.clone(), name.clone());
+        register_command(name, move |executor| {
+            let idle = plugin.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+            let (mut store, instance) = match idle {
+                Some(instance) => instance,
+                None => plugin
+                    .instantiate()
+                    .map_err(|e| StackError::new("wasm", format!("{export}: {e}")))?,
+            };
+            let function = instance
+                .get_typed_func::<(), ()>(&mut store, &export)
+                .map_err(|e| StackError::new("wasm", e))?;
+            store
+                .set_fuel(FUEL)
+                .map_err(|e| StackError::new("wasm", e))?;
+
+            // Lend the stack to the module while the command runs
+            store.data_mut().stack = std::mem::take(&mut executor.stack);
+            let result = function.call(&mut store, ());
+            let host = store.data_mut();
+            executor.stack = std::mem::take(&mut host.stack);
+            host.buffer.clear();
+            let error = host.error.take();
+
+            // The instance trapped halfway may be broken, so it's dropped
+            if result.is_ok() {
+                let mut idle = plugin.idle.lock().unwrap_or_else(|e| e.into_inner());
+                idle.push((store, instance));
+            }
+            result.map_err(|e| match e.downcast_ref::<Trap>() {
+                Some(trap) => StackError::new("wasm", format!("{export}: {trap}")),
+                None => StackError::new("wasm", format!("{export}: {e}")),
+            })?;
+            match error {
+                Some(message) => Err(StackError::new("wasm", message)),
+                None => Ok(()),
+            }
+        })
+        .map_err(|e| format!("{path}: {}", e.message))?;
+    }
+    Ok(names)
+}
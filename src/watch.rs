@@ -0,0 +1,102 @@
+use crate::livereload;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Time to wait for the following changes of a saving
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Arguments of this process except the options of the watch mode
+fn arguments() -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut skip = false;
+    let mut rest = false;
+    for argument in env::args().skip(1) {
+        if skip {
+            skip = false;
+        } else if rest || argument == "--" {
+            // Pass the arguments of the program as they are
+            rest = true;
+            arguments.push(argument);
+        } else if argument == "--watch-path" {
+            skip = true;
+        } else if argument != "--watch" && !argument.starts_with("--watch-path=") {
+            arguments.push(argument);
+        }
+    }
+    arguments
+}
+
+/// Run this process without the watch mode, and restart it whenever the paths change
+pub fn run(paths: &[String]) -> Result<(), String> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(|e| e.to_string())?;
+
+    // Watch directory of the file to follow the replacing by editors
+    let mut targets: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        let path = Path::new(path)
+            .canonicalize()
+            .map_err(|e| format!("{path}: {e}"))?;
+        let (directory, mode) = if path.is_dir() {
+            (path.clone(), RecursiveMode::Recursive)
+        } else {
+            let parent = path.parent().unwrap_or(Path::new("/")).to_path_buf();
+            (parent, RecursiveMode::NonRecursive)
+        };
+        watcher
+            .watch(&directory, mode)
+            .map_err(|e| format!("{}: {e}", directory.display()))?;
+        targets.push(path);
+    }
+
+    let program = env::current_exe().map_err(|e| e.to_string())?;
+    let arguments = arguments();
+    loop {
+        // Let the pages served by the program reload when it's restarted
+        let mut child = Command::new(&program)
+            .args(&arguments)
+            .env(livereload::ENV, "1")
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        println!(
+            "Watching {} for changes.",
+            targets
+                .iter()
+                .map(|x| x.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+
+        // Wait the change of the watched paths
+        loop {
+            let Ok(event) = receiver.recv() else {
+                child.kill().ok();
+                return Ok(());
+            };
+            let Ok(event) = event else {
+                continue;
+            };
+            let changed = matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) && event
+                .paths
+                .iter()
+                .any(|path| targets.iter().any(|target| path.starts_with(target)));
+            if changed {
+                break;
+            }
+        }
+        thread::sleep(DEBOUNCE);
+        while receiver.try_recv().is_ok() {}
+
+        println!("Change is detected. Restarting...");
+        child.kill().ok();
+        child.wait().ok();
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{proxy, tls, StackError, Type};
+use serde_json::json;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::LazyLock;
+use std::thread;
+use std::time::Duration;
+use tungstenite::http::Uri;
+
+/// Number of the values at the top of the stack sent with the error
+const STACK_LIMIT: usize = 10;
+
+/// Length of the value shown in the stack state
+const VALUE_LIMIT: usize = 200;
+
+/// Count of the reports waiting to be sent (more are dropped)
+const QUEUE_SIZE: usize = 64;
+
+/// Time to wait for connecting, reading and writing the webhook
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Worker sending the reports one by one, so that a slow webhook can't pile up the threads
+static WORKER: LazyLock<SyncSender<(String, String)>> = LazyLock::new(|| {
+    let (sender, receiver) = mpsc::sync_channel::<(String, String)>(QUEUE_SIZE);
+    thread::spawn(move || {
+        for (url, body) in receiver {
+            match post(&url, &body) {
+                Ok(status) if status < 300 => {}
+                Ok(status) => println!("Error! webhook responded with status {status}"),
+                Err(e) => println!("Error! webhook: {e}"),
+            }
+        }
+    });
+    sender
+});
+
+/// POST the JSON body to the URL, and return the status code of the response
+pub fn post(url: &str, body: &str) -> Result<u16, String> {
+    let uri: Uri = url
+        .parse()
+        .map_err(|e| format!("invalid URL \"{url}\": {e}"))?;
+    let secure = uri.scheme_str() == Some("https");
+    let host = uri.host().ok_or(format!("host is not in \"{url}\""))?;
+    let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+    let target = uri.path_and_query().map(|x| x.as_str()).unwrap_or("/");
+    let authority = uri.authority().map(|x| x.as_str()).unwrap_or(host);
+
+    let request = format!(
+        "POST {target} HTTP/1.1\r\nHost: {authority}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let proxy = proxy::resolve(host, secure, None);
+    let stream = proxy::connect_timeout(host, port, proxy, Some(TIMEOUT))?;
+    let mut stream: Box<dyn ReadWrite> = match secure {
+        true => Box::new(tls::connect(stream, host, false)?),
+        false => Box::new(stream),
+    };
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut status = String::new();
+    BufReader::new(stream)
+        .read_line(&mut status)
+        .map_err(|e| e.to_string())?;
+    status
+        .split_whitespace()
+        .nth(1)
+        .and_then(|x| x.parse().ok())
+        .ok_or(format!("invalid response \"{}\"", status.trim()))
+}
+
+/// Stream that can be read and written
+trait ReadWrite: Read + Write {}
+
+impl<T: Read + Write> ReadWrite for T {}
+
+/// Report the error of the handler to the webhook in the background.
+/// The body has "text" and "content" so that Slack and Discord can show it
+pub fn report(url: &str, err: &StackError, route: &str, request_id: &str, stack: &[Type]) {
+    let stack: Vec<String> = stack
+        .iter()
+        .rev()
+        .take(STACK_LIMIT)
+        .map(|x| x.display().chars().take(VALUE_LIMIT).collect())
+        .collect();
+    let summary = format!("Error in \"{route}\" (request {request_id}): {err}");
+    let body = json!({
+        "text": summary,
+        "content": summary,
+        "error": err.name,
+        "message": err.message,
+        "route": route,
+        "request_id": request_id,
+        "stack": stack,
+    })
+    .to_string();
+
+    if WORKER.try_send((url.to_string(), body)).is_err() {
+        println!("Error! webhook: too many reports are waiting, so the report is dropped");
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{proxy, tls, Type};
+use std::collections::HashMap;
+use std::net::TcpStream;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Connector, Message, WebSocket};
+
+/// Client socket of WebSocket
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+/// Opened sockets and the last identifier
+type Sockets = (usize, HashMap<usize, Arc<Mutex<Socket>>>);
+
+static SOCKETS: LazyLock<Mutex<Sockets>> = LazyLock::new(|| Mutex::new((0, HashMap::new())));
+
+/// Lock the socket registry
+fn sockets() -> MutexGuard<'static, Sockets> {
+    SOCKETS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Get identifier of the handle object
+fn id(handle: &Type) -> Result<usize, String> {
+    let (_, object) = handle.get_object();
+    object
+        .get("id")
+        .map(|x| x.get_number() as usize)
+        .ok_or("the value is not a websocket".to_string())
+}
+
+/// Get socket of the handle object
+fn socket(handle: &Type) -> Result<Arc<Mutex<Socket>>, String> {
+    let id = id(handle)?;
+    sockets()
+        .1
+        .get(&id)
+        .cloned()
+        .ok_or(format!("websocket {id} is closed"))
+}
+
+/// Connect to the server by URL or options object, and return handle object of the socket
+pub fn connect(option: &Type) -> Result<Type, String> {
+    let (url, insecure, proxy) = if let Type::Object(_, value) = option {
+        (
+            value.get("url").map(|x| x.get_string()).unwrap_or_default(),
+            value.get("insecure").map(|x| x.get_bool()).unwrap_or(false),
+            value.get("proxy").map(|x| x.get_string()),
+        )
+    } else {
+        (option.get_string(), false, None)
+    };
+
+    let request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| e.to_string())?;
+    let secure = request.uri().scheme_str() == Some("wss");
+    let host = request.uri().host().unwrap_or_default().to_string();
+    let port = request
+        .uri()
+        .port_u16()
+        .unwrap_or(if secure { 443 } else { 80 });
+    let stream = proxy::connect(&host, port, proxy::resolve(&host, secure, proxy))?;
+    let connector = if secure {
+        Connector::Rustls(tls::config(insecure)?)
+    } else {
+        Connector::Plain
+    };
+    let (socket, _) = tungstenite::client_tls_with_config(request, stream, None, Some(connector))
+        .map_err(|e| e.to_string())?;
+
+    let mut sockets = sockets();
+    sockets.0 += 1;
+    let id = sockets.0;
+    sockets.1.insert(id, Arc::new(Mutex::new(socket)));
+    Ok(Type::Object(
+        "websocket".to_string(),
+        HashMap::from([
+            ("id".to_string(), Type::Number(id as f64)),
+            ("url".to_string(), Type::String(url.into())),
+        ]),
+    ))
+}
+
+/// Send string as text message or binary as binary message
+pub fn send(handle: &Type, data: Type) -> Result<(), String> {
+    let message = match data {
+        Type::Binary(data) => Message::binary(data.into_inner()),
+        other => Message::text(other.get_string()),
+    };
+    let socket = socket(handle)?;
+    let mut socket = socket.lock().unwrap_or_else(|e| e.into_inner());
+    socket.send(message).map_err(|e| e.to_string())
+}
+
+/// Wait the next data message (None when the socket is closed)
+pub fn receive(handle: &Type) -> Result<Option<Type>, String> {
+    let socket = socket(handle)?;
+    let mut socket = socket.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => return Ok(Some(Type::String(text.to_string().into()))),
+            Ok(Message::Binary(data)) => return Ok(Some(Type::Binary(data.to_vec().into()))),
+            Ok(Message::Close(_)) => return Ok(None),
+            // Ping is answered automatically
+            Ok(_) => continue,
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(None),
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+/// Close the socket, and return whether it was opened
+pub fn close(handle: &Type) -> bool {
+    let Ok(id) = id(handle) else {
+        return false;
+    };
+    let Some(socket) = sockets().1.remove(&id) else {
+        return false;
+    };
+    let mut socket = socket.lock().unwrap_or_else(|e| e.into_inner());
+    socket.close(None).ok();
+    // Flush the close frame
+    socket.flush().ok();
+    true
+}
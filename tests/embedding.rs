@@ -0,0 +1,81 @@
+use serde_json::json;
+use stack_server::{register_command, EvalError, Executor, Mode, Type};
+
+#[test]
+fn eval_takes_the_values_pushed() {
+    let mut executor = Executor::new(Mode::Quiet);
+    assert_eq!(
+        executor.eval("1 2 add (a) [1 2]"),
+        Ok(vec![json!(3), json!("a"), json!([1, 2])])
+    );
+    // Values pushed before are kept on the stack
+    assert_eq!(executor.eval(""), Ok(vec![]));
+}
+
+#[test]
+fn variables_are_shared_with_the_host() {
+    let mut executor = Executor::new(Mode::Quiet);
+    executor.set_var("user", &json!({"name": "alice"})).unwrap();
+    executor.eval("user (name) property (name) var").unwrap();
+    assert_eq!(executor.get_var::<String>("name").as_deref(), Some("alice"));
+    assert_eq!(executor.get_var::<String>("missing"), None);
+}
+
+#[test]
+fn error_value_fails_the_evaluation() {
+    let mut executor = Executor::new(Mode::Quiet);
+    match executor.eval("(broken: reason) (error) cast") {
+        Err(EvalError::Error(err)) => assert_eq!(err.name, "broken"),
+        result => panic!("unexpected {result:?}"),
+    }
+}
+
+#[test]
+fn exit_is_left_to_the_host() {
+    let mut executor = Executor::new(Mode::Quiet);
+    assert_eq!(executor.eval("3 exit (after)"), Err(EvalError::Exit(3)));
+    // The executor can be used again after the exit request
+    assert_eq!(executor.eval("(again)"), Ok(vec![json!("again")]));
+}
+
+#[test]
+fn registered_commands_cannot_shadow_others() {
+    let double = |executor: &mut Executor| {
+        let value = executor.pop_stack().get_number();
+        executor.push_stack(Type::Number(value * 2.0));
+        Ok(())
+    };
+    assert!(register_command("embedding-double", double).is_ok());
+    assert!(register_command("embedding-double", double).is_err());
+    // Built-in commands are taken as well
+    assert!(register_command("repeat", double).is_err());
+
+    let mut executor = Executor::new(Mode::Quiet);
+    assert_eq!(
+        executor.eval("(ab) 2 repeat 4 embedding-double"),
+        Ok(vec![json!("abab"), json!(8)])
+    );
+}
+
+#[test]
+fn huge_delays_do_not_stop_the_scheduler() {
+    let mut executor = Executor::new(Mode::Quiet);
+    executor
+        .eval("1e30 () after pop 1e30 () every pop 0.05 (true (scheduled) global-set) after pop")
+        .unwrap();
+    let mut ran = false;
+    for _ in 0..100 {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        if let Ok(values) = executor.eval("(scheduled) global-get") {
+            ran = values == vec![json!(true)];
+            break;
+        }
+    }
+    assert!(ran, "the later task didn't run");
+
+    // Delays that never come are refused when they are registered
+    for delay in ["-1", "0 0 div", "1 0 div"] {
+        assert!(executor.eval(&format!("{delay} () after")).is_err());
+        assert!(executor.eval(&format!("{delay} () every")).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use stack_server::{Executor, Mode};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Format the program by the fmt subcommand
+fn format(code: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_stack-server"))
+        .arg("fmt")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(code.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn formatting_keeps_the_string_values() {
+    let code = "(two   spaced)    (one)   concat\n\
+                [1    2 [ (a  b)   ]]\n\
+                (multi\n   line    text\n\t\tindented\n)   (x) var   x\n\
+                (code (\n  nested   block\n) end) # comment   kept #\n";
+    let formatted = format(code);
+    assert_ne!(formatted, code);
+    let values = Executor::new(Mode::Quiet).eval(code).unwrap();
+    assert_eq!(values[0], "two   spacedone");
+    assert_eq!(Executor::new(Mode::Quiet).eval(&formatted), Ok(values));
+    // Formatting is stable
+    assert_eq!(format(&formatted), formatted);
+}